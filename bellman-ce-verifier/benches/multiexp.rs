@@ -0,0 +1,54 @@
+use bellman_ce_verifier::{multiexp, FixedBaseTable};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pairing_ce::bn256::{Fr, G1, G1Affine};
+use pairing_ce::ff::PrimeField;
+use pairing_ce::{CurveAffine, CurveProjective};
+use rand::{thread_rng, Rand};
+
+fn bench_ic_accumulation(c: &mut Criterion) {
+    let mut rng = thread_rng();
+    let mut group = c.benchmark_group("ic_accumulation");
+
+    for n in [1usize, 8, 64, 256, 1024] {
+        let bases = (0..n)
+            .map(|_| G1::rand(&mut rng).into_affine())
+            .collect::<Vec<G1Affine>>();
+        let scalars = (0..n)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        // The loop `verify_proof` used before switching to `multiexp`.
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |b, _| {
+            b.iter(|| {
+                let mut acc = G1::zero();
+                for (base, scalar) in bases.iter().zip(scalars.iter()) {
+                    acc.add_assign(&base.mul(scalar.into_repr()));
+                }
+                acc
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("multiexp", n), &n, |b, _| {
+            b.iter(|| multiexp(&bases, &scalars))
+        });
+
+        let tables = bases
+            .iter()
+            .map(|base| FixedBaseTable::new(base, 4))
+            .collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::new("fixed_base_w4", n), &n, |b, _| {
+            b.iter(|| {
+                let mut acc = G1::zero();
+                for (table, scalar) in tables.iter().zip(scalars.iter()) {
+                    acc.add_assign(&table.mul(scalar));
+                }
+                acc
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ic_accumulation);
+criterion_main!(benches);
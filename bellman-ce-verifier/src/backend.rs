@@ -0,0 +1,341 @@
+use core::marker::PhantomData;
+
+use pairing_ce::{
+    Engine,
+    CurveAffine,
+    EncodedPoint
+};
+use pairing_ce::ff::PrimeField;
+#[cfg(feature = "runtime-benchmarks")]
+use pairing_ce::{
+    CurveProjective,
+    ff::Field
+};
+#[cfg(feature = "runtime-benchmarks")]
+use verifier_traits::{
+    BenchmarkData,
+    BenchmarkSetup
+};
+use verifier_traits::{
+    split_proof,
+    split_verifying_key,
+    Groth16Verifier,
+    VerifierError
+};
+
+use super::{
+    prepare_verifier_key,
+    prepare_verifying_key_deferred,
+    verify_proof,
+    PreparationConfig,
+    Proof,
+    VerifierKey,
+    PreparedVerifyingKey
+};
+
+use sp_std::prelude::*;
+
+/// `Groth16Verifier` for the engines of pairing_ce, e.g.
+/// `BellmanCeVerifier<pairing_ce::bn256::Bn256>`.
+///
+/// Stored prepared keys use the layout of `decode_verifying_key`. They are
+/// decoded without curve and subgroup checks and prepared with
+/// `prepare_verifying_key_deferred`, so reading one back costs no pairing.
+pub struct BellmanCeVerifier<E>(PhantomData<E>);
+
+fn decode_point<G: CurveAffine>(bytes: &[u8]) -> Result<G, VerifierError> {
+    let mut repr = G::Uncompressed::empty();
+    if repr.as_ref().len() != bytes.len() {
+        return Err(VerifierError::InvalidEncoding);
+    }
+    repr.as_mut().copy_from_slice(bytes);
+
+    repr.into_affine().map_err(|_| VerifierError::InvalidEncoding)
+}
+
+fn decode_point_unchecked<G: CurveAffine>(bytes: &[u8]) -> Result<G, VerifierError> {
+    let mut repr = G::Uncompressed::empty();
+    repr.as_mut().copy_from_slice(bytes);
+
+    repr.into_affine_unchecked().map_err(|_| VerifierError::InvalidEncoding)
+}
+
+fn encode_point<G: CurveAffine>(point: &G, out: &mut Vec<u8>) {
+    out.extend_from_slice(point.into_uncompressed().as_ref());
+}
+
+/// Decodes the points of a key with `decode_g1` and `decode_g2`, which may
+/// or may not check them.
+fn decode_key<E: Engine>(
+    bytes: &[u8],
+    decode_g1: fn(&[u8]) -> Result<E::G1Affine, VerifierError>,
+    decode_g2: fn(&[u8]) -> Result<E::G2Affine, VerifierError>
+) -> Result<VerifierKey<E>, VerifierError>
+{
+    let vk = split_verifying_key(
+        bytes,
+        <E::G1Affine as CurveAffine>::Uncompressed::size(),
+        <E::G2Affine as CurveAffine>::Uncompressed::size()
+    )?;
+
+    Ok(VerifierKey {
+        alpha_g1: decode_g1(vk.alpha_g1)?,
+        beta_g2: decode_g2(vk.beta_g2)?,
+        gamma_g2: decode_g2(vk.gamma_g2)?,
+        delta_g2: decode_g2(vk.delta_g2)?,
+        ic: vk.ic
+            .into_iter()
+            .map(decode_g1)
+            .collect::<Result<_, _>>()?
+    })
+}
+
+impl<E: Engine> Groth16Verifier for BellmanCeVerifier<E> {
+    type Fr = E::Fr;
+    type Proof = Proof<E>;
+    type VerifyingKey = VerifierKey<E>;
+    type PreparedVerifyingKey = PreparedVerifyingKey<E>;
+
+    fn prepare_verifying_key(vk: &VerifierKey<E>) -> PreparedVerifyingKey<E> {
+        prepare_verifier_key(vk)
+    }
+
+    fn verify_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::Fr]
+    ) -> Result<(), VerifierError>
+    {
+        if verify_proof(pvk, proof, public_inputs)? {
+            Ok(())
+        } else {
+            Err(VerifierError::InvalidProof)
+        }
+    }
+
+    fn decode_proof(bytes: &[u8]) -> Result<Proof<E>, VerifierError> {
+        let proof = split_proof(
+            bytes,
+            <E::G1Affine as CurveAffine>::Uncompressed::size(),
+            <E::G2Affine as CurveAffine>::Uncompressed::size()
+        )?;
+
+        Ok(Proof {
+            a: decode_point(proof.a)?,
+            b: decode_point(proof.b)?,
+            c: decode_point(proof.c)?
+        })
+    }
+
+    fn decode_verifying_key(bytes: &[u8]) -> Result<VerifierKey<E>, VerifierError> {
+        decode_key(bytes, decode_point, decode_point)
+    }
+
+    fn public_input_count(vk: &VerifierKey<E>) -> usize {
+        vk.ic.len().saturating_sub(1)
+    }
+
+    fn encode_prepared_verifying_key(vk: &VerifierKey<E>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode_point(&vk.alpha_g1, &mut bytes);
+        encode_point(&vk.beta_g2, &mut bytes);
+        encode_point(&vk.gamma_g2, &mut bytes);
+        encode_point(&vk.delta_g2, &mut bytes);
+        for ic in &vk.ic {
+            encode_point(ic, &mut bytes);
+        }
+        bytes
+    }
+
+    fn decode_prepared_verifying_key(
+        bytes: &[u8]
+    ) -> Result<PreparedVerifyingKey<E>, VerifierError>
+    {
+        let vk = decode_key(bytes, decode_point_unchecked, decode_point_unchecked)?;
+        Ok(prepare_verifying_key_deferred(&vk, &PreparationConfig::default()))
+    }
+
+    fn decode_public_input(bytes: &[u8]) -> Result<E::Fr, VerifierError> {
+        let mut repr = <E::Fr as PrimeField>::Repr::default();
+        let limbs = repr.as_mut();
+        if limbs.len() * 8 != bytes.len() {
+            return Err(VerifierError::InvalidEncoding);
+        }
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(chunk);
+            *limb = u64::from_be_bytes(buf);
+        }
+
+        E::Fr::from_repr(repr).map_err(|_| VerifierError::InvalidEncoding)
+    }
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+fn small_scalar<F: PrimeField>(v: u64) -> F {
+    let mut repr = F::Repr::default();
+    repr.as_mut()[0] = v;
+    F::from_repr(repr).expect("small values are below the modulus")
+}
+
+/// Draws a full width scalar from a splitmix64 stream.
+#[cfg(feature = "runtime-benchmarks")]
+fn benchmark_scalar<F: PrimeField>(state: &mut u64) -> F {
+    let mut shift = small_scalar::<F>(1 << 32);
+    shift.square();
+
+    let mut acc = F::zero();
+    for _ in 0..4 {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        acc.mul_assign(&shift);
+        acc.add_assign(&small_scalar(z ^ (z >> 31)));
+    }
+
+    acc
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+fn encode_multiple<G: CurveAffine>(generator: G, scalar: G::Scalar, out: &mut Vec<u8>) {
+    encode_point(&generator.mul(scalar.into_repr()).into_affine(), out);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<E: Engine> BenchmarkSetup for BellmanCeVerifier<E> {
+    fn benchmark_setup(num_inputs: usize, seed: u64) -> BenchmarkData {
+        let mut state = seed;
+        let mut scalar = || benchmark_scalar::<E::Fr>(&mut state);
+        let (alpha, beta, gamma, delta) = (scalar(), scalar(), scalar(), scalar());
+        let ic: Vec<E::Fr> = (0..=num_inputs).map(|_| scalar()).collect();
+        let (a, b) = (scalar(), scalar());
+
+        let inputs: Vec<E::Fr> = (0..num_inputs).map(|i| {
+            let mut x = small_scalar::<E::Fr>(i as u64 + 1);
+            x.negate();
+            x
+        }).collect();
+
+        // c = (a * b - alpha * beta - acc * gamma) / delta
+        let mut acc = ic[0];
+        for (x, k) in inputs.iter().zip(&ic[1..]) {
+            let mut t = *x;
+            t.mul_assign(k);
+            acc.add_assign(&t);
+        }
+        acc.mul_assign(&gamma);
+        let mut c = a;
+        c.mul_assign(&b);
+        let mut t = alpha;
+        t.mul_assign(&beta);
+        c.sub_assign(&t);
+        c.sub_assign(&acc);
+        c.mul_assign(&delta.inverse().unwrap());
+
+        let g1 = E::G1Affine::one();
+        let g2 = E::G2Affine::one();
+
+        let mut key = Vec::new();
+        encode_multiple(g1, alpha, &mut key);
+        encode_multiple(g2, beta, &mut key);
+        encode_multiple(g2, gamma, &mut key);
+        encode_multiple(g2, delta, &mut key);
+        for k in &ic {
+            encode_multiple(g1, *k, &mut key);
+        }
+
+        let mut proof = Vec::new();
+        encode_multiple(g1, a, &mut proof);
+        encode_multiple(g2, b, &mut proof);
+        encode_multiple(g1, c, &mut proof);
+
+        let mut public_inputs = Vec::new();
+        for x in &inputs {
+            for limb in x.into_repr().as_ref().iter().rev() {
+                public_inputs.extend_from_slice(&limb.to_be_bytes());
+            }
+        }
+
+        BenchmarkData {
+            key,
+            proof,
+            public_inputs
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::Bn256;
+    use rand::{SeedableRng, XorShiftRng};
+    use verifier_traits::{Groth16Verifier, VerifierError};
+
+    use super::BellmanCeVerifier;
+    use crate::verify_proofs_batch;
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    fn fixture() -> (&'static [u8], &'static [u8], &'static [u8]) {
+        (
+            include_bytes!("../../fixtures/bn128/vkey.bin"),
+            include_bytes!("../../fixtures/bn128/proof.bin"),
+            include_bytes!("../../fixtures/bn128/public.bin")
+        )
+    }
+
+    #[test]
+    fn stored_keys_verify_like_prepared_ones() {
+        let (key, proof, public_inputs) = fixture();
+        let vk = Backend::decode_verifying_key(key).unwrap();
+        assert_eq!(Backend::public_input_count(&vk), 1);
+
+        let stored = Backend::encode_prepared_verifying_key(&vk);
+        assert_eq!(stored, key);
+        let deferred = Backend::decode_prepared_verifying_key(&stored).unwrap();
+        let prepared = Backend::prepare_verifying_key(&vk);
+
+        for pvk in [&prepared, &deferred] {
+            assert_eq!(Backend::verify_encoded(pvk, proof, public_inputs), Ok(()));
+            let mut wrong = public_inputs.to_vec();
+            wrong[31] ^= 1;
+            assert_eq!(
+                Backend::verify_encoded(pvk, proof, &wrong),
+                Err(VerifierError::InvalidProof)
+            );
+        }
+
+        assert_eq!(
+            Backend::decode_prepared_verifying_key(&stored[1..]).err(),
+            Some(VerifierError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn batches_mix_stored_and_prepared_keys() {
+        let (key, proof, public_inputs) = fixture();
+        let vk = Backend::decode_verifying_key(key).unwrap();
+        let prepared = Backend::prepare_verifying_key(&vk);
+        let deferred = Backend::decode_prepared_verifying_key(
+            &Backend::encode_prepared_verifying_key(&vk)
+        ).unwrap();
+        let proof = Backend::decode_proof(proof).unwrap();
+        let inputs = Backend::decode_public_inputs(public_inputs).unwrap();
+        let mut rng = XorShiftRng::from_seed([0x0ba7_c4ed, 1, 2, 3]);
+
+        let items = [
+            (&deferred, &proof, &inputs[..]),
+            (&prepared, &proof, &inputs[..])
+        ];
+        assert!(verify_proofs_batch(&items, &mut rng).unwrap());
+
+        let mut wrong = public_inputs.to_vec();
+        wrong[31] ^= 1;
+        let wrong = Backend::decode_public_inputs(&wrong).unwrap();
+        let items = [
+            (&prepared, &proof, &inputs[..]),
+            (&deferred, &proof, &wrong[..])
+        ];
+        assert!(!verify_proofs_batch(&items, &mut rng).unwrap());
+    }
+}
@@ -0,0 +1,150 @@
+use pairing_ce::{
+    Engine,
+    CurveProjective,
+    CurveAffine
+};
+
+use pairing_ce::ff::{Field, PrimeField, ScalarEngine};
+use rand::{Rand, Rng};
+
+use verifier_traits::VerifierError;
+use super::verifier::accumulate_inputs;
+
+use super::{
+    AlphaBeta,
+    Proof,
+    PreparedVerifyingKey,
+};
+
+use sp_std::prelude::*;
+
+/// A `(prepared key, proof, public inputs)` triple in a batch.
+pub type BatchItem<'a, E> = (
+    &'a PreparedVerifyingKey<E>,
+    &'a Proof<E>,
+    &'a [<E as ScalarEngine>::Fr]
+);
+
+/// Verifies a batch of proofs which may belong to different circuits, each
+/// given as a `(prepared key, proof, public inputs)` triple.
+///
+/// Every proof's equation is scaled by a random scalar drawn from `rng` and
+/// all of them are checked with a single Miller loop and one shared final
+/// exponentiation. `Ok(true)` means every proof in the batch is valid; on
+/// `Ok(false)` callers that need to know which proof was rejected should fall
+/// back to `verify_proof` for each item.
+pub fn verify_proofs_batch<E: Engine, R: Rng>(
+    items: &[BatchItem<'_, E>],
+    rng: &mut R
+) -> Result<bool, VerifierError>
+{
+    if items.is_empty() {
+        return Ok(true);
+    }
+
+    // For every proof i with random r_i we check
+    // r_i * A_i * B_i + r_i * inputs_i * (-gamma_i) + r_i * C_i * (-delta_i) = alpha_i * beta_i ^ r_i
+    // and multiply the equations together, so that the left hand side becomes
+    // a single Miller loop over 3 * n terms. Keys with a deferred alpha * beta
+    // add r_i * (-alpha_i) * beta_i to the left hand side instead.
+    let b_prepared = items.iter()
+        .map(|(_, proof, _)| proof.b.prepare())
+        .collect::<Vec<_>>();
+    let mut scaled = Vec::with_capacity(items.len() * 4);
+    let mut g2 = Vec::with_capacity(items.len() * 4);
+    let mut alpha_g1_beta_g2 = E::Fqk::one();
+
+    for ((pvk, proof, public_inputs), b) in items.iter().zip(&b_prepared) {
+        let mut acc = accumulate_inputs(pvk, public_inputs)?;
+        let r = E::Fr::rand(rng).into_repr();
+
+        acc.mul_assign(r);
+        scaled.push(proof.a.mul(r));
+        scaled.push(acc);
+        scaled.push(proof.c.mul(r));
+        g2.push(b);
+        g2.push(&pvk.neg_gamma_g2);
+        g2.push(&pvk.neg_delta_g2);
+        match &pvk.alpha_g1_beta_g2 {
+            AlphaBeta::Pairing(alpha_beta) => alpha_g1_beta_g2.mul_assign(&alpha_beta.pow(r)),
+            AlphaBeta::Deferred(neg_alpha, beta) => {
+                scaled.push(neg_alpha.mul(r));
+                g2.push(beta);
+            }
+        }
+    }
+
+    E::G1::batch_normalization(&mut scaled);
+    let scaled = scaled.iter().map(|p| p.into_affine().prepare()).collect::<Vec<_>>();
+
+    let terms = scaled.iter().zip(g2).collect::<Vec<_>>();
+
+    // See `verify_proof` for when the Miller loop is zero.
+    let result = E::final_exponentiation(&E::miller_loop(terms.iter()))
+        .ok_or(VerifierError::InvalidVerifyingKey)?;
+
+    Ok(result == alpha_g1_beta_g2)
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::{Bn256, Fr};
+    use rand::{SeedableRng, XorShiftRng};
+    use verifier_traits::Groth16Verifier;
+
+    use super::verify_proofs_batch;
+    use crate::{BellmanCeVerifier, PreparedVerifyingKey, Proof, VerifierError};
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    fn fixture() -> (PreparedVerifyingKey<Bn256>, Proof<Bn256>, Vec<Fr>) {
+        let vk = Backend::decode_verifying_key(
+            include_bytes!("../../fixtures/bn128/vkey.bin")
+        ).unwrap();
+        let proof = Backend::decode_proof(
+            include_bytes!("../../fixtures/bn128/proof.bin")
+        ).unwrap();
+        let inputs = Backend::decode_public_inputs(
+            include_bytes!("../../fixtures/bn128/public.bin")
+        ).unwrap();
+        (Backend::prepare_verifying_key(&vk), proof, inputs)
+    }
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([0x0ba7_c4ed, 1, 2, 3])
+    }
+
+    #[test]
+    fn accepts_valid_batches() {
+        let (pvk, proof, inputs) = fixture();
+
+        assert!(verify_proofs_batch::<Bn256, _>(&[], &mut rng()).unwrap());
+        let items = [(&pvk, &proof, &inputs[..]), (&pvk, &proof, &inputs[..])];
+        assert!(verify_proofs_batch(&items, &mut rng()).unwrap());
+    }
+
+    #[test]
+    fn rejects_a_batch_with_one_bad_proof() {
+        let (pvk, proof, inputs) = fixture();
+
+        let bad = Proof { c: proof.a, ..proof.clone() };
+        let items = [
+            (&pvk, &proof, &inputs[..]),
+            (&pvk, &bad, &inputs[..]),
+            (&pvk, &proof, &inputs[..])
+        ];
+        assert!(!verify_proofs_batch(&items, &mut rng()).unwrap());
+    }
+
+    #[test]
+    fn rejects_a_batch_with_a_wrong_input_count() {
+        let (pvk, proof, inputs) = fixture();
+
+        let too_many = [inputs[0], inputs[0]];
+        let items = [(&pvk, &proof, &inputs[..]), (&pvk, &proof, &too_many[..])];
+        assert!(matches!(
+            verify_proofs_batch(&items, &mut rng()),
+            Err(VerifierError::PublicInputCount { expected: 1, actual: 2 })
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+//! Borsh encoding of proofs and keys, for NEAR and Solana programs.
+//!
+//! Points are uncompressed, as the adapter writes them, and fields follow in
+//! declaration order:
+//!
+//! * `Proof`: `a || b || c`
+//! * `VerifyingKey`: `alpha_g1 || beta_g1 || beta_g2 || gamma_g2 || delta_g1 || delta_g2 || n || ic[0] || ... || ic[n - 1]`
+//! * `VerifierKey`: `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || n || ic[0] || ... || ic[n - 1]`
+//!
+//! `n` is a little-endian `u32`. Without it, this is the byte layout of
+//! `verifier_traits`, so a proof encodes to the bytes `Groth16Verifier`
+//! decodes, and so does a `VerifierKey` once `n` is dropped. This is also
+//! the layout of `bellman-verifier`'s `borsh` feature, so BN254 encodings
+//! are interchangeable between the two crates.
+
+use borsh::maybestd::io::{
+    Error,
+    ErrorKind,
+    Result,
+    Write
+};
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize
+};
+use pairing_ce::{
+    CurveAffine,
+    EncodedPoint,
+    Engine
+};
+use sp_std::prelude::*;
+
+use super::{
+    Proof,
+    VerifierKey,
+    VerifyingKey
+};
+
+fn write_point<G: CurveAffine, W: Write>(
+    point: &G,
+    writer: &mut W
+) -> Result<()>
+{
+    writer.write_all(point.into_uncompressed().as_ref())
+}
+
+fn read_point<G: CurveAffine>(buf: &mut &[u8]) -> Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    let len = repr.as_ref().len();
+    if buf.len() < len {
+        return Err(Error::new(ErrorKind::InvalidInput, "unexpected end of input"));
+    }
+    repr.as_mut().copy_from_slice(&buf[..len]);
+    *buf = &buf[len..];
+
+    repr.into_affine()
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid curve point"))
+}
+
+fn write_points<G: CurveAffine, W: Write>(
+    points: &[G],
+    writer: &mut W
+) -> Result<()>
+{
+    let len = u32::try_from(points.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "more than u32::MAX points"))?;
+    len.serialize(writer)?;
+    points.iter().try_for_each(|p| write_point(p, writer))
+}
+
+fn read_points<G: CurveAffine>(buf: &mut &[u8]) -> Result<Vec<G>> {
+    let len = u32::deserialize(buf)? as usize;
+    // `len` is untrusted, do not allocate more than the input can hold.
+    let size = G::Uncompressed::size();
+    let mut points = Vec::with_capacity(len.min(buf.len() / size));
+    for _ in 0..len {
+        points.push(read_point(buf)?);
+    }
+    Ok(points)
+}
+
+impl<E: Engine> BorshSerialize for Proof<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.a, writer)?;
+        write_point(&self.b, writer)?;
+        write_point(&self.c, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for Proof<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(Proof {
+            a: read_point(buf)?,
+            b: read_point(buf)?,
+            c: read_point(buf)?
+        })
+    }
+}
+
+impl<E: Engine> BorshSerialize for VerifyingKey<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.alpha_g1, writer)?;
+        write_point(&self.beta_g1, writer)?;
+        write_point(&self.beta_g2, writer)?;
+        write_point(&self.gamma_g2, writer)?;
+        write_point(&self.delta_g1, writer)?;
+        write_point(&self.delta_g2, writer)?;
+        write_points(&self.ic, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for VerifyingKey<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(VerifyingKey {
+            alpha_g1: read_point(buf)?,
+            beta_g1: read_point(buf)?,
+            beta_g2: read_point(buf)?,
+            gamma_g2: read_point(buf)?,
+            delta_g1: read_point(buf)?,
+            delta_g2: read_point(buf)?,
+            ic: read_points(buf)?
+        })
+    }
+}
+
+impl<E: Engine> BorshSerialize for VerifierKey<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.alpha_g1, writer)?;
+        write_point(&self.beta_g2, writer)?;
+        write_point(&self.gamma_g2, writer)?;
+        write_point(&self.delta_g2, writer)?;
+        write_points(&self.ic, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for VerifierKey<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(VerifierKey {
+            alpha_g1: read_point(buf)?,
+            beta_g2: read_point(buf)?,
+            gamma_g2: read_point(buf)?,
+            delta_g2: read_point(buf)?,
+            ic: read_points(buf)?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{
+        BorshDeserialize,
+        BorshSerialize
+    };
+    use pairing_ce::bn256::{
+        Bn256,
+        Fr,
+        G1Affine,
+        G2Affine
+    };
+    use pairing_ce::ff::PrimeField;
+    use pairing_ce::{
+        CurveAffine,
+        CurveProjective
+    };
+    use verifier_traits::Groth16Verifier;
+
+    use crate::{
+        BellmanCeVerifier,
+        Proof,
+        VerifierKey,
+        VerifyingKey
+    };
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    fn scalar(k: u64) -> Fr {
+        Fr::from_str(&k.to_string()).unwrap()
+    }
+
+    fn g1(k: u64) -> G1Affine {
+        G1Affine::one().mul(scalar(k).into_repr()).into_affine()
+    }
+
+    fn g2(k: u64) -> G2Affine {
+        G2Affine::one().mul(scalar(k).into_repr()).into_affine()
+    }
+
+    fn key() -> VerifyingKey<Bn256> {
+        VerifyingKey {
+            alpha_g1: g1(2),
+            beta_g1: g1(3),
+            beta_g2: g2(3),
+            gamma_g2: g2(5),
+            delta_g1: g1(7),
+            delta_g2: g2(7),
+            ic: vec![g1(11), g1(13), g1(17)]
+        }
+    }
+
+    #[test]
+    fn proofs_encode_as_in_verifier_traits() {
+        let encoded = include_bytes!("../../fixtures/bn128/proof.bin");
+        let proof = Backend::decode_proof(encoded).unwrap();
+
+        let bytes = proof.try_to_vec().unwrap();
+        assert_eq!(bytes, encoded);
+        assert!(Proof::try_from_slice(&bytes).unwrap() == proof);
+    }
+
+    #[test]
+    fn keys_encode_as_in_verifier_traits_but_for_the_ic_length() {
+        let encoded = include_bytes!("../../fixtures/bn128/vkey.bin");
+        let vk = Backend::decode_verifying_key(encoded).unwrap();
+
+        let bytes = vk.try_to_vec().unwrap();
+        let fixed = 64 + 3 * 128;
+        let n = vk.ic.len() as u32;
+        assert_eq!(bytes[..fixed], encoded[..fixed]);
+        assert_eq!(bytes[fixed..fixed + 4], n.to_le_bytes());
+        assert_eq!(bytes[fixed + 4..], encoded[fixed..]);
+        assert!(VerifierKey::try_from_slice(&bytes).unwrap() == vk);
+
+        let vk = key();
+        let bytes = vk.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 3 * 64 + 3 * 128 + 4 + 3 * 64);
+        assert!(VerifyingKey::try_from_slice(&bytes).unwrap() == vk);
+        let bytes = VerifierKey::from(vk).try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 64 + 3 * 128 + 4 + 3 * 64);
+    }
+
+    #[test]
+    fn rejects_invalid_encodings() {
+        let bytes = key().try_to_vec().unwrap();
+
+        assert!(VerifyingKey::<Bn256>::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+        assert!(VerifyingKey::<Bn256>::try_from_slice(&[&bytes[..], &[0]].concat()).is_err());
+
+        let mut off_curve = bytes.clone();
+        off_curve[63] ^= 1;
+        assert!(VerifyingKey::<Bn256>::try_from_slice(&off_curve).is_err());
+
+        // A huge `ic` length fails on the missing points.
+        let mut long = bytes[..3 * 64 + 3 * 128].to_vec();
+        long.extend(u32::MAX.to_le_bytes());
+        assert!(VerifyingKey::<Bn256>::try_from_slice(&long).is_err());
+    }
+
+    #[test]
+    fn proofs_round_trip_through_scale_and_borsh() {
+        use codec::{
+            Decode,
+            Encode
+        };
+
+        // pairing_ce points have no SCALE encoding, so a proof goes through
+        // it as `bellman-verifier`'s BN254 `Proof`, whose SCALE bytes are the
+        // Borsh bytes of both crates.
+        type ScaleProof = bellman_verifier::Proof<bellman_verifier::bn256::Bn256>;
+
+        let encoded = include_bytes!("../../fixtures/bn128/proof.bin");
+        let proof = Backend::decode_proof(encoded).unwrap();
+
+        let borsh = proof.try_to_vec().unwrap();
+        let scale = ScaleProof::decode(&mut &borsh[..]).unwrap().encode();
+        assert_eq!(scale, borsh);
+        assert!(Proof::try_from_slice(&scale).unwrap() == proof);
+
+        let mut off_curve = borsh;
+        off_curve[63] ^= 1;
+        assert!(ScaleProof::decode(&mut &off_curve[..]).is_err());
+        assert!(Proof::<Bn256>::try_from_slice(&off_curve).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use pairing_ce::{
+    CurveProjective,
+    CurveAffine
+};
+
+use pairing_ce::ff::PrimeField;
+
+use sp_std::prelude::*;
+
+/// Largest window accepted by `FixedBaseTable::new`. A table takes
+/// `ceil(254 / window) * (2^window - 1)` affine points, at this window
+/// `22 * 4095 = 90,090` of them, so about 6 MiB per base for BN254.
+pub const MAX_FIXED_BASE_WINDOW: u32 = 12;
+
+/// Precomputed multiples of a fixed point.
+///
+/// For every `window`-bit digit position `j` of a scalar the table holds
+/// `k * 2^(window * j) * base` for `k` in `1..2^window`, so a scalar
+/// multiplication becomes one table lookup and one mixed addition per window
+/// with no doublings at all.
+#[derive(Clone)]
+pub struct FixedBaseTable<G: CurveAffine> {
+    window: u32,
+    table: Vec<G>
+}
+
+impl<G: CurveAffine> FixedBaseTable<G> {
+    /// Builds the table for `base`. `window` is clamped to
+    /// `1..=MAX_FIXED_BASE_WINDOW`.
+    pub fn new(base: &G, window: u32) -> Self {
+        let window = window.clamp(1, MAX_FIXED_BASE_WINDOW);
+        let per_window = (1usize << window) - 1;
+        let num_windows = (G::Scalar::NUM_BITS as usize + window as usize - 1) / window as usize;
+
+        let mut table = Vec::with_capacity(num_windows * per_window);
+        let mut window_base = base.into_projective();
+        for _ in 0..num_windows {
+            let mut multiple = window_base;
+            for _ in 0..per_window {
+                table.push(multiple);
+                multiple.add_assign(&window_base);
+            }
+            // `multiple` is now 2^window * window_base, the base of the next window.
+            window_base = multiple;
+        }
+
+        G::Projective::batch_normalization(&mut table);
+
+        FixedBaseTable {
+            window,
+            table: table.into_iter().map(|p| p.into_affine()).collect()
+        }
+    }
+
+    /// Multiplies the base of this table by `scalar`.
+    pub fn mul(&self, scalar: &G::Scalar) -> G::Projective {
+        let window = self.window as usize;
+        let per_window = (1usize << window) - 1;
+        let num_bits = G::Scalar::NUM_BITS as usize;
+        let repr = scalar.into_repr();
+        let limbs = repr.as_ref();
+
+        let mut acc = G::Projective::zero();
+        for (j, chunk) in self.table.chunks(per_window).enumerate() {
+            let mut digit = 0usize;
+            for k in 0..window {
+                let bit = j * window + k;
+                if bit < num_bits && (limbs[bit / 64] >> (bit % 64)) & 1 == 1 {
+                    digit |= 1 << k;
+                }
+            }
+
+            if digit != 0 {
+                acc.add_assign_mixed(&chunk[digit - 1]);
+            }
+        }
+
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::{
+        Fr,
+        G1,
+        G1Affine
+    };
+    use pairing_ce::ff::Field;
+    use rand::{
+        Rand,
+        SeedableRng,
+        XorShiftRng
+    };
+
+    use super::*;
+
+    #[test]
+    fn mul_matches_scalar_multiplication() {
+        let mut rng = XorShiftRng::from_seed([0xf1edba5e, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let base = G1::rand(&mut rng).into_affine();
+        let mut minus_one = Fr::one();
+        minus_one.negate();
+        let scalars = [Fr::zero(), Fr::one(), minus_one]
+            .into_iter()
+            .chain((0..8).map(|_| Fr::rand(&mut rng)))
+            .collect::<Vec<_>>();
+
+        for window in [1, 3, 4, 8] {
+            let table = FixedBaseTable::new(&base, window);
+            for scalar in &scalars {
+                assert!(table.mul(scalar) == base.mul(scalar.into_repr()), "window {}", window);
+            }
+        }
+
+        let identity = FixedBaseTable::new(&G1Affine::zero(), 4);
+        assert!(identity.mul(&scalars[3]) == G1::zero());
+    }
+
+    #[test]
+    fn new_clamps_the_window() {
+        let base = G1Affine::one();
+        assert_eq!(FixedBaseTable::new(&base, 0).window, 1);
+        assert_eq!(FixedBaseTable::new(&base, 64).window, MAX_FIXED_BASE_WINDOW);
+
+        // `ceil(254 / 4)` windows of 15 multiples.
+        assert_eq!(FixedBaseTable::new(&base, 4).table.len(), 64 * 15);
+    }
+}
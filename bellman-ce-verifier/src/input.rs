@@ -0,0 +1,194 @@
+//! Conversion of 256-bit public inputs into scalars, checked against or
+//! reduced modulo the field modulus as chosen by `InputMode`.
+
+use pairing_ce::ff::PrimeField;
+use verifier_traits::{
+    u256_from_decimal,
+    u256_from_hex
+};
+
+pub use verifier_traits::{
+    InputError,
+    InputMode
+};
+
+/// Converts 32 big-endian bytes, e.g. a Solidity `uint256`.
+pub fn public_input_from_be_bytes<F: PrimeField>(
+    bytes: &[u8; 32],
+    mode: InputMode
+) -> Result<F, InputError>
+{
+    match mode {
+        InputMode::Strict => from_be_strict(bytes),
+        InputMode::Reduce => Ok(from_be_reduced(bytes))
+    }
+}
+
+/// Converts 32 little-endian bytes.
+pub fn public_input_from_le_bytes<F: PrimeField>(
+    bytes: &[u8; 32],
+    mode: InputMode
+) -> Result<F, InputError>
+{
+    let mut be = *bytes;
+    be.reverse();
+    public_input_from_be_bytes(&be, mode)
+}
+
+/// Converts a decimal string, such as an entry of snarkjs' `public.json`.
+pub fn public_input_from_decimal<F: PrimeField>(
+    s: &str,
+    mode: InputMode
+) -> Result<F, InputError>
+{
+    public_input_from_be_bytes(&u256_from_decimal(s)?, mode)
+}
+
+/// Converts a `0x` prefixed hex string.
+pub fn public_input_from_hex<F: PrimeField>(
+    s: &str,
+    mode: InputMode
+) -> Result<F, InputError>
+{
+    public_input_from_be_bytes(&u256_from_hex(s)?, mode)
+}
+
+fn from_be_strict<F: PrimeField>(bytes: &[u8; 32]) -> Result<F, InputError> {
+    let mut repr = F::Repr::default();
+    let limbs = repr.as_mut();
+    if limbs.len() * 8 < bytes.len()
+        && bytes[..bytes.len() - limbs.len() * 8].iter().any(|b| *b != 0)
+    {
+        return Err(InputError::OutOfRange);
+    }
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *limb = u64::from_be_bytes(buf);
+    }
+
+    F::from_repr(repr).map_err(|_| InputError::OutOfRange)
+}
+
+fn small<F: PrimeField>(v: u64) -> F {
+    let mut repr = F::Repr::default();
+    repr.as_mut()[0] = v;
+    F::from_repr(repr).expect("small values are below the modulus")
+}
+
+fn from_be_reduced<F: PrimeField>(bytes: &[u8; 32]) -> F {
+    let base = small::<F>(256);
+    let mut acc = F::zero();
+    for b in bytes.iter() {
+        acc.mul_assign(&base);
+        acc.add_assign(&small(*b as u64));
+    }
+
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::Fr;
+    use pairing_ce::ff::{
+        Field,
+        PrimeField
+    };
+
+    use super::*;
+
+    const R_HEX: &str = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+    const R_DECIMAL: &str =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    const R_MINUS_1_DECIMAL: &str =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+    const MAX_DECIMAL: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    /// `(2^256 - 1) mod r`.
+    const MAX_MOD_R_HEX: &str =
+        "0x0e0a77c19a07df2f666ea36f7879462e36fc76959f60cd29ac96341c4ffffffa";
+
+    fn parse(s: &str, mode: InputMode) -> Result<Fr, InputError> {
+        if s.starts_with("0x") {
+            public_input_from_hex(s, mode)
+        } else {
+            public_input_from_decimal(s, mode)
+        }
+    }
+
+    fn minus_one() -> Fr {
+        let mut v = Fr::one();
+        v.negate();
+        v
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn converts_r_minus_1_in_both_modes() {
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            assert_eq!(parse(R_MINUS_1_DECIMAL, mode), Ok(minus_one()));
+        }
+    }
+
+    #[test]
+    fn rejects_or_reduces_r() {
+        for s in [R_HEX, R_DECIMAL] {
+            assert_eq!(parse(s, InputMode::Strict), Err(InputError::OutOfRange));
+            assert_eq!(parse(s, InputMode::Reduce), Ok(Fr::zero()));
+        }
+    }
+
+    #[test]
+    fn rejects_or_reduces_2_256_minus_1() {
+        let reduced = parse(MAX_MOD_R_HEX, InputMode::Strict).unwrap();
+
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            let expected = match mode {
+                InputMode::Strict => Err(InputError::OutOfRange),
+                InputMode::Reduce => Ok(reduced)
+            };
+            assert_eq!(public_input_from_be_bytes(&[0xff; 32], mode), expected);
+            assert_eq!(public_input_from_le_bytes(&[0xff; 32], mode), expected);
+        }
+        assert_eq!(parse(MAX_DECIMAL, InputMode::Reduce), Ok(reduced));
+        assert_eq!(
+            parse(&format!("0x{}", "f".repeat(64)), InputMode::Reduce),
+            Ok(reduced)
+        );
+    }
+
+    #[test]
+    fn hex_decimal_and_bytes_agree() {
+        let be = u256_from_decimal(R_MINUS_1_DECIMAL).unwrap();
+        let mut le = be;
+        le.reverse();
+
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            let expected = parse(R_MINUS_1_DECIMAL, mode);
+            assert_eq!(parse(&format!("0x{}", hex(&be)), mode), expected);
+            assert_eq!(public_input_from_be_bytes(&be, mode), expected);
+            assert_eq!(public_input_from_le_bytes(&le, mode), expected);
+        }
+
+        let forty_two = Fr::from_str("42").unwrap();
+        assert_eq!(parse("0x2a", InputMode::Strict), Ok(forty_two));
+        assert_eq!(parse("42", InputMode::Strict), Ok(forty_two));
+    }
+
+    #[test]
+    fn passes_on_format_errors() {
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            assert_eq!(
+                public_input_from_decimal::<Fr>("0x2a", mode),
+                Err(InputError::InvalidFormat)
+            );
+            assert_eq!(
+                public_input_from_hex::<Fr>(&format!("0x1{}", "0".repeat(64)), mode),
+                Err(InputError::Overflow)
+            );
+        }
+    }
+}
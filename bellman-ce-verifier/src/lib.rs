@@ -1,9 +1,61 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Groth16 verification on `pairing_ce` engines.
+//!
+//! `pairing_ce` needs `std`, so everything but `Bn254Verifier` and the
+//! `verifier_traits` re-exports is only built with the `std` feature.
+//! Without it, `Bn254Verifier` verifies on `bellman-verifier`'s `bn256`
+//! engine, which takes the same bytes, so runtimes can verify BN254 proofs
+//! through this crate in `no_std`.
+
+#[cfg(feature = "std")]
 use pairing_ce::{CurveAffine, RawEncodable, Engine, GenericCurveProjective, EncodedPoint, GroupDecodingError};
+#[cfg(feature = "std")]
 use codec::{ Encode, Decode };
+#[cfg(feature = "std")]
+use sp_std::prelude::*;
 
+#[cfg(feature = "std")]
+mod backend;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(all(feature = "std", feature = "borsh"))]
+mod borsh_codec;
+#[cfg(feature = "std")]
+mod fixed_base;
+#[cfg(feature = "std")]
+mod input;
+#[cfg(feature = "std")]
+mod multiexp;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
 mod verifier;
+#[cfg(feature = "std")]
+pub use self::backend::*;
+#[cfg(feature = "std")]
+pub use self::batch::*;
+#[cfg(feature = "std")]
+pub use self::fixed_base::*;
+#[cfg(feature = "std")]
+pub use self::input::*;
+#[cfg(feature = "std")]
+pub use self::multiexp::*;
+#[cfg(feature = "std")]
+pub use self::report::*;
+#[cfg(feature = "std")]
 pub use self::verifier::*;
+pub use verifier_traits::{Groth16Verifier, VerifierError};
 
+/// `Groth16Verifier` for BN254 keys and proofs in the byte layout of
+/// `verifier_traits`, on `pairing_ce` with `std` and on
+/// `bellman_verifier::bn256` without it.
+#[cfg(feature = "std")]
+pub type Bn254Verifier = BellmanCeVerifier<pairing_ce::bn256::Bn256>;
+#[cfg(not(feature = "std"))]
+pub type Bn254Verifier = bellman_verifier::BellmanVerifier<bellman_verifier::bn256::Bn256>;
+
+#[cfg(feature = "std")]
 #[derive(Clone, Encode, Decode, Default, Eq)]
 pub struct Proof<E: Engine> {
     pub a: E::G1Affine,
@@ -11,6 +63,7 @@ pub struct Proof<E: Engine> {
     pub c: E::G1Affine
 }
 
+#[cfg(feature = "std")]
 impl<E: Engine> PartialEq for Proof<E> {
     fn eq(&self, other: &Self) -> bool {
         self.a == other.a &&
@@ -20,6 +73,7 @@ impl<E: Engine> PartialEq for Proof<E> {
 }
 
 
+#[cfg(feature = "std")]
 #[derive(Clone)]
 pub struct VerifyingKey<E: Engine> {
     // alpha in g1 for verifying and for creating A/C elements of
@@ -49,6 +103,7 @@ pub struct VerifyingKey<E: Engine> {
 }
 
 
+#[cfg(feature = "std")]
 impl<E: Engine> PartialEq for VerifyingKey<E> {
     fn eq(&self, other: &Self) -> bool {
         self.alpha_g1 == other.alpha_g1 &&
@@ -61,20 +116,108 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
     }
 }
 
+/// The part of a `VerifyingKey` that verification needs, without the
+/// prover-only `beta_g1` and `delta_g1`. snarkjs verification keys carry
+/// exactly these points.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct VerifierKey<E: Engine> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g2: E::G2Affine,
+    pub ic: Vec<E::G1Affine>
+}
+
+#[cfg(feature = "std")]
+impl<E: Engine> PartialEq for VerifierKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.alpha_g1 == other.alpha_g1 &&
+        self.beta_g2 == other.beta_g2 &&
+        self.gamma_g2 == other.gamma_g2 &&
+        self.delta_g2 == other.delta_g2 &&
+        self.ic == other.ic
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Engine> From<&VerifyingKey<E>> for VerifierKey<E> {
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        VerifierKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.ic.clone()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Engine> From<VerifyingKey<E>> for VerifierKey<E> {
+    fn from(vk: VerifyingKey<E>) -> Self {
+        VerifierKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.ic
+        }
+    }
+}
+
+/// `e(alpha_g1, beta_g2)` of a prepared key.
+#[cfg(feature = "std")]
+pub(crate) enum AlphaBeta<E: Engine> {
+    /// Computed once, when the key was prepared.
+    Pairing(E::Fqk),
+    /// `-alpha_g1` and `beta_g2`, paired in the Miller loop of every
+    /// verification instead, see `prepare_verifying_key_deferred`.
+    Deferred(E::G1Affine, <E::G2Affine as CurveAffine>::Prepared)
+}
+
+#[cfg(feature = "std")]
+impl<E: Engine> Clone for AlphaBeta<E> {
+    fn clone(&self) -> Self {
+        match self {
+            AlphaBeta::Pairing(alpha_beta) => AlphaBeta::Pairing(*alpha_beta),
+            AlphaBeta::Deferred(neg_alpha, beta) => AlphaBeta::Deferred(*neg_alpha, beta.clone())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 pub struct PreparedVerifyingKey<E: Engine> {
     /// Pairing result of alpha*beta
-    alpha_g1_beta_g2: E::Fqk,
+    alpha_g1_beta_g2: AlphaBeta<E>,
     /// -gamma in G2
     neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
     /// -delta in G2
     neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
     /// Copy of IC from `VerifiyingKey`.
-    ic: Vec<E::G1Affine>
+    ic: Vec<E::G1Affine>,
+    /// Fixed-base tables for `ic[1..]`, empty unless requested through
+    /// `PreparationConfig::fixed_base_window`.
+    ic_tables: Vec<FixedBaseTable<E::G1Affine>>
+}
+
+/// Options for `prepare_verifying_key_with_config`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PreparationConfig {
+    /// When set, `FixedBaseTable`s with this window are built for every
+    /// public input's IC point. They make each verification under the key
+    /// cheaper but take `ceil(254 / w) * (2^w - 1)` G1 points per input, e.g.
+    /// `64 * 15 = 960` points, about 70 KiB per input in memory for `w = 4`
+    /// on BN254, and 6 MiB at `MAX_FIXED_BASE_WINDOW`. Worth it only for
+    /// long-lived keys that verify many proofs.
+    pub fixed_base_window: Option<u32>
 }
 
 
 /// This is an error that could occur during circuit synthesis contexts,
 /// such as CRS generation, proving or verification.
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub enum SynthesisError {
     /// During synthesis, we lacked knowledge of a variable assignment.
@@ -95,3 +238,92 @@ pub enum SynthesisError {
     UnconstrainedVariable
 }
 
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use pairing_ce::bn256::{Bn256, G1Affine};
+    use verifier_traits::Groth16Verifier;
+
+    use super::{
+        prepare_verifying_key,
+        verify_proof,
+        BellmanCeVerifier,
+        Bn254Verifier,
+        CurveAffine,
+        VerifierError,
+        VerifierKey,
+        VerifyingKey
+    };
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    fn verifier_key() -> VerifierKey<Bn256> {
+        Backend::decode_verifying_key(
+            include_bytes!("../../fixtures/bn128/vkey.bin")
+        ).unwrap()
+    }
+
+    fn full_key(vk: &VerifierKey<Bn256>) -> VerifyingKey<Bn256> {
+        VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g1: G1Affine::one(),
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g1: G1Affine::one(),
+            delta_g2: vk.delta_g2,
+            ic: vk.ic.clone()
+        }
+    }
+
+    #[test]
+    fn verifier_keys_drop_the_prover_points() {
+        let vk = verifier_key();
+        let full = full_key(&vk);
+
+        assert!(VerifierKey::from(&full) == vk);
+        assert!(VerifierKey::from(full) == vk);
+
+        let mut other = vk.clone();
+        other.ic.pop();
+        assert!(other != vk);
+    }
+
+    #[test]
+    fn full_keys_verify_like_verifier_keys() {
+        let vk = verifier_key();
+        let proof = Backend::decode_proof(
+            include_bytes!("../../fixtures/bn128/proof.bin")
+        ).unwrap();
+        let inputs = Backend::decode_public_inputs(
+            include_bytes!("../../fixtures/bn128/public.bin")
+        ).unwrap();
+
+        let pvk = prepare_verifying_key(&full_key(&vk));
+        assert!(verify_proof(&pvk, &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn bn254_verifiers_take_the_same_bytes() {
+        type NoStd = bellman_verifier::BellmanVerifier<bellman_verifier::bn256::Bn256>;
+
+        let key = include_bytes!("../../fixtures/bn128/vkey.bin");
+        let proof = include_bytes!("../../fixtures/bn128/proof.bin");
+        let inputs = include_bytes!("../../fixtures/bn128/public.bin");
+        let mut wrong = inputs.to_vec();
+        wrong[31] ^= 1;
+
+        let pvk = Bn254Verifier::decode_prepared_verifying_key(key).unwrap();
+        assert_eq!(Bn254Verifier::verify_encoded(&pvk, proof, inputs), Ok(()));
+        assert_eq!(
+            Bn254Verifier::verify_encoded(&pvk, proof, &wrong),
+            Err(VerifierError::InvalidProof)
+        );
+
+        let pvk = NoStd::decode_prepared_verifying_key(key).unwrap();
+        assert_eq!(NoStd::verify_encoded(&pvk, proof, inputs), Ok(()));
+        assert_eq!(
+            NoStd::verify_encoded(&pvk, proof, &wrong),
+            Err(VerifierError::InvalidProof)
+        );
+    }
+}
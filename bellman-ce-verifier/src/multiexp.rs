@@ -0,0 +1,170 @@
+use pairing_ce::{
+    CurveProjective,
+    CurveAffine
+};
+
+use pairing_ce::ff::{PrimeField, PrimeFieldRepr};
+
+use sp_std::prelude::*;
+
+/// Computes `sum(bases[i] * scalars[i])` with Pippenger's bucket method.
+///
+/// Scalars are split into windows of `c` bits. For every window the bases are
+/// sorted into `2^c - 1` buckets by the value of their digit, the buckets are
+/// summed with a running sum, and the window results are combined with `c`
+/// doublings each. This costs roughly `254 / c * (n + 2^c)` additions instead
+/// of the `254` doublings and additions per base of a scalar multiplication.
+///
+/// Below `NAIVE_THRESHOLD` bases the bucket overhead outweighs the savings,
+/// so plain scalar multiplications are used instead.
+///
+/// `bases` and `scalars` are zipped, so any surplus elements of the longer
+/// slice are ignored.
+pub fn multiexp<G: CurveAffine>(
+    bases: &[G],
+    scalars: &[G::Scalar]
+) -> G::Projective
+{
+    let n = bases.len().min(scalars.len());
+    if n < NAIVE_THRESHOLD {
+        let mut acc = G::Projective::zero();
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            acc.add_assign(&base.mul(scalar.into_repr()));
+        }
+        return acc;
+    }
+
+    let c = window_size(n);
+    let num_bits = G::Scalar::NUM_BITS as usize;
+    let reprs = scalars[..n].iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+
+    let mut buckets = vec![G::Projective::zero(); (1 << c) - 1];
+    let mut acc = G::Projective::zero();
+
+    // Walk the windows from the most significant one down, so that the
+    // accumulator only needs `c` doublings between two windows.
+    let mut start = ((num_bits - 1) / c) * c;
+    loop {
+        for _ in 0..c {
+            acc.double();
+        }
+
+        for bucket in buckets.iter_mut() {
+            *bucket = G::Projective::zero();
+        }
+
+        for (base, repr) in bases.iter().zip(reprs.iter()) {
+            let digit = window_digit(repr, start, c, num_bits);
+
+            if digit != 0 {
+                buckets[digit - 1].add_assign_mixed(base);
+            }
+        }
+
+        // sum(i * bucket[i - 1]) as a running sum over the buckets, highest first.
+        let mut running_sum = G::Projective::zero();
+        for bucket in buckets.iter().rev() {
+            running_sum.add_assign(bucket);
+            acc.add_assign(&running_sum);
+        }
+
+        if start == 0 {
+            break;
+        }
+        start -= c;
+    }
+
+    acc
+}
+
+/// Reads the `c` bits of `repr` starting at bit `start`, least significant first.
+fn window_digit<R: PrimeFieldRepr>(repr: &R, start: usize, c: usize, num_bits: usize) -> usize {
+    let limbs = repr.as_ref();
+    let mut digit = 0usize;
+
+    for j in 0..c {
+        let bit = start + j;
+        if bit < num_bits && (limbs[bit / 64] >> (bit % 64)) & 1 == 1 {
+            digit |= 1 << j;
+        }
+    }
+
+    digit
+}
+
+const NAIVE_THRESHOLD: usize = 4;
+
+/// Picks the window size for `n` bases, roughly `log2(n)` as in bellman's
+/// multiexp, and a small constant window for short inputs.
+fn window_size(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        (usize::BITS - n.leading_zeros()) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::{
+        Fr,
+        G1,
+        G1Affine
+    };
+    use pairing_ce::ff::Field;
+    use rand::{
+        Rand,
+        SeedableRng,
+        XorShiftRng
+    };
+
+    use super::*;
+
+    fn naive(bases: &[G1Affine], scalars: &[Fr]) -> G1 {
+        let mut acc = G1::zero();
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            acc.add_assign(&base.mul(scalar.into_repr()));
+        }
+        acc
+    }
+
+    #[test]
+    fn matches_the_naive_loop() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        // Both sides of `NAIVE_THRESHOLD` and of the constant window size.
+        for n in [0, 1, NAIVE_THRESHOLD - 1, NAIVE_THRESHOLD, 5, 31, 32, 100] {
+            let bases = (0..n)
+                .map(|_| G1::rand(&mut rng).into_affine())
+                .collect::<Vec<_>>();
+            let mut scalars = (0..n)
+                .map(|_| Fr::rand(&mut rng))
+                .collect::<Vec<_>>();
+
+            // Digits of all zeros and all ones in every window.
+            if n > 2 {
+                let mut minus_one = Fr::one();
+                minus_one.negate();
+                scalars[0] = Fr::zero();
+                scalars[1] = Fr::one();
+                scalars[2] = minus_one;
+            }
+
+            assert!(multiexp(&bases, &scalars) == naive(&bases, &scalars), "{} bases", n);
+        }
+    }
+
+    #[test]
+    fn ignores_the_surplus_of_the_longer_slice() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let bases = (0..40)
+            .map(|_| G1::rand(&mut rng).into_affine())
+            .collect::<Vec<_>>();
+        let scalars = (0..33)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        assert!(multiexp(&bases, &scalars) == naive(&bases, &scalars));
+        assert!(multiexp(&bases[..3], &scalars) == naive(&bases[..3], &scalars));
+    }
+}
@@ -0,0 +1,194 @@
+//! A check-by-check account of a verification, for tracking down why a
+//! proof is rejected.
+
+use std::fmt;
+
+use pairing_ce::{
+    Engine,
+    CurveAffine
+};
+
+use verifier_traits::VerifierError;
+use super::{
+    verify_proof,
+    Proof,
+    PreparedVerifyingKey
+};
+
+/// The outcome of `verify_proof_with_report`.
+#[derive(Debug)]
+pub struct VerificationReport {
+    /// IC points of the key, one more than the public inputs it expects.
+    pub ic_points: usize,
+    /// Public inputs the key expects.
+    pub expected_inputs: usize,
+    /// Public inputs that were given.
+    pub actual_inputs: usize,
+    /// Proof points at infinity, by name. The pairing check does not reject
+    /// them, but an honest prover practically never produces one, so they
+    /// usually point at a decoding bug.
+    pub identity_points: Vec<&'static str>,
+    /// What `verify_proof` returned.
+    pub result: Result<bool, VerifierError>
+}
+
+impl VerificationReport {
+    /// The check that rejected the proof, `None` if it verified.
+    pub fn failed_check(&self) -> Option<&'static str> {
+        match self.result {
+            Ok(true) => None,
+            Ok(false) => Some("pairing check"),
+            Err(VerifierError::PublicInputCount { .. }) => Some("public input count"),
+            Err(VerifierError::InvalidEncoding) => Some("encoding"),
+            Err(_) => Some("verifying key")
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key_ok = !matches!(self.result, Err(VerifierError::InvalidVerifyingKey));
+        let count_ok = key_ok && self.expected_inputs == self.actual_inputs;
+
+        writeln!(
+            f,
+            "verifying key: {}",
+            match (key_ok, self.ic_points) {
+                (true, _) => "ok",
+                (false, 0) => "failed, no IC points",
+                (false, _) => "failed"
+            }
+        )?;
+        writeln!(
+            f,
+            "public inputs: expected {}, got {}: {}",
+            self.expected_inputs,
+            self.actual_inputs,
+            if count_ok { "ok" } else { "failed" }
+        )?;
+        if !self.identity_points.is_empty() {
+            writeln!(
+                f,
+                "proof points at infinity: {}",
+                self.identity_points.join(", ")
+            )?;
+        }
+        write!(
+            f,
+            "pairing check: {}",
+            match self.result {
+                Ok(true) => "ok",
+                Ok(false) => "failed",
+                Err(_) => "skipped"
+            }
+        )
+    }
+}
+
+/// Runs `verify_proof` and reports which of its checks passed.
+pub fn verify_proof_with_report<E: Engine>(
+    pvk: &PreparedVerifyingKey<E>,
+    proof: &Proof<E>,
+    public_inputs: &[E::Fr]
+) -> VerificationReport
+{
+    let mut identity_points = Vec::new();
+    if proof.a.is_zero() {
+        identity_points.push("a");
+    }
+    if proof.b.is_zero() {
+        identity_points.push("b");
+    }
+    if proof.c.is_zero() {
+        identity_points.push("c");
+    }
+
+    VerificationReport {
+        ic_points: pvk.ic.len(),
+        expected_inputs: pvk.ic.len().saturating_sub(1),
+        actual_inputs: public_inputs.len(),
+        identity_points,
+        result: verify_proof(pvk, proof, public_inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::{Bn256, Fr, G1Affine};
+    use pairing_ce::CurveAffine;
+    use verifier_traits::{Groth16Verifier, VerifierError};
+
+    use super::{verify_proof_with_report, VerificationReport};
+    use crate::{BellmanCeVerifier, PreparedVerifyingKey, Proof};
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    fn fixture() -> (PreparedVerifyingKey<Bn256>, Proof<Bn256>, Vec<Fr>) {
+        let vk = Backend::decode_verifying_key(
+            include_bytes!("../../fixtures/bn128/vkey.bin")
+        ).unwrap();
+        let proof = Backend::decode_proof(
+            include_bytes!("../../fixtures/bn128/proof.bin")
+        ).unwrap();
+        let inputs = Backend::decode_public_inputs(
+            include_bytes!("../../fixtures/bn128/public.bin")
+        ).unwrap();
+        (Backend::prepare_verifying_key(&vk), proof, inputs)
+    }
+
+    #[test]
+    fn reports_a_valid_proof() {
+        let (pvk, proof, inputs) = fixture();
+
+        let report = verify_proof_with_report(&pvk, &proof, &inputs);
+        assert_eq!(report.failed_check(), None);
+        assert_eq!(
+            report.to_string(),
+            "verifying key: ok\npublic inputs: expected 1, got 1: ok\npairing check: ok"
+        );
+    }
+
+    #[test]
+    fn reports_the_failed_check() {
+        let (pvk, proof, inputs) = fixture();
+
+        let report = verify_proof_with_report(&pvk, &proof, &[]);
+        assert_eq!(report.failed_check(), Some("public input count"));
+        assert_eq!(
+            report.to_string(),
+            "verifying key: ok\npublic inputs: expected 1, got 0: failed\npairing check: skipped"
+        );
+
+        let bad = Proof { c: G1Affine::zero(), ..proof.clone() };
+        let report = verify_proof_with_report(&pvk, &bad, &inputs);
+        assert_eq!(report.failed_check(), Some("pairing check"));
+        assert_eq!(report.identity_points, ["c"]);
+        assert_eq!(
+            report.to_string(),
+            "verifying key: ok\npublic inputs: expected 1, got 1: ok\n\
+             proof points at infinity: c\npairing check: failed"
+        );
+
+        let mut no_ic = pvk;
+        no_ic.ic.clear();
+        let report = verify_proof_with_report(&no_ic, &proof, &inputs);
+        assert_eq!(report.failed_check(), Some("verifying key"));
+        assert_eq!(
+            report.to_string(),
+            "verifying key: failed, no IC points\n\
+             public inputs: expected 0, got 1: failed\npairing check: skipped"
+        );
+    }
+
+    #[test]
+    fn names_ic_points_only_when_the_key_has_none() {
+        let report = VerificationReport {
+            ic_points: 2,
+            expected_inputs: 1,
+            actual_inputs: 1,
+            identity_points: Vec::new(),
+            result: Err(VerifierError::InvalidVerifyingKey)
+        };
+        assert!(report.to_string().starts_with("verifying key: failed\n"));
+    }
+}
@@ -5,30 +5,107 @@ use pairing_ce::{
     CurveAffine
 };
 
-use pairing_ce::ff::PrimeField;
+use pairing_ce::ff::Field;
 
-use super::SynthesisError;
+use verifier_traits::VerifierError;
+use super::multiexp;
 
 use super::{
+    AlphaBeta,
     Proof,
     VerifyingKey,
+    VerifierKey,
     PreparedVerifyingKey,
+    PreparationConfig,
+    FixedBaseTable,
 };
 
+use sp_std::prelude::*;
+
 pub fn prepare_verifying_key<E: Engine>(
     vk: &VerifyingKey<E>
 ) -> PreparedVerifyingKey<E>
+{
+    prepare_verifier_key(&VerifierKey::from(vk))
+}
+
+pub fn prepare_verifier_key<E: Engine>(
+    vk: &VerifierKey<E>
+) -> PreparedVerifyingKey<E>
+{
+    prepare_verifying_key_with_config(vk, &PreparationConfig::default())
+}
+
+/// Full `VerifyingKey`s are converted with `VerifierKey::from(&vk)`.
+pub fn prepare_verifying_key_with_config<E: Engine>(
+    vk: &VerifierKey<E>,
+    config: &PreparationConfig
+) -> PreparedVerifyingKey<E>
+{
+    let alpha_beta = AlphaBeta::Pairing(E::pairing(vk.alpha_g1, vk.beta_g2));
+    prepare_with_alpha_beta(vk, alpha_beta, config)
+}
+
+/// Prepares `vk` with a caller supplied `e(alpha_g1, beta_g2)`, such as the
+/// `vk_alphabeta_12` of a snarkjs verification key, which saves the pairing
+/// otherwise computed during preparation.
+///
+/// With `check` set the value is compared against the recomputed pairing and
+/// `VerifierError::InvalidVerifyingKey` is returned on mismatch. Without
+/// it a wrong value is not detected here, it only makes every proof under the
+/// key fail to verify.
+pub fn prepare_verifying_key_with_alpha_beta<E: Engine>(
+    vk: &VerifierKey<E>,
+    alpha_g1_beta_g2: E::Fqk,
+    config: &PreparationConfig,
+    check: bool
+) -> Result<PreparedVerifyingKey<E>, VerifierError>
+{
+    if check && alpha_g1_beta_g2 != E::pairing(vk.alpha_g1, vk.beta_g2) {
+        return Err(VerifierError::InvalidVerifyingKey);
+    }
+
+    Ok(prepare_with_alpha_beta(vk, AlphaBeta::Pairing(alpha_g1_beta_g2), config))
+}
+
+/// Prepares `vk` without computing `e(alpha_g1, beta_g2)`. Every
+/// verification under the key pairs `-alpha_g1` with `beta_g2` in its Miller
+/// loop instead, which costs less than the pairing as long as the key only
+/// verifies a few proofs, e.g. when it is read back from storage for each
+/// proof.
+pub fn prepare_verifying_key_deferred<E: Engine>(
+    vk: &VerifierKey<E>,
+    config: &PreparationConfig
+) -> PreparedVerifyingKey<E>
+{
+    let mut neg_alpha = vk.alpha_g1;
+    neg_alpha.negate();
+    let alpha_beta = AlphaBeta::Deferred(neg_alpha, vk.beta_g2.prepare());
+    prepare_with_alpha_beta(vk, alpha_beta, config)
+}
+
+fn prepare_with_alpha_beta<E: Engine>(
+    vk: &VerifierKey<E>,
+    alpha_g1_beta_g2: AlphaBeta<E>,
+    config: &PreparationConfig
+) -> PreparedVerifyingKey<E>
 {
     let mut gamma = vk.gamma_g2;
     gamma.negate();
     let mut delta = vk.delta_g2;
     delta.negate();
 
+    let ic_tables = match config.fixed_base_window {
+        Some(window) => vk.ic.iter().skip(1).map(|ic| FixedBaseTable::new(ic, window)).collect(),
+        None => Vec::new()
+    };
+
     PreparedVerifyingKey {
-        alpha_g1_beta_g2: E::pairing(vk.alpha_g1, vk.beta_g2),
+        alpha_g1_beta_g2,
         neg_gamma_g2: gamma.prepare(),
         neg_delta_g2: delta.prepare(),
-        ic: vk.ic.clone()
+        ic: vk.ic.clone(),
+        ic_tables
     }
 }
 
@@ -36,17 +113,9 @@ pub fn verify_proof<'a, E: Engine>(
     pvk: &'a PreparedVerifyingKey<E>,
     proof: &Proof<E>,
     public_inputs: &[E::Fr]
-) -> Result<bool, SynthesisError>
+) -> Result<bool, VerifierError>
 {
-    if (public_inputs.len() + 1) != pvk.ic.len() {
-        return Err(SynthesisError::MalformedVerifyingKey);
-    }
-
-    let mut acc = pvk.ic[0].into_projective();
-
-    for (i, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
-        acc.add_assign(&b.mul(i.into_repr()));
-    }
+    let acc = accumulate_inputs(pvk, public_inputs)?;
 
     // The original verification equation is:
     // A * B = alpha * beta + inputs * gamma + C * delta
@@ -56,11 +125,180 @@ pub fn verify_proof<'a, E: Engine>(
     // A * B + inputs * (-gamma) + C * (-delta) = alpha * beta
     // which allows us to do a single final exponentiation.
 
-    Ok(E::final_exponentiation(
-        &E::miller_loop([
-            (&proof.a.prepare(), &proof.b.prepare()),
-            (&acc.into_affine().prepare(), &pvk.neg_gamma_g2),
-            (&proof.c.prepare(), &pvk.neg_delta_g2)
-        ].iter())
-    ).unwrap() == pvk.alpha_g1_beta_g2)
-}
\ No newline at end of file
+    // A key with a deferred alpha * beta moves it to the left hand side, as
+    // (-alpha) * beta, and compares against one.
+    let a = proof.a.prepare();
+    let b = proof.b.prepare();
+    let acc = acc.into_affine().prepare();
+    let c = proof.c.prepare();
+    let mut terms = vec![
+        (&a, &b),
+        (&acc, &pvk.neg_gamma_g2),
+        (&c, &pvk.neg_delta_g2)
+    ];
+
+    let (neg_alpha, expected) = match &pvk.alpha_g1_beta_g2 {
+        AlphaBeta::Pairing(alpha_beta) => (None, *alpha_beta),
+        AlphaBeta::Deferred(neg_alpha, beta) => (Some((neg_alpha.prepare(), beta)), E::Fqk::one())
+    };
+    if let Some((neg_alpha, beta)) = &neg_alpha {
+        terms.push((neg_alpha, *beta));
+    }
+
+    // The Miller loop is only zero for points off the curve, which keys
+    // stored unchecked may hold.
+    let result = E::final_exponentiation(&E::miller_loop(terms.iter()))
+        .ok_or(VerifierError::InvalidVerifyingKey)?;
+
+    Ok(result == expected)
+}
+/// Computes `ic[0] + sum(ic[i + 1] * public_inputs[i])`, the point which is
+/// paired with `-gamma` during verification.
+pub(crate) fn accumulate_inputs<E: Engine>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[E::Fr]
+) -> Result<E::G1, VerifierError>
+{
+    if pvk.ic.is_empty() {
+        return Err(VerifierError::InvalidVerifyingKey);
+    }
+    if (public_inputs.len() + 1) != pvk.ic.len() {
+        return Err(VerifierError::public_input_count(
+            pvk.ic.len() - 1,
+            public_inputs.len()
+        ));
+    }
+
+    Ok(fold_leading_inputs(pvk, public_inputs))
+}
+
+/// Computes `ic[0] + sum(ic[i + 1] * inputs[i])` for the first
+/// `inputs.len()` public inputs. The caller checks that `inputs` is not
+/// longer than the key's public inputs.
+fn fold_leading_inputs<E: Engine>(
+    pvk: &PreparedVerifyingKey<E>,
+    inputs: &[E::Fr]
+) -> E::G1
+{
+    let mut acc = pvk.ic[0].into_projective();
+
+    if pvk.ic_tables.is_empty() {
+        acc.add_assign(&multiexp(&pvk.ic[1..], inputs));
+    } else {
+        for (table, input) in pvk.ic_tables.iter().zip(inputs.iter()) {
+            acc.add_assign(&table.mul(input));
+        }
+    }
+
+    acc
+}
+
+impl<E: Engine> PreparedVerifyingKey<E> {
+    /// Returns a key with `inputs` applied as the leading public inputs.
+    ///
+    /// The contribution of the fixed inputs is folded into `ic[0]`, so the
+    /// derived key verifies the same proofs as `self` but expects only the
+    /// remaining public inputs, e.g. everything after a Merkle root or round
+    /// ID that is constant for a deployment. Fixed-base tables of the
+    /// remaining inputs are kept.
+    ///
+    /// Fixing more inputs than the key has returns
+    /// `VerifierError::PublicInputCount` with the key's input count as
+    /// `expected`.
+    pub fn with_fixed_inputs(
+        &self,
+        inputs: &[E::Fr]
+    ) -> Result<Self, VerifierError>
+    {
+        if self.ic.is_empty() {
+            return Err(VerifierError::InvalidVerifyingKey);
+        }
+        if inputs.len() >= self.ic.len() {
+            return Err(VerifierError::public_input_count(
+                self.ic.len() - 1,
+                inputs.len()
+            ));
+        }
+
+        let mut ic = Vec::with_capacity(self.ic.len() - inputs.len());
+        ic.push(fold_leading_inputs(self, inputs).into_affine());
+        ic.extend_from_slice(&self.ic[inputs.len() + 1..]);
+
+        Ok(PreparedVerifyingKey {
+            alpha_g1_beta_g2: self.alpha_g1_beta_g2.clone(),
+            neg_gamma_g2: self.neg_gamma_g2.clone(),
+            neg_delta_g2: self.neg_delta_g2.clone(),
+            ic,
+            ic_tables: self.ic_tables.iter().skip(inputs.len()).cloned().collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing_ce::bn256::{Bn256, Fr};
+    use pairing_ce::ff::Field;
+    use verifier_traits::Groth16Verifier;
+
+    use super::{prepare_verifying_key_deferred, prepare_verifying_key_with_config, verify_proof};
+    use crate::{BellmanCeVerifier, PreparationConfig, PreparedVerifyingKey, Proof, VerifierError};
+
+    type Backend = BellmanCeVerifier<Bn256>;
+
+    /// The fixture key prepared without and with fixed-base tables, and with
+    /// tables and a deferred `e(alpha, beta)`, with its proof and inputs.
+    fn fixture() -> (Vec<PreparedVerifyingKey<Bn256>>, Proof<Bn256>, Vec<Fr>) {
+        let vk = Backend::decode_verifying_key(
+            include_bytes!("../../fixtures/bn128/vkey.bin")
+        ).unwrap();
+        let proof = Backend::decode_proof(
+            include_bytes!("../../fixtures/bn128/proof.bin")
+        ).unwrap();
+        let inputs = Backend::decode_public_inputs(
+            include_bytes!("../../fixtures/bn128/public.bin")
+        ).unwrap();
+        let tables = PreparationConfig { fixed_base_window: Some(4) };
+        let keys = vec![
+            prepare_verifying_key_with_config(&vk, &PreparationConfig::default()),
+            prepare_verifying_key_with_config(&vk, &tables),
+            prepare_verifying_key_deferred(&vk, &tables)
+        ];
+        (keys, proof, inputs)
+    }
+
+    #[test]
+    fn folded_keys_verify_like_the_full_key() {
+        let (keys, proof, inputs) = fixture();
+
+        for pvk in &keys {
+            assert!(verify_proof(pvk, &proof, &inputs).unwrap());
+
+            let unchanged = pvk.with_fixed_inputs(&[]).unwrap();
+            assert!(verify_proof(&unchanged, &proof, &inputs).unwrap());
+
+            let folded = pvk.with_fixed_inputs(&inputs).unwrap();
+            assert!(verify_proof(&folded, &proof, &[]).unwrap());
+            assert!(matches!(
+                verify_proof(&folded, &proof, &inputs),
+                Err(VerifierError::PublicInputCount { expected: 0, actual: 1 })
+            ));
+
+            let mut wrong = inputs[0];
+            wrong.add_assign(&Fr::one());
+            let wrong = pvk.with_fixed_inputs(&[wrong]).unwrap();
+            assert!(!verify_proof(&wrong, &proof, &[]).unwrap());
+        }
+    }
+
+    #[test]
+    fn rejects_fixing_too_many_inputs() {
+        let (keys, _, inputs) = fixture();
+
+        for pvk in &keys {
+            assert!(matches!(
+                pvk.with_fixed_inputs(&[inputs[0], inputs[0]]),
+                Err(VerifierError::PublicInputCount { expected: 1, actual: 2 })
+            ));
+        }
+    }
+}
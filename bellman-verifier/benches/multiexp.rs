@@ -0,0 +1,53 @@
+use bellman_verifier::{multiexp, FixedBaseTable};
+use bls12_381::{Bls12, G1Affine, G1Projective, Scalar};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use group::{ff::Field, Curve, Group};
+use rand::thread_rng;
+
+fn bench_ic_accumulation(c: &mut Criterion) {
+    let mut rng = thread_rng();
+    let mut group = c.benchmark_group("ic_accumulation");
+
+    for n in [1usize, 8, 64, 256, 1024] {
+        let bases = (0..n)
+            .map(|_| G1Projective::random(&mut rng).to_affine())
+            .collect::<Vec<G1Affine>>();
+        let scalars = (0..n)
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        // The loop `verify_proof` used before switching to `multiexp`.
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |b, _| {
+            b.iter(|| {
+                let mut acc = G1Projective::identity();
+                for (base, scalar) in bases.iter().zip(scalars.iter()) {
+                    acc += base * scalar;
+                }
+                acc
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("multiexp", n), &n, |b, _| {
+            b.iter(|| multiexp::<Bls12>(&bases, &scalars))
+        });
+
+        let tables = bases
+            .iter()
+            .map(|base| FixedBaseTable::<Bls12>::new(base, 4))
+            .collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::new("fixed_base_w4", n), &n, |b, _| {
+            b.iter(|| {
+                let mut acc = G1Projective::identity();
+                for (table, scalar) in tables.iter().zip(scalars.iter()) {
+                    acc += table.mul(scalar);
+                }
+                acc
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ic_accumulation);
+criterion_main!(benches);
@@ -0,0 +1,291 @@
+use core::marker::PhantomData;
+
+use group::{ff::{PrimeField, PrimeFieldBits}, UncompressedEncoding};
+use pairing::MultiMillerLoop;
+use verifier_traits::{split_proof, split_verifying_key, Groth16Verifier, VerifierError};
+#[cfg(feature = "runtime-benchmarks")]
+use group::{ff::Field, prime::PrimeCurveAffine, Curve};
+#[cfg(feature = "runtime-benchmarks")]
+use verifier_traits::{BenchmarkData, BenchmarkSetup};
+
+use super::{
+    prepare_verifier_key, prepare_verifying_key_deferred, verify_proof, PreparationConfig,
+    PreparedVerifyingKey, Proof, VerifierKey,
+};
+
+use sp_std::prelude::*;
+
+/// `Groth16Verifier` for the engines of the `pairing` crate, e.g.
+/// `BellmanVerifier<bls12_381::Bls12>`.
+///
+/// Stored prepared keys use the layout of `decode_verifying_key`. They are
+/// decoded without curve and subgroup checks and prepared with
+/// `prepare_verifying_key_deferred`, so reading one back costs no pairing.
+///
+/// Public inputs are decoded through `PrimeField::from_repr`, which is
+/// assumed to be little-endian as for `bls12_381::Scalar` and `bn256::Fr`.
+pub struct BellmanVerifier<E>(PhantomData<E>);
+
+fn uncompressed_size<G: UncompressedEncoding>() -> usize {
+    G::Uncompressed::default().as_ref().len()
+}
+
+fn decode_point<G: UncompressedEncoding>(bytes: &[u8]) -> Result<G, VerifierError> {
+    let mut repr = G::Uncompressed::default();
+    if repr.as_ref().len() != bytes.len() {
+        return Err(VerifierError::InvalidEncoding);
+    }
+    repr.as_mut().copy_from_slice(bytes);
+
+    Option::from(G::from_uncompressed(&repr)).ok_or(VerifierError::InvalidEncoding)
+}
+
+pub(crate) fn decode_point_unchecked<G: UncompressedEncoding>(
+    bytes: &[u8],
+) -> Result<G, VerifierError> {
+    let mut repr = G::Uncompressed::default();
+    repr.as_mut().copy_from_slice(bytes);
+
+    Option::from(G::from_uncompressed_unchecked(&repr)).ok_or(VerifierError::InvalidEncoding)
+}
+
+fn encode_point<G: UncompressedEncoding>(point: &G, out: &mut Vec<u8>) {
+    out.extend_from_slice(point.to_uncompressed().as_ref());
+}
+
+/// Decodes the points of a key with `decode_g1` and `decode_g2`, which may
+/// or may not check them.
+pub(crate) fn decode_key<E: MultiMillerLoop>(
+    bytes: &[u8],
+    decode_g1: fn(&[u8]) -> Result<E::G1Affine, VerifierError>,
+    decode_g2: fn(&[u8]) -> Result<E::G2Affine, VerifierError>,
+) -> Result<VerifierKey<E>, VerifierError> {
+    let vk = split_verifying_key(
+        bytes,
+        uncompressed_size::<E::G1Affine>(),
+        uncompressed_size::<E::G2Affine>(),
+    )?;
+
+    Ok(VerifierKey {
+        alpha_g1: decode_g1(vk.alpha_g1)?,
+        beta_g2: decode_g2(vk.beta_g2)?,
+        gamma_g2: decode_g2(vk.gamma_g2)?,
+        delta_g2: decode_g2(vk.delta_g2)?,
+        ic: vk.ic.into_iter().map(decode_g1).collect::<Result<_, _>>()?,
+    })
+}
+
+impl<E: MultiMillerLoop> Groth16Verifier for BellmanVerifier<E>
+where
+    E::Fr: PrimeFieldBits,
+{
+    type Fr = E::Fr;
+    type Proof = Proof<E>;
+    type VerifyingKey = VerifierKey<E>;
+    type PreparedVerifyingKey = PreparedVerifyingKey<E>;
+
+    fn prepare_verifying_key(vk: &VerifierKey<E>) -> PreparedVerifyingKey<E> {
+        prepare_verifier_key(vk)
+    }
+
+    fn verify_proof(
+        pvk: &PreparedVerifyingKey<E>,
+        proof: &Proof<E>,
+        public_inputs: &[E::Fr],
+    ) -> Result<(), VerifierError> {
+        verify_proof(pvk, proof, public_inputs)
+    }
+
+    fn decode_proof(bytes: &[u8]) -> Result<Proof<E>, VerifierError> {
+        let proof = split_proof(
+            bytes,
+            uncompressed_size::<E::G1Affine>(),
+            uncompressed_size::<E::G2Affine>(),
+        )?;
+
+        Ok(Proof {
+            a: decode_point(proof.a)?,
+            b: decode_point(proof.b)?,
+            c: decode_point(proof.c)?,
+        })
+    }
+
+    fn decode_verifying_key(bytes: &[u8]) -> Result<VerifierKey<E>, VerifierError> {
+        decode_key(bytes, decode_point, decode_point)
+    }
+
+    fn public_input_count(vk: &VerifierKey<E>) -> usize {
+        vk.ic.len().saturating_sub(1)
+    }
+
+    fn encode_prepared_verifying_key(vk: &VerifierKey<E>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode_point(&vk.alpha_g1, &mut bytes);
+        encode_point(&vk.beta_g2, &mut bytes);
+        encode_point(&vk.gamma_g2, &mut bytes);
+        encode_point(&vk.delta_g2, &mut bytes);
+        for ic in &vk.ic {
+            encode_point(ic, &mut bytes);
+        }
+        bytes
+    }
+
+    fn decode_prepared_verifying_key(
+        bytes: &[u8],
+    ) -> Result<PreparedVerifyingKey<E>, VerifierError> {
+        let vk = decode_key(bytes, decode_point_unchecked, decode_point_unchecked)?;
+        Ok(prepare_verifying_key_deferred(&vk, &PreparationConfig::default()))
+    }
+
+    fn decode_public_input(bytes: &[u8]) -> Result<E::Fr, VerifierError> {
+        let mut repr = <E::Fr as PrimeField>::Repr::default();
+        if repr.as_ref().len() != bytes.len() {
+            return Err(VerifierError::InvalidEncoding);
+        }
+        for (r, b) in repr.as_mut().iter_mut().zip(bytes.iter().rev()) {
+            *r = *b;
+        }
+
+        Option::from(E::Fr::from_repr(repr)).ok_or(VerifierError::InvalidEncoding)
+    }
+}
+
+/// Draws a full width scalar from a splitmix64 stream.
+#[cfg(feature = "runtime-benchmarks")]
+fn benchmark_scalar<F: PrimeField>(state: &mut u64) -> F {
+    let shift = F::from(1 << 32).square();
+    (0..4).fold(F::zero(), |acc, _| {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        acc * shift + F::from(z ^ (z >> 31))
+    })
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl<E: MultiMillerLoop> BenchmarkSetup for BellmanVerifier<E>
+where
+    E::Fr: PrimeFieldBits,
+{
+    fn benchmark_setup(num_inputs: usize, seed: u64) -> BenchmarkData {
+        let mut state = seed;
+        let mut scalar = || benchmark_scalar::<E::Fr>(&mut state);
+        let (alpha, beta, gamma, delta) = (scalar(), scalar(), scalar(), scalar());
+        let ic: Vec<E::Fr> = (0..=num_inputs).map(|_| scalar()).collect();
+        let (a, b) = (scalar(), scalar());
+
+        let inputs: Vec<E::Fr> = (0..num_inputs)
+            .map(|i| -E::Fr::from(i as u64 + 1))
+            .collect();
+        let acc = inputs
+            .iter()
+            .zip(&ic[1..])
+            .fold(ic[0], |acc, (x, k)| acc + *x * k);
+        let c = (a * b - alpha * beta - acc * gamma) * delta.invert().unwrap();
+
+        let g1 = E::G1Affine::generator();
+        let g2 = E::G2Affine::generator();
+
+        let mut key = Vec::new();
+        encode_point(&(g1 * alpha).to_affine(), &mut key);
+        encode_point(&(g2 * beta).to_affine(), &mut key);
+        encode_point(&(g2 * gamma).to_affine(), &mut key);
+        encode_point(&(g2 * delta).to_affine(), &mut key);
+        for k in &ic {
+            encode_point(&(g1 * k).to_affine(), &mut key);
+        }
+
+        let mut proof = Vec::new();
+        encode_point(&(g1 * a).to_affine(), &mut proof);
+        encode_point(&(g2 * b).to_affine(), &mut proof);
+        encode_point(&(g1 * c).to_affine(), &mut proof);
+
+        let mut public_inputs = Vec::new();
+        for x in &inputs {
+            let mut repr = x.to_repr();
+            repr.as_mut().reverse();
+            public_inputs.extend_from_slice(repr.as_ref());
+        }
+
+        BenchmarkData {
+            key,
+            proof,
+            public_inputs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::Bls12;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use verifier_traits::{Groth16Verifier, VerifierError};
+
+    use super::BellmanVerifier;
+    use crate::verify_proofs_batch;
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    fn fixture() -> (&'static [u8], &'static [u8], &'static [u8]) {
+        (
+            include_bytes!("../../fixtures/bls12381/vkey.bin"),
+            include_bytes!("../../fixtures/bls12381/proof.bin"),
+            include_bytes!("../../fixtures/bls12381/public.bin"),
+        )
+    }
+
+    #[test]
+    fn stored_keys_verify_like_prepared_ones() {
+        let (key, proof, public_inputs) = fixture();
+        let vk = Backend::decode_verifying_key(key).unwrap();
+        assert_eq!(Backend::public_input_count(&vk), 1);
+
+        let stored = Backend::encode_prepared_verifying_key(&vk);
+        assert_eq!(stored, key);
+        let deferred = Backend::decode_prepared_verifying_key(&stored).unwrap();
+        let prepared = Backend::prepare_verifying_key(&vk);
+
+        for pvk in [&prepared, &deferred] {
+            assert_eq!(Backend::verify_encoded(pvk, proof, public_inputs), Ok(()));
+            let mut wrong = public_inputs.to_vec();
+            wrong[31] ^= 1;
+            assert_eq!(
+                Backend::verify_encoded(pvk, proof, &wrong),
+                Err(VerifierError::InvalidProof)
+            );
+        }
+
+        assert_eq!(
+            Backend::decode_prepared_verifying_key(&stored[1..]).err(),
+            Some(VerifierError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn batches_mix_stored_and_prepared_keys() {
+        let (key, proof, public_inputs) = fixture();
+        let vk = Backend::decode_verifying_key(key).unwrap();
+        let prepared = Backend::prepare_verifying_key(&vk);
+        let deferred =
+            Backend::decode_prepared_verifying_key(&Backend::encode_prepared_verifying_key(&vk))
+                .unwrap();
+        let proof = Backend::decode_proof(proof).unwrap();
+        let inputs = Backend::decode_public_inputs(public_inputs).unwrap();
+        let mut rng = StdRng::seed_from_u64(0x0ba7_c4ed);
+
+        let items = [
+            (&deferred, &proof, &inputs[..]),
+            (&prepared, &proof, &inputs[..]),
+        ];
+        assert_eq!(verify_proofs_batch(&items, &mut rng), Ok(()));
+
+        let wrong = [-inputs[0]];
+        let items = [
+            (&prepared, &proof, &inputs[..]),
+            (&deferred, &proof, &wrong[..]),
+        ];
+        assert!(verify_proofs_batch(&items, &mut rng).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use group::{ff::{Field, PrimeFieldBits}, prime::PrimeCurveAffine, Curve, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use rand_core::RngCore;
+use super::{AlphaBeta, PreparedVerifyingKey, Proof, VerifierError};
+use super::verifier::accumulate_inputs;
+
+use sp_std::prelude::*;
+
+/// A `(prepared key, proof, public inputs)` triple in a batch.
+pub type BatchItem<'a, E> = (
+    &'a PreparedVerifyingKey<E>,
+    &'a Proof<E>,
+    &'a [<E as pairing::Engine>::Fr],
+);
+
+/// Verifies a batch of proofs which may belong to different circuits, each
+/// given as a `(prepared key, proof, public inputs)` triple.
+///
+/// Every proof's equation is scaled by a random scalar drawn from `rng` and
+/// all of them are checked with a single multi-Miller loop and one shared
+/// final exponentiation. The batch is accepted only if every proof is valid;
+/// on failure it does not tell which proof was rejected, so callers that need
+/// that should fall back to `verify_proof` for each item.
+pub fn verify_proofs_batch<E, R>(
+    items: &[BatchItem<'_, E>],
+    rng: &mut R,
+) -> Result<(), VerifierError>
+where
+    E: MultiMillerLoop,
+    E::Fr: PrimeFieldBits,
+    R: RngCore,
+{
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    // For every proof i with random r_i we check
+    // r_i * A_i * B_i + r_i * inputs_i * (-gamma_i) + r_i * C_i * (-delta_i) = r_i * alpha_i * beta_i
+    // and sum the equations up, so the right hand side becomes a sum in Gt and
+    // the left hand side a single multi-Miller loop over 3 * n terms. Keys
+    // with a deferred alpha * beta add r_i * (-alpha_i) * beta_i to the left
+    // hand side instead.
+    let b_prepared = items
+        .iter()
+        .map(|(_, proof, _)| E::G2Prepared::from(proof.b))
+        .collect::<Vec<_>>();
+    let mut scaled = Vec::with_capacity(items.len() * 4);
+    let mut g2 = Vec::with_capacity(items.len() * 4);
+    let mut alpha_g1_beta_g2 = E::Gt::identity();
+
+    for ((pvk, proof, public_inputs), b) in items.iter().zip(&b_prepared) {
+        let acc = accumulate_inputs(pvk, public_inputs)?;
+        let r = E::Fr::random(&mut *rng);
+
+        scaled.push(proof.a * r);
+        scaled.push(acc * r);
+        scaled.push(proof.c * r);
+        g2.push(b);
+        g2.push(&pvk.neg_gamma_g2);
+        g2.push(&pvk.neg_delta_g2);
+        match &pvk.alpha_g1_beta_g2 {
+            AlphaBeta::Pairing(alpha_beta) => alpha_g1_beta_g2 += *alpha_beta * r,
+            AlphaBeta::Deferred(neg_alpha, beta) => {
+                scaled.push(*neg_alpha * r);
+                g2.push(beta);
+            }
+        }
+    }
+
+    let mut scaled_affine = vec![E::G1Affine::identity(); scaled.len()];
+    E::G1::batch_normalize(&scaled, &mut scaled_affine);
+
+    let terms = scaled_affine.iter().zip(g2).collect::<Vec<_>>();
+
+    // As in `verify_proof`, a Miller loop result outside `Gt` never passes.
+    let result = E::multi_miller_loop(&terms).final_exponentiation();
+    if bool::from((result - alpha_g1_beta_g2).is_identity()) {
+        Ok(())
+    } else {
+        Err(VerifierError::InvalidProof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::Bls12;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use verifier_traits::Groth16Verifier;
+
+    use super::verify_proofs_batch;
+    use crate::{BellmanVerifier, PreparedVerifyingKey, Proof, VerifierError};
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    fn fixture() -> (
+        PreparedVerifyingKey<Bls12>,
+        Proof<Bls12>,
+        Vec<bls12_381::Scalar>,
+    ) {
+        let vk = Backend::decode_verifying_key(include_bytes!("../../fixtures/bls12381/vkey.bin"))
+            .unwrap();
+        let proof =
+            Backend::decode_proof(include_bytes!("../../fixtures/bls12381/proof.bin")).unwrap();
+        let inputs =
+            Backend::decode_public_inputs(include_bytes!("../../fixtures/bls12381/public.bin"))
+                .unwrap();
+        (Backend::prepare_verifying_key(&vk), proof, inputs)
+    }
+
+    #[test]
+    fn accepts_valid_batches() {
+        let (pvk, proof, inputs) = fixture();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(verify_proofs_batch::<Bls12, _>(&[], &mut rng), Ok(()));
+        let items = [(&pvk, &proof, &inputs[..]), (&pvk, &proof, &inputs[..])];
+        assert_eq!(verify_proofs_batch(&items, &mut rng), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_batch_with_one_bad_proof() {
+        let (pvk, proof, inputs) = fixture();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let bad = Proof {
+            c: proof.a,
+            ..proof.clone()
+        };
+        let items = [
+            (&pvk, &proof, &inputs[..]),
+            (&pvk, &bad, &inputs[..]),
+            (&pvk, &proof, &inputs[..]),
+        ];
+        assert_eq!(
+            verify_proofs_batch(&items, &mut rng),
+            Err(VerifierError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn rejects_a_batch_with_a_wrong_input_count() {
+        let (pvk, proof, inputs) = fixture();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let too_many = [inputs[0], inputs[0]];
+        let items = [(&pvk, &proof, &inputs[..]), (&pvk, &proof, &too_many[..])];
+        assert_eq!(
+            verify_proofs_batch(&items, &mut rng),
+            Err(VerifierError::PublicInputCount {
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+}
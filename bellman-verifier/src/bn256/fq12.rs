@@ -0,0 +1,257 @@
+//! `Fq12 = Fq6[w] / (w^2 - v)`, where the Miller loop accumulates and `Gt`
+//! lives, with the final exponentiation of `substrate-bn`.
+
+use core::ops::Mul;
+
+use bn::Fq2;
+
+use super::fq6::{fq2, Fq6, Limbs};
+
+/// `xi^((q^k - 1) / 6)` for `k = 0..=3`, as `(c0, c1)`.
+const FROBENIUS_COEFFS_C1: [(Limbs, Limbs); 4] = [
+    ([1, 0, 0, 0], [0, 0, 0, 0]),
+    (
+        [0xd60b_35da_dcc9_e470, 0x5c52_1e08_292f_2176, 0xe8b9_9fdd_76e6_8b60, 0x1284_b71c_2865_a7df],
+        [0xca5c_f05f_80f3_62ac, 0x7479_9277_8eee_c7e5, 0xa632_7cfe_1215_0b8e, 0x2469_96f3_b4fa_e7e6],
+    ),
+    (
+        [0xe4bd_44e5_607c_fd49, 0xc28f_069f_bb96_6e3d, 0x5e6d_d9e7_e0ac_ccb0, 0x3064_4e72_e131_a029],
+        [0, 0, 0, 0],
+    ),
+    (
+        [0xe86f_7d39_1ed4_a67f, 0x894c_b38d_be55_d24a, 0xefe9_608c_d0ac_aa90, 0x19dc_81cf_cc82_e4bb],
+        [0x7694_aa2b_f4c0_c101, 0x7f03_a5e3_97d4_39ec, 0x06cb_eee3_3576_139d, 0x00ab_f8b6_0be7_7d73],
+    ),
+];
+
+/// The BN parameter `z` of BN254, whose ate loop count is `6z + 2`.
+const BN_Z: u64 = 4_965_661_367_192_848_881;
+
+/// An element `c0 + c1 w` of Fq12.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq12 {
+    pub c0: Fq6,
+    pub c1: Fq6,
+}
+
+impl Fq12 {
+    pub fn new(c0: Fq6, c1: Fq6) -> Fq12 {
+        Fq12 { c0, c1 }
+    }
+
+    pub fn zero() -> Fq12 {
+        Fq12::new(Fq6::zero(), Fq6::zero())
+    }
+
+    pub fn one() -> Fq12 {
+        Fq12::new(Fq6::one(), Fq6::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn square(&self) -> Fq12 {
+        let ab = self.c0 * self.c1;
+
+        Fq12::new(
+            (self.c1.mul_by_nonresidue() + self.c0) * (self.c0 + self.c1)
+                - ab
+                - ab.mul_by_nonresidue(),
+            ab + ab,
+        )
+    }
+
+    pub fn inverse(&self) -> Option<Fq12> {
+        let t = (self.c0.square() - self.c1.square().mul_by_nonresidue()).inverse()?;
+        Some(Fq12::new(self.c0 * t, -(self.c1 * t)))
+    }
+
+    /// `self^(q^6)`, the inverse of elements of the cyclotomic subgroup,
+    /// which holds `Gt`.
+    pub fn conjugate(&self) -> Fq12 {
+        Fq12::new(self.c0, -self.c1)
+    }
+
+    /// `self^(q^power)`, for `power` up to 3.
+    pub fn frobenius_map(&self, power: usize) -> Fq12 {
+        Fq12::new(
+            self.c0.frobenius_map(power),
+            self.c1
+                .frobenius_map(power)
+                .scale(fq2(FROBENIUS_COEFFS_C1[power])),
+        )
+    }
+
+    /// `self^exp` by square and multiply, `exp` as little-endian limbs.
+    pub fn pow(&self, exp: &[u64]) -> Fq12 {
+        let mut res = Fq12::one();
+        for limb in exp.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.square();
+                if (limb >> i) & 1 == 1 {
+                    res = res * *self;
+                }
+            }
+        }
+        res
+    }
+
+    /// Multiplies by the sparse element `ell_0 + ell_vv v w + ell_vw v^2 w`
+    /// a Miller loop line evaluates to.
+    pub(super) fn mul_by_024(&self, ell_0: Fq2, ell_vw: Fq2, ell_vv: Fq2) -> Fq12 {
+        let (z0, z1, z2) = (self.c0.c0, self.c0.c1, self.c0.c2);
+        let (z3, z4, z5) = (self.c1.c0, self.c1.c1, self.c1.c2);
+        let xi = super::fq6::xi();
+
+        let (x0, x2, x4) = (ell_0, ell_vv, ell_vw);
+
+        let d0 = z0 * x0;
+        let d2 = z2 * x2;
+        let d4 = z4 * x4;
+        let t2 = z0 + z4;
+        let t1 = z0 + z2;
+        let s0 = z1 + z3 + z5;
+
+        let s1 = z1 * x2;
+        let t3 = s1 + d4;
+        let r0 = t3 * xi + d0;
+
+        let t3 = z5 * x4;
+        let s1 = s1 + t3;
+        let t3 = t3 + d2;
+        let t4 = t3 * xi;
+        let t3 = z1 * x0;
+        let s1 = s1 + t3;
+        let r1 = t4 + t3;
+
+        let t0 = x0 + x2;
+        let t3 = t1 * t0 - d0 - d2;
+        let t4 = z3 * x4;
+        let s1 = s1 + t4;
+        let r2 = t3 + t4;
+
+        let t0 = z2 + z4;
+        let t1 = x2 + x4;
+        let t3 = t0 * t1 - d2 - d4;
+        let t4 = t3 * xi;
+        let t3 = z3 * x0;
+        let s1 = s1 + t3;
+        let r3 = t4 + t3;
+
+        let t3 = z5 * x2;
+        let s1 = s1 + t3;
+        let t4 = t3 * xi;
+        let t0 = x0 + x4;
+        let t3 = t2 * t0 - d0 - d4;
+        let r4 = t4 + t3;
+
+        let t0 = x0 + x2 + x4;
+        let r5 = s0 * t0 - s1;
+
+        Fq12::new(Fq6::new(r0, r1, r2), Fq6::new(r3, r4, r5))
+    }
+
+    /// Granger-Scott squaring, valid in the cyclotomic subgroup only.
+    fn cyclotomic_square(&self) -> Fq12 {
+        let xi = super::fq6::xi();
+        let (z0, z4, z3) = (self.c0.c0, self.c0.c1, self.c0.c2);
+        let (z2, z1, z5) = (self.c1.c0, self.c1.c1, self.c1.c2);
+
+        let tmp = z0 * z1;
+        let t0 = (z0 + z1) * (z1 * xi + z0) - tmp - tmp * xi;
+        let t1 = tmp + tmp;
+
+        let tmp = z2 * z3;
+        let t2 = (z2 + z3) * (z3 * xi + z2) - tmp - tmp * xi;
+        let t3 = tmp + tmp;
+
+        let tmp = z4 * z5;
+        let t4 = (z4 + z5) * (z5 * xi + z4) - tmp - tmp * xi;
+        let t5 = tmp + tmp;
+
+        let z0 = t0 - z0;
+        let z0 = z0 + z0 + t0;
+
+        let z1 = t1 + z1;
+        let z1 = z1 + z1 + t1;
+
+        let tmp = t5 * xi;
+        let z2 = tmp + z2;
+        let z2 = z2 + z2 + tmp;
+
+        let z3 = t4 - z3;
+        let z3 = z3 + z3 + t4;
+
+        let z4 = t2 - z4;
+        let z4 = z4 + z4 + t2;
+
+        let z5 = t3 + z5;
+        let z5 = z5 + z5 + t3;
+
+        Fq12::new(Fq6::new(z0, z4, z3), Fq6::new(z2, z1, z5))
+    }
+
+    /// `self^(-z)`, in the cyclotomic subgroup.
+    fn exp_by_neg_z(&self) -> Fq12 {
+        let mut res = Fq12::one();
+        for i in (0..64 - BN_Z.leading_zeros()).rev() {
+            res = res.cyclotomic_square();
+            if (BN_Z >> i) & 1 == 1 {
+                res = res * *self;
+            }
+        }
+        res.conjugate()
+    }
+
+    /// `self^((q^12 - 1) / r)`, `None` for zero.
+    pub fn final_exponentiation(&self) -> Option<Fq12> {
+        // The easy part, `(q^6 - 1) (q^2 + 1)`, lands in the cyclotomic
+        // subgroup.
+        let c = self.conjugate() * self.inverse()?;
+        let r = c.frobenius_map(2) * c;
+
+        // The hard part, `(q^4 - q^2 + 1) / r`, as in `substrate-bn`.
+        let a = r.exp_by_neg_z();
+        let b = a.cyclotomic_square();
+        let c = b.cyclotomic_square();
+        let d = c * b;
+
+        let e = d.exp_by_neg_z();
+        let f = e.cyclotomic_square();
+        let g = f.exp_by_neg_z();
+        let h = d.conjugate();
+        let i = g.conjugate();
+
+        let j = i * e;
+        let k = j * h;
+        let l = k * b;
+        let m = k * e;
+        let n = r * m;
+
+        let o = l.frobenius_map(1);
+        let p = o * n;
+
+        let q = k.frobenius_map(2);
+        let s = q * p;
+
+        let t = r.conjugate() * l;
+        let u = t.frobenius_map(3);
+
+        Some(u * s)
+    }
+}
+
+impl Mul for Fq12 {
+    type Output = Fq12;
+
+    fn mul(self, rhs: Fq12) -> Fq12 {
+        let aa = self.c0 * rhs.c0;
+        let bb = self.c1 * rhs.c1;
+
+        Fq12::new(
+            bb.mul_by_nonresidue() + aa,
+            (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - aa - bb,
+        )
+    }
+}
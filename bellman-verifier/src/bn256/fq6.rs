@@ -0,0 +1,190 @@
+//! `Fq6 = Fq2[v] / (v^3 - xi)` with `xi = 9 + u`, the middle of the tower
+//! that the pairing computes in.
+//!
+//! `substrate-bn` keeps its own Fq6 and Fq12 private, so the tower is
+//! rebuilt here on top of its public `Fq2`, following the same formulas.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use bn::arith::U256;
+use bn::{Fq, Fq2};
+
+/// Little-endian 64-bit limbs of a canonical base field element.
+pub(super) type Limbs = [u64; 4];
+
+const ZERO: Limbs = [0, 0, 0, 0];
+const ONE: Limbs = [1, 0, 0, 0];
+
+/// `xi^((q^k - 1) / 3)` for `k = 0..=3`, as `(c0, c1)`.
+const FROBENIUS_COEFFS_C1: [(Limbs, Limbs); 4] = [
+    (ONE, ZERO),
+    (
+        [0x99e3_9557_176f_553d, 0xb78c_c310_c2c3_330c, 0x4c0b_ec3c_f559_b143, 0x2fb3_4798_4f79_11f7],
+        [0x1665_d51c_640f_cba2, 0x32ae_2a1d_0b7c_9dce, 0x4ba4_cc8b_d75a_0794, 0x16c9_e550_61eb_ae20],
+    ),
+    (
+        [0xe4bd_44e5_607c_fd48, 0xc28f_069f_bb96_6e3d, 0x5e6d_d9e7_e0ac_ccb0, 0x3064_4e72_e131_a029],
+        ZERO,
+    ),
+    (
+        [0x7b74_6ee8_7bdc_fb6d, 0x805f_fd3d_5d69_42d3, 0xbaff_1c77_959f_25ac, 0x0856_e078_b755_ef0a],
+        [0x380c_ab2b_aaa5_86de, 0x0fdf_31bf_98ff_2631, 0xa9f3_0e6d_ec26_094f, 0x04f1_de41_b3d1_766f],
+    ),
+];
+
+/// `xi^(2 (q^k - 1) / 3)` for `k = 0..=3`, as `(c0, c1)`.
+const FROBENIUS_COEFFS_C2: [(Limbs, Limbs); 4] = [
+    (ONE, ZERO),
+    (
+        [0x848a_1f55_921e_a762, 0xd333_65f7_be94_ec72, 0x80f3_c0b7_5a18_1e84, 0x05b5_4f5e_64ee_a801],
+        [0xc13b_4711_cd2b_8126, 0x3685_d2ea_1bde_c763, 0x9f3a_80b0_3b0b_1c92, 0x2c14_5edb_e7fd_8aee],
+    ),
+    (
+        [0x5763_4731_77ff_fffe, 0xd4f2_63f1_acdb_5c4f, 0x59e2_6bce_a0d4_8bac, 0x0000_0000_0000_0000],
+        ZERO,
+    ),
+    (
+        [0x0e1a_92bc_3ccb_f066, 0xe633_0945_75b0_6bcb, 0x19be_e0f7_b5b2_444e, 0x0bc5_8c66_11c0_8dab],
+        [0x5fe3_ed9d_730c_239f, 0xa44a_9e08_737f_96e5, 0xfeb0_f6ef_0cd2_1d04, 0x23d5_e999_e191_0a12],
+    ),
+];
+
+pub(super) fn fq(limbs: Limbs) -> Fq {
+    Fq::from_u256(U256::from(limbs)).expect("constant is below the modulus")
+}
+
+pub(super) fn fq2((c0, c1): (Limbs, Limbs)) -> Fq2 {
+    Fq2::new(fq(c0), fq(c1))
+}
+
+/// `xi = 9 + u`, the non-residue Fq6 and the sextic twist are built with.
+pub(super) fn xi() -> Fq2 {
+    Fq2::new(fq([9, 0, 0, 0]), Fq::one())
+}
+
+pub(super) fn fq2_scale(a: Fq2, by: Fq) -> Fq2 {
+    Fq2::new(a.real() * by, a.imaginary() * by)
+}
+
+/// The Frobenius map of Fq2, i.e. `a^q`.
+pub(super) fn fq2_conjugate(a: Fq2) -> Fq2 {
+    Fq2::new(a.real(), -a.imaginary())
+}
+
+pub(super) fn fq2_inverse(a: Fq2) -> Option<Fq2> {
+    let (c0, c1) = (a.real(), a.imaginary());
+    let t = (c0 * c0 + c1 * c1).inverse()?;
+    Some(Fq2::new(c0 * t, -(c1 * t)))
+}
+
+/// An element `c0 + c1 v + c2 v^2` of Fq6.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq6 {
+    pub c0: Fq2,
+    pub c1: Fq2,
+    pub c2: Fq2,
+}
+
+impl Fq6 {
+    pub fn new(c0: Fq2, c1: Fq2, c2: Fq2) -> Fq6 {
+        Fq6 { c0, c1, c2 }
+    }
+
+    pub fn zero() -> Fq6 {
+        Fq6::new(Fq2::zero(), Fq2::zero(), Fq2::zero())
+    }
+
+    pub fn one() -> Fq6 {
+        Fq6::new(Fq2::one(), Fq2::zero(), Fq2::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    /// Multiplies by `v`, the non-residue Fq12 is built with.
+    pub fn mul_by_nonresidue(&self) -> Fq6 {
+        Fq6::new(self.c2 * xi(), self.c0, self.c1)
+    }
+
+    pub fn scale(&self, by: Fq2) -> Fq6 {
+        Fq6::new(self.c0 * by, self.c1 * by, self.c2 * by)
+    }
+
+    /// `self^(q^power)`, for `power` up to 3.
+    pub fn frobenius_map(&self, power: usize) -> Fq6 {
+        let conjugate = |a: Fq2| if power % 2 == 1 { fq2_conjugate(a) } else { a };
+
+        Fq6::new(
+            conjugate(self.c0),
+            conjugate(self.c1) * fq2(FROBENIUS_COEFFS_C1[power]),
+            conjugate(self.c2) * fq2(FROBENIUS_COEFFS_C2[power]),
+        )
+    }
+
+    pub fn square(&self) -> Fq6 {
+        let s0 = self.c0 * self.c0;
+        let ab = self.c0 * self.c1;
+        let s1 = ab + ab;
+        let t = self.c0 - self.c1 + self.c2;
+        let s2 = t * t;
+        let bc = self.c1 * self.c2;
+        let s3 = bc + bc;
+        let s4 = self.c2 * self.c2;
+
+        Fq6::new(
+            s0 + s3 * xi(),
+            s1 + s4 * xi(),
+            s1 + s2 + s3 - s0 - s4,
+        )
+    }
+
+    pub fn inverse(&self) -> Option<Fq6> {
+        let c0 = self.c0 * self.c0 - self.c1 * self.c2 * xi();
+        let c1 = self.c2 * self.c2 * xi() - self.c0 * self.c1;
+        let c2 = self.c1 * self.c1 - self.c0 * self.c2;
+        let t = fq2_inverse((self.c2 * c1 + self.c1 * c2) * xi() + self.c0 * c0)?;
+
+        Some(Fq6::new(c0 * t, c1 * t, c2 * t))
+    }
+}
+
+impl Add for Fq6 {
+    type Output = Fq6;
+
+    fn add(self, rhs: Fq6) -> Fq6 {
+        Fq6::new(self.c0 + rhs.c0, self.c1 + rhs.c1, self.c2 + rhs.c2)
+    }
+}
+
+impl Sub for Fq6 {
+    type Output = Fq6;
+
+    fn sub(self, rhs: Fq6) -> Fq6 {
+        Fq6::new(self.c0 - rhs.c0, self.c1 - rhs.c1, self.c2 - rhs.c2)
+    }
+}
+
+impl Neg for Fq6 {
+    type Output = Fq6;
+
+    fn neg(self) -> Fq6 {
+        Fq6::new(-self.c0, -self.c1, -self.c2)
+    }
+}
+
+impl Mul for Fq6 {
+    type Output = Fq6;
+
+    fn mul(self, rhs: Fq6) -> Fq6 {
+        let aa = self.c0 * rhs.c0;
+        let bb = self.c1 * rhs.c1;
+        let cc = self.c2 * rhs.c2;
+
+        Fq6::new(
+            ((self.c1 + self.c2) * (rhs.c1 + rhs.c2) - bb - cc) * xi() + aa,
+            (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - aa - bb + cc * xi(),
+            (self.c0 + self.c2) * (rhs.c0 + rhs.c2) - aa + bb - cc,
+        )
+    }
+}
@@ -0,0 +1,361 @@
+use core::fmt;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use bn::arith::U256;
+use ff::{Field, FieldBits, PrimeField, PrimeFieldBits};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Modulus of the scalar field, as little-endian 64-bit limbs.
+const MODULUS: [u64; 4] = [
+    0x43e1_f593_f000_0001,
+    0x2833_e848_79b9_7091,
+    0xb850_45b6_8181_585d,
+    0x3064_4e72_e131_a029,
+];
+
+/// `(r - 1) >> S`, the odd part of the multiplicative group order.
+const T: [u64; 4] = [
+    0x9b97_0914_3e1f_593f,
+    0x1815_85d2_833e_8487,
+    0x131a_029b_8504_5b68,
+    0x0000_0003_0644_e72e,
+];
+
+/// `(T - 1) / 2`, used by Tonelli-Shanks.
+const T_MINUS_ONE_OVER_TWO: [u64; 4] = [
+    0xcdcb_848a_1f0f_ac9f,
+    0x0c0a_c2e9_419f_4243,
+    0x098d_014d_c282_2db4,
+    0x0000_0001_8322_7397,
+];
+
+/// An element of the BN254 scalar field.
+///
+/// Arithmetic is delegated to `substrate-bn`, which is not constant time, and
+/// so neither are the `subtle` traits implemented here. That is fine for
+/// verification, where every input is public, but this type must not be used
+/// with secret scalars.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Fr(pub(crate) bn::Fr);
+
+impl fmt::Debug for Fr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for b in self.to_repr().iter().rev() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Fr {
+    fn default() -> Self {
+        Fr::zero()
+    }
+}
+
+impl From<u64> for Fr {
+    fn from(val: u64) -> Fr {
+        Fr(bn::Fr::new(U256::from(val)).expect("u64 is below the modulus"))
+    }
+}
+
+impl ConstantTimeEq for Fr {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from((self == other) as u8)
+    }
+}
+
+impl ConditionallySelectable for Fr {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        if bool::from(choice) {
+            *b
+        } else {
+            *a
+        }
+    }
+}
+
+impl Neg for Fr {
+    type Output = Fr;
+
+    #[inline]
+    fn neg(self) -> Fr {
+        Fr(-self.0)
+    }
+}
+
+impl Neg for &Fr {
+    type Output = Fr;
+
+    #[inline]
+    fn neg(self) -> Fr {
+        -*self
+    }
+}
+
+impl Add<&Fr> for &Fr {
+    type Output = Fr;
+
+    #[inline]
+    fn add(self, rhs: &Fr) -> Fr {
+        Fr(self.0 + rhs.0)
+    }
+}
+
+impl Sub<&Fr> for &Fr {
+    type Output = Fr;
+
+    #[inline]
+    fn sub(self, rhs: &Fr) -> Fr {
+        Fr(self.0 - rhs.0)
+    }
+}
+
+impl Mul<&Fr> for &Fr {
+    type Output = Fr;
+
+    #[inline]
+    fn mul(self, rhs: &Fr) -> Fr {
+        Fr(self.0 * rhs.0)
+    }
+}
+
+impl_binops_additive!(Fr, Fr);
+impl_binops_multiplicative!(Fr, Fr);
+
+impl Fr {
+    /// Returns the canonical value as little-endian 64-bit limbs.
+    pub(super) fn to_limbs(self) -> [u64; 4] {
+        u256_to_limbs(self.0.into_u256())
+    }
+}
+
+fn u256_to_limbs(val: U256) -> [u64; 4] {
+    [
+        val.0[0] as u64,
+        (val.0[0] >> 64) as u64,
+        val.0[1] as u64,
+        (val.0[1] >> 64) as u64,
+    ]
+}
+
+impl Field for Fr {
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut buf = [0u8; 64];
+        rng.fill_bytes(&mut buf);
+        Fr(bn::Fr::interpret(&buf))
+    }
+
+    fn zero() -> Self {
+        Fr(bn::Fr::zero())
+    }
+
+    fn one() -> Self {
+        Fr(bn::Fr::one())
+    }
+
+    fn is_zero_vartime(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    fn square(&self) -> Self {
+        self * self
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        match self.0.inverse() {
+            Some(inv) => CtOption::new(Fr(inv), Choice::from(1)),
+            None => CtOption::new(Fr::zero(), Choice::from(0)),
+        }
+    }
+
+    /// Tonelli-Shanks, variable time.
+    fn sqrt(&self) -> CtOption<Self> {
+        if self.0.is_zero() {
+            return CtOption::new(Fr::zero(), Choice::from(1));
+        }
+
+        let w = self.pow_vartime(T_MINUS_ONE_OVER_TWO);
+        let mut x = w * self;
+        let mut b = x * w;
+        let mut z = Fr::root_of_unity();
+        let mut v = Fr::S;
+
+        while b != Fr::one() {
+            // Find the least k such that b^(2^k) = 1.
+            let mut k = 0;
+            let mut b2k = b;
+            while b2k != Fr::one() {
+                b2k = b2k.square();
+                k += 1;
+                if k == v {
+                    return CtOption::new(Fr::zero(), Choice::from(0));
+                }
+            }
+
+            let mut w = z;
+            for _ in 0..(v - k - 1) {
+                w = w.square();
+            }
+
+            z = w.square();
+            b *= z;
+            x *= w;
+            v = k;
+        }
+
+        CtOption::new(x, Choice::from(1))
+    }
+}
+
+impl PrimeField for Fr {
+    /// Little-endian bytes, like the other `ff` implementations.
+    type Repr = [u8; 32];
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let mut be = repr;
+        be.reverse();
+        let fr = U256::from_slice(&be).ok().and_then(bn::Fr::new);
+
+        match fr {
+            Some(fr) => CtOption::new(Fr(fr), Choice::from(1)),
+            None => CtOption::new(Fr::zero(), Choice::from(0)),
+        }
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        let mut repr = [0u8; 32];
+        self.0
+            .into_u256()
+            .to_big_endian(&mut repr)
+            .expect("buffer is 32 bytes");
+        repr.reverse();
+        repr
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_repr()[0] & 1)
+    }
+
+    const NUM_BITS: u32 = 254;
+
+    const CAPACITY: u32 = 253;
+
+    fn multiplicative_generator() -> Self {
+        Fr::from(7)
+    }
+
+    const S: u32 = 28;
+
+    fn root_of_unity() -> Self {
+        Fr::multiplicative_generator().pow_vartime(T)
+    }
+}
+
+impl PrimeFieldBits for Fr {
+    type ReprBits = [u64; 4];
+
+    fn to_le_bits(&self) -> FieldBits<Self::ReprBits> {
+        FieldBits::new(self.to_limbs())
+    }
+
+    fn char_le_bits() -> FieldBits<Self::ReprBits> {
+        FieldBits::new(MODULUS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::{Field, PrimeField, PrimeFieldBits};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{Fr, MODULUS};
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(0x0b25_6f12)
+    }
+
+    #[test]
+    fn field_axioms() {
+        let mut rng = rng();
+        for _ in 0..50 {
+            let (a, b, c) = (Fr::random(&mut rng), Fr::random(&mut rng), Fr::random(&mut rng));
+
+            assert_eq!(a + b, b + a);
+            assert_eq!(a * b, b * a);
+            assert_eq!((a + b) + c, a + (b + c));
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!(a + Fr::zero(), a);
+            assert_eq!(a * Fr::one(), a);
+            assert_eq!(a - a, Fr::zero());
+            assert_eq!(a + (-a), Fr::zero());
+            assert_eq!(a.double(), a + a);
+            assert_eq!(a.square(), a * a);
+            if !bool::from(a.is_zero()) {
+                assert_eq!(a * a.invert().unwrap(), Fr::one());
+            }
+        }
+
+        assert!(bool::from(Fr::zero().invert().is_none()));
+        assert_eq!(-Fr::one() + Fr::one(), Fr::zero());
+        assert_eq!(Fr::from(6) * Fr::from(7), Fr::from(42));
+    }
+
+    #[test]
+    fn square_roots() {
+        let mut rng = rng();
+        for _ in 0..50 {
+            let a = Fr::random(&mut rng);
+            let root = a.square().sqrt().unwrap();
+            assert!(root == a || root == -a);
+        }
+
+        assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+        // The generator of the multiplicative group is not a square.
+        assert!(bool::from(Fr::multiplicative_generator().sqrt().is_none()));
+        assert!(bool::from((Fr::multiplicative_generator() * Fr::from(4)).sqrt().is_none()));
+
+        // `root_of_unity` has order exactly 2^S.
+        let mut w = Fr::root_of_unity();
+        for _ in 0..Fr::S - 1 {
+            w = w.square();
+        }
+        assert_eq!(w, -Fr::one());
+        assert_eq!(w.square(), Fr::one());
+    }
+
+    #[test]
+    fn canonical_encoding() {
+        let mut rng = rng();
+        for _ in 0..20 {
+            let a = Fr::random(&mut rng);
+            assert_eq!(Fr::from_repr(a.to_repr()).unwrap(), a);
+        }
+        assert_eq!(Fr::from(1).to_repr()[0], 1);
+
+        // The modulus and everything above it are rejected.
+        let mut modulus = [0u8; 32];
+        for (bytes, limb) in modulus.chunks_mut(8).zip(MODULUS.iter()) {
+            bytes.copy_from_slice(&limb.to_le_bytes());
+        }
+        assert!(bool::from(Fr::from_repr(modulus).is_none()));
+        assert!(bool::from(Fr::from_repr([0xff; 32]).is_none()));
+
+        let mut r_minus_one = modulus;
+        r_minus_one[0] -= 1;
+        assert_eq!(Fr::from_repr(r_minus_one).unwrap(), -Fr::one());
+
+        let bits = (-Fr::one()).to_le_bits();
+        let char_bits = Fr::char_le_bits();
+        assert!(!bits[0] && char_bits[0]);
+        assert_eq!(bits[1..], char_bits[1..]);
+    }
+}
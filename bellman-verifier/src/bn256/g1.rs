@@ -0,0 +1,267 @@
+use core::borrow::Borrow;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use bn::{AffineG1, Fq};
+use ff::Field;
+use group::prime::{PrimeCurve, PrimeCurveAffine, PrimeGroup};
+use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
+use pairing::PairingCurveAffine;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use super::{ct_option, fq_from_be, fq_is_larger, fq_to_be, Bn256, Fr, G2Affine, Gt, FLAGS_MASK,
+    INFINITY_FLAG, SIGN_FLAG};
+
+/// A point of G1 in Jacobian coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G1(pub(crate) bn::G1);
+
+/// A point of G1 in affine coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G1Affine(pub(crate) bn::G1);
+
+impl_curve!(G1, G1Affine, bn::G1);
+
+/// The 64 byte uncompressed encoding of a G1 point used by snarkjs and
+/// pairing_ce: `x || y`, both big-endian. The identity is all zeros except
+/// for the infinity flag `0x40` in the first byte.
+#[derive(Clone, Copy)]
+pub struct G1Uncompressed([u8; 64]);
+
+/// The 32 byte compressed encoding of a G1 point: `x` big-endian, with
+/// `0x80` set in the first byte when `y` is the larger of `y` and `-y`, and
+/// `0x40` set for the identity.
+#[derive(Clone, Copy)]
+pub struct G1Compressed([u8; 32]);
+
+impl_bytes_wrapper!(G1Uncompressed, 64);
+impl_bytes_wrapper!(G1Compressed, 32);
+
+impl_codec_uncompressed!(G1Affine, G1Uncompressed, 64);
+
+impl G1Affine {
+    /// Builds a point from affine coordinates, checking that it is on the
+    /// curve. G1 has cofactor one, so that is also the subgroup check.
+    pub fn from_xy(x: Fq, y: Fq) -> Option<G1Affine> {
+        AffineG1::new(x, y).ok().map(|p| G1Affine(p.into()))
+    }
+
+    fn xy(&self) -> Option<(Fq, Fq)> {
+        AffineG1::from_jacobian(self.0).map(|p| (p.x(), p.y()))
+    }
+}
+
+impl UncompressedEncoding for G1Affine {
+    type Uncompressed = G1Uncompressed;
+
+    fn from_uncompressed(bytes: &G1Uncompressed) -> CtOption<Self> {
+        Self::from_uncompressed_unchecked(bytes)
+    }
+
+    fn from_uncompressed_unchecked(bytes: &G1Uncompressed) -> CtOption<Self> {
+        let bytes = &bytes.0;
+        let flags = bytes[0] & FLAGS_MASK;
+
+        let point = if flags == INFINITY_FLAG {
+            if bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|b| *b == 0) {
+                Some(G1Affine::identity())
+            } else {
+                None
+            }
+        } else if flags == 0 {
+            fq_from_be(&bytes[..32])
+                .zip(fq_from_be(&bytes[32..]))
+                .and_then(|(x, y)| G1Affine::from_xy(x, y))
+        } else {
+            None
+        };
+
+        ct_option(point)
+    }
+
+    fn to_uncompressed(&self) -> G1Uncompressed {
+        let mut res = [0u8; 64];
+
+        match self.xy() {
+            Some((x, y)) => {
+                fq_to_be(x, &mut res[..32]);
+                fq_to_be(y, &mut res[32..]);
+            }
+            None => res[0] = INFINITY_FLAG,
+        }
+
+        G1Uncompressed(res)
+    }
+}
+
+impl GroupEncoding for G1Affine {
+    type Repr = G1Compressed;
+
+    fn from_bytes(bytes: &G1Compressed) -> CtOption<Self> {
+        Self::from_bytes_unchecked(bytes)
+    }
+
+    fn from_bytes_unchecked(bytes: &G1Compressed) -> CtOption<Self> {
+        let bytes = &bytes.0;
+
+        if bytes[0] & INFINITY_FLAG != 0 {
+            let point = if bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|b| *b == 0) {
+                Some(G1Affine::identity())
+            } else {
+                None
+            };
+            return ct_option(point);
+        }
+
+        let larger = bytes[0] & SIGN_FLAG != 0;
+        let mut x = *bytes;
+        x[0] &= !SIGN_FLAG;
+
+        let point = fq_from_be(&x).and_then(|x| {
+            let y = (x * x * x + bn::G1::b()).sqrt()?;
+            let y = if fq_is_larger(y) == larger { y } else { -y };
+            G1Affine::from_xy(x, y)
+        });
+
+        ct_option(point)
+    }
+
+    fn to_bytes(&self) -> G1Compressed {
+        let mut res = [0u8; 32];
+
+        match self.xy() {
+            Some((x, y)) => {
+                fq_to_be(x, &mut res);
+                if fq_is_larger(y) {
+                    res[0] |= SIGN_FLAG;
+                }
+            }
+            None => res[0] = INFINITY_FLAG,
+        }
+
+        G1Compressed(res)
+    }
+}
+
+impl GroupEncoding for G1 {
+    type Repr = G1Compressed;
+
+    fn from_bytes(bytes: &G1Compressed) -> CtOption<Self> {
+        G1Affine::from_bytes(bytes).map(G1::from)
+    }
+
+    fn from_bytes_unchecked(bytes: &G1Compressed) -> CtOption<Self> {
+        G1Affine::from_bytes_unchecked(bytes).map(G1::from)
+    }
+
+    fn to_bytes(&self) -> G1Compressed {
+        self.to_affine().to_bytes()
+    }
+}
+
+impl PairingCurveAffine for G1Affine {
+    type Pair = G2Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &G2Affine) -> Gt {
+        <Bn256 as pairing::Engine>::pairing(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bn::Fq;
+    use ff::Field;
+    use group::prime::PrimeCurveAffine;
+    use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::super::{Fr, INFINITY_FLAG, SIGN_FLAG};
+    use super::{G1Affine, G1Compressed, G1Uncompressed, G1};
+
+    #[test]
+    fn group_law() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6f01);
+        let (p, q) = (G1::random(&mut rng), G1::random(&mut rng));
+        let (a, b) = (Fr::random(&mut rng), Fr::random(&mut rng));
+
+        assert_eq!(p + q, q + p);
+        assert_eq!(p - p, G1::identity());
+        assert_eq!(p.double(), p + p);
+        assert_eq!(p * (a + b), p * a + p * b);
+        assert_eq!(G1::generator() * -Fr::one(), -G1::generator());
+        assert_eq!((p + q).to_affine(), (q + p.to_affine()).to_affine());
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6f02);
+        let points = (0..20)
+            .map(|_| G1::random(&mut rng).to_affine())
+            .chain([G1Affine::identity(), G1Affine::generator(), -G1Affine::generator()]);
+
+        for p in points {
+            assert_eq!(G1Affine::from_uncompressed(&p.to_uncompressed()).unwrap(), p);
+            assert_eq!(G1Affine::from_bytes(&p.to_bytes()).unwrap(), p);
+        }
+
+        // The generator is (1, 2), with y the smaller root.
+        let mut expected = [0u8; 64];
+        expected[31] = 1;
+        expected[63] = 2;
+        assert_eq!(G1Affine::generator().to_uncompressed().as_ref(), &expected[..]);
+        assert_eq!(G1Affine::generator().to_bytes().as_ref()[0] & SIGN_FLAG, 0);
+        assert_ne!((-G1Affine::generator()).to_bytes().as_ref()[0] & SIGN_FLAG, 0);
+        assert_eq!(G1Affine::identity().to_uncompressed().as_ref()[0], INFINITY_FLAG);
+    }
+
+    #[test]
+    fn rejects_invalid_encodings() {
+        let generator = G1Affine::generator().to_uncompressed();
+        let decode = |bytes: [u8; 64]| {
+            bool::from(G1Affine::from_uncompressed(&G1Uncompressed(bytes)).is_some())
+        };
+        let bytes = |p: &G1Uncompressed| p.0;
+
+        // (1, 3) is not on the curve.
+        let mut off_curve = bytes(&generator);
+        off_curve[63] = 3;
+        assert!(!decode(off_curve));
+
+        // x = 1 + q is x = 1 but not canonical.
+        let mut q_plus_one = [0u8; 32];
+        Fq::modulus().to_big_endian(&mut q_plus_one).unwrap();
+        q_plus_one[31] += 1;
+        let mut non_canonical = bytes(&generator);
+        non_canonical[..32].copy_from_slice(&q_plus_one);
+        assert!(!decode(non_canonical));
+
+        // Flags are not allowed on uncompressed points, and the identity
+        // must be all zeros otherwise.
+        for flag in [SIGN_FLAG, INFINITY_FLAG, SIGN_FLAG | INFINITY_FLAG] {
+            let mut flagged = bytes(&generator);
+            flagged[0] |= flag;
+            assert!(!decode(flagged));
+        }
+        let mut identity = [0u8; 64];
+        identity[0] = INFINITY_FLAG;
+        assert!(decode(identity));
+        identity[63] = 1;
+        assert!(!decode(identity));
+        assert!(!decode([0u8; 64]));
+
+        let decode_compressed = |bytes: [u8; 32]| {
+            bool::from(G1Affine::from_bytes(&G1Compressed(bytes)).is_some())
+        };
+        // x = 0 gives y^2 = 3, which has no root.
+        assert!(!decode_compressed([0u8; 32]));
+        assert!(!decode_compressed(q_plus_one));
+        let mut identity = [0u8; 32];
+        identity[0] = INFINITY_FLAG;
+        assert!(decode_compressed(identity));
+        identity[0] |= SIGN_FLAG;
+        assert!(!decode_compressed(identity));
+    }
+}
@@ -0,0 +1,291 @@
+use core::borrow::Borrow;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use bn::{AffineG2, Fq2};
+use ff::Field;
+use group::prime::{PrimeCurve, PrimeCurveAffine, PrimeGroup};
+use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
+use pairing::PairingCurveAffine;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use super::{ct_option, fq2_from_be, fq2_is_larger, fq2_to_be, Bn256, Fr, G1Affine, Gt, FLAGS_MASK,
+    INFINITY_FLAG, SIGN_FLAG};
+
+/// A point of G2 in Jacobian coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G2(pub(crate) bn::G2);
+
+/// A point of G2 in affine coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G2Affine(pub(crate) bn::G2);
+
+impl_curve!(G2, G2Affine, bn::G2);
+
+/// The 128 byte uncompressed encoding of a G2 point used by snarkjs and
+/// pairing_ce: `x.c1 || x.c0 || y.c1 || y.c0`, all big-endian. The identity
+/// is all zeros except for the infinity flag `0x40` in the first byte.
+#[derive(Clone, Copy)]
+pub struct G2Uncompressed([u8; 128]);
+
+/// The 64 byte compressed encoding of a G2 point: `x.c1 || x.c0`
+/// big-endian, with `0x80` set in the first byte when `y` is the larger of
+/// `y` and `-y`, and `0x40` set for the identity.
+#[derive(Clone, Copy)]
+pub struct G2Compressed([u8; 64]);
+
+impl_bytes_wrapper!(G2Uncompressed, 128);
+impl_bytes_wrapper!(G2Compressed, 64);
+
+impl_codec_uncompressed!(G2Affine, G2Uncompressed, 128);
+
+impl G2Affine {
+    /// Builds a point from affine coordinates, checking that it is on the
+    /// curve and in the prime order subgroup.
+    pub fn from_xy(x: Fq2, y: Fq2) -> Option<G2Affine> {
+        AffineG2::new(x, y).ok().map(|p| G2Affine(p.into()))
+    }
+
+    /// Like `from_xy`, but only checks that the point is on the curve.
+    fn from_xy_unchecked(x: Fq2, y: Fq2) -> Option<G2Affine> {
+        if y * y == x * x * x + bn::G2::b() {
+            Some(G2Affine(bn::G2::new(x, y, Fq2::one())))
+        } else {
+            None
+        }
+    }
+
+    fn xy(&self) -> Option<(Fq2, Fq2)> {
+        AffineG2::from_jacobian(self.0).map(|p| (p.x(), p.y()))
+    }
+
+    fn from_uncompressed_inner(
+        bytes: &G2Uncompressed,
+        from_xy: fn(Fq2, Fq2) -> Option<G2Affine>,
+    ) -> CtOption<Self> {
+        let bytes = &bytes.0;
+        let flags = bytes[0] & FLAGS_MASK;
+
+        let point = if flags == INFINITY_FLAG {
+            if bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|b| *b == 0) {
+                Some(G2Affine::identity())
+            } else {
+                None
+            }
+        } else if flags == 0 {
+            fq2_from_be(&bytes[..64])
+                .zip(fq2_from_be(&bytes[64..]))
+                .and_then(|(x, y)| from_xy(x, y))
+        } else {
+            None
+        };
+
+        ct_option(point)
+    }
+
+    fn from_compressed_inner(
+        bytes: &G2Compressed,
+        from_xy: fn(Fq2, Fq2) -> Option<G2Affine>,
+    ) -> CtOption<Self> {
+        let bytes = &bytes.0;
+
+        if bytes[0] & INFINITY_FLAG != 0 {
+            let point = if bytes[0] == INFINITY_FLAG && bytes[1..].iter().all(|b| *b == 0) {
+                Some(G2Affine::identity())
+            } else {
+                None
+            };
+            return ct_option(point);
+        }
+
+        let larger = bytes[0] & SIGN_FLAG != 0;
+        let mut x = *bytes;
+        x[0] &= !SIGN_FLAG;
+
+        let point = fq2_from_be(&x).and_then(|x| {
+            let y = (x * x * x + bn::G2::b()).sqrt()?;
+            let y = if fq2_is_larger(y) == larger { y } else { -y };
+            from_xy(x, y)
+        });
+
+        ct_option(point)
+    }
+}
+
+impl UncompressedEncoding for G2Affine {
+    type Uncompressed = G2Uncompressed;
+
+    fn from_uncompressed(bytes: &G2Uncompressed) -> CtOption<Self> {
+        Self::from_uncompressed_inner(bytes, G2Affine::from_xy)
+    }
+
+    fn from_uncompressed_unchecked(bytes: &G2Uncompressed) -> CtOption<Self> {
+        Self::from_uncompressed_inner(bytes, G2Affine::from_xy_unchecked)
+    }
+
+    fn to_uncompressed(&self) -> G2Uncompressed {
+        let mut res = [0u8; 128];
+
+        match self.xy() {
+            Some((x, y)) => {
+                fq2_to_be(x, &mut res[..64]);
+                fq2_to_be(y, &mut res[64..]);
+            }
+            None => res[0] = INFINITY_FLAG,
+        }
+
+        G2Uncompressed(res)
+    }
+}
+
+impl GroupEncoding for G2Affine {
+    type Repr = G2Compressed;
+
+    fn from_bytes(bytes: &G2Compressed) -> CtOption<Self> {
+        Self::from_compressed_inner(bytes, G2Affine::from_xy)
+    }
+
+    fn from_bytes_unchecked(bytes: &G2Compressed) -> CtOption<Self> {
+        Self::from_compressed_inner(bytes, G2Affine::from_xy_unchecked)
+    }
+
+    fn to_bytes(&self) -> G2Compressed {
+        let mut res = [0u8; 64];
+
+        match self.xy() {
+            Some((x, y)) => {
+                fq2_to_be(x, &mut res);
+                if fq2_is_larger(y) {
+                    res[0] |= SIGN_FLAG;
+                }
+            }
+            None => res[0] = INFINITY_FLAG,
+        }
+
+        G2Compressed(res)
+    }
+}
+
+impl GroupEncoding for G2 {
+    type Repr = G2Compressed;
+
+    fn from_bytes(bytes: &G2Compressed) -> CtOption<Self> {
+        G2Affine::from_bytes(bytes).map(G2::from)
+    }
+
+    fn from_bytes_unchecked(bytes: &G2Compressed) -> CtOption<Self> {
+        G2Affine::from_bytes_unchecked(bytes).map(G2::from)
+    }
+
+    fn to_bytes(&self) -> G2Compressed {
+        self.to_affine().to_bytes()
+    }
+}
+
+impl PairingCurveAffine for G2Affine {
+    type Pair = G1Affine;
+    type PairingResult = Gt;
+
+    fn pairing_with(&self, other: &G1Affine) -> Gt {
+        <Bn256 as pairing::Engine>::pairing(other, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bn::{Fq, Fq2};
+    use ff::Field;
+    use group::prime::PrimeCurveAffine;
+    use group::{Curve, Group, GroupEncoding, UncompressedEncoding};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::super::fq6::fq;
+    use super::super::{fq2_to_be, Fr, INFINITY_FLAG, SIGN_FLAG};
+    use super::{G2Affine, G2Compressed, G2Uncompressed, G2};
+
+    /// A point on the twist outside of the prime order subgroup.
+    fn off_subgroup() -> (Fq2, Fq2) {
+        (1..)
+            .map(|i| Fq2::new(fq([i, 0, 0, 0]), Fq::one()))
+            .find_map(|x| (x * x * x + bn::G2::b()).sqrt().map(|y| (x, y)))
+            .unwrap()
+    }
+
+    #[test]
+    fn group_law() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6f03);
+        let (p, q) = (G2::random(&mut rng), G2::random(&mut rng));
+        let (a, b) = (Fr::random(&mut rng), Fr::random(&mut rng));
+
+        assert_eq!(p + q, q + p);
+        assert_eq!(p - p, G2::identity());
+        assert_eq!(p.double(), p + p);
+        assert_eq!(p * (a + b), p * a + p * b);
+        assert_eq!(G2::generator() * -Fr::one(), -G2::generator());
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6f04);
+        let points = (0..20)
+            .map(|_| G2::random(&mut rng).to_affine())
+            .chain([G2Affine::identity(), G2Affine::generator(), -G2Affine::generator()]);
+
+        for p in points {
+            assert_eq!(G2Affine::from_uncompressed(&p.to_uncompressed()).unwrap(), p);
+            assert_eq!(G2Affine::from_bytes(&p.to_bytes()).unwrap(), p);
+            assert_eq!(
+                (p.to_bytes().0[0] & SIGN_FLAG != 0),
+                (-p).to_bytes().0[0] & SIGN_FLAG == 0 && !bool::from(p.is_identity())
+            );
+        }
+        assert_eq!(G2Affine::identity().to_bytes().0[0], INFINITY_FLAG);
+    }
+
+    #[test]
+    fn rejects_invalid_encodings() {
+        let generator = G2Affine::generator().to_uncompressed().0;
+        let decode = |bytes: [u8; 128]| {
+            bool::from(G2Affine::from_uncompressed(&G2Uncompressed(bytes)).is_some())
+        };
+
+        let mut off_curve = generator;
+        off_curve[127] ^= 1;
+        assert!(!decode(off_curve));
+
+        // x.c1 = q is not canonical.
+        let mut non_canonical = generator;
+        Fq::modulus().to_big_endian(&mut non_canonical[..32]).unwrap();
+        assert!(!decode(non_canonical));
+
+        for flag in [SIGN_FLAG, INFINITY_FLAG, SIGN_FLAG | INFINITY_FLAG] {
+            let mut flagged = generator;
+            flagged[0] |= flag;
+            assert!(!decode(flagged));
+        }
+        let mut identity = [0u8; 128];
+        identity[0] = INFINITY_FLAG;
+        assert!(decode(identity));
+        identity[64] = 1;
+        assert!(!decode(identity));
+
+        // On the curve but not in the subgroup: only the unchecked
+        // decoders accept it.
+        let (x, y) = off_subgroup();
+        let mut bytes = [0u8; 128];
+        fq2_to_be(x, &mut bytes[..64]);
+        fq2_to_be(y, &mut bytes[64..]);
+        assert!(!decode(bytes));
+        assert!(bool::from(
+            G2Affine::from_uncompressed_unchecked(&G2Uncompressed(bytes)).is_some()
+        ));
+        let mut compressed = [0u8; 64];
+        compressed.copy_from_slice(&bytes[..64]);
+        assert!(bool::from(G2Affine::from_bytes(&G2Compressed(compressed)).is_none()));
+        assert!(bool::from(
+            G2Affine::from_bytes_unchecked(&G2Compressed(compressed)).is_some()
+        ));
+    }
+}
@@ -0,0 +1,466 @@
+// Operator boilerplate in the style of the bls12_381 crate: every type
+// implements its operators on references, these macros derive the owned
+// variants and the `*Assign` traits from them.
+
+macro_rules! impl_add_binop_specify_output {
+    ($lhs:ident, $rhs:ident, $output:ident) => {
+        impl<'b> Add<&'b $rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn add(self, rhs: &'b $rhs) -> $output {
+                &self + rhs
+            }
+        }
+
+        impl<'a> Add<$rhs> for &'a $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn add(self, rhs: $rhs) -> $output {
+                self + &rhs
+            }
+        }
+
+        impl Add<$rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn add(self, rhs: $rhs) -> $output {
+                &self + &rhs
+            }
+        }
+    };
+}
+
+macro_rules! impl_sub_binop_specify_output {
+    ($lhs:ident, $rhs:ident, $output:ident) => {
+        impl<'b> Sub<&'b $rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn sub(self, rhs: &'b $rhs) -> $output {
+                &self - rhs
+            }
+        }
+
+        impl<'a> Sub<$rhs> for &'a $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn sub(self, rhs: $rhs) -> $output {
+                self - &rhs
+            }
+        }
+
+        impl Sub<$rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn sub(self, rhs: $rhs) -> $output {
+                &self - &rhs
+            }
+        }
+    };
+}
+
+macro_rules! impl_binops_additive {
+    ($lhs:ident, $rhs:ident) => {
+        impl_add_binop_specify_output!($lhs, $rhs, $lhs);
+        impl_sub_binop_specify_output!($lhs, $rhs, $lhs);
+
+        impl SubAssign<$rhs> for $lhs {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $rhs) {
+                *self = &*self - &rhs;
+            }
+        }
+
+        impl AddAssign<$rhs> for $lhs {
+            #[inline]
+            fn add_assign(&mut self, rhs: $rhs) {
+                *self = &*self + &rhs;
+            }
+        }
+
+        impl<'b> SubAssign<&'b $rhs> for $lhs {
+            #[inline]
+            fn sub_assign(&mut self, rhs: &'b $rhs) {
+                *self = &*self - rhs;
+            }
+        }
+
+        impl<'b> AddAssign<&'b $rhs> for $lhs {
+            #[inline]
+            fn add_assign(&mut self, rhs: &'b $rhs) {
+                *self = &*self + rhs;
+            }
+        }
+    };
+}
+
+macro_rules! impl_binops_multiplicative_mixed {
+    ($lhs:ident, $rhs:ident, $output:ident) => {
+        impl<'b> Mul<&'b $rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn mul(self, rhs: &'b $rhs) -> $output {
+                &self * rhs
+            }
+        }
+
+        impl<'a> Mul<$rhs> for &'a $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn mul(self, rhs: $rhs) -> $output {
+                self * &rhs
+            }
+        }
+
+        impl Mul<$rhs> for $lhs {
+            type Output = $output;
+
+            #[inline]
+            fn mul(self, rhs: $rhs) -> $output {
+                &self * &rhs
+            }
+        }
+    };
+}
+
+macro_rules! impl_binops_multiplicative {
+    ($lhs:ident, $rhs:ident) => {
+        impl_binops_multiplicative_mixed!($lhs, $rhs, $lhs);
+
+        impl MulAssign<$rhs> for $lhs {
+            #[inline]
+            fn mul_assign(&mut self, rhs: $rhs) {
+                *self = &*self * &rhs;
+            }
+        }
+
+        impl<'b> MulAssign<&'b $rhs> for $lhs {
+            #[inline]
+            fn mul_assign(&mut self, rhs: &'b $rhs) {
+                *self = &*self * rhs;
+            }
+        }
+    };
+}
+
+macro_rules! impl_sum {
+    ($t:ident) => {
+        impl<T: Borrow<$t>> Sum<T> for $t {
+            fn sum<I>(iter: I) -> Self
+            where
+                I: Iterator<Item = T>,
+            {
+                iter.fold(Self::identity(), |acc, item| acc + item.borrow())
+            }
+        }
+    };
+}
+
+// Arithmetic and the `group` traits shared by G1 and G2. `$projective` and
+// `$affine` wrap the same `substrate-bn` Jacobian type, the affine one is
+// kept normalized (`z = 1`, or the identity). Like `Fr`, the `subtle` impls
+// are not actually constant time.
+macro_rules! impl_curve {
+    ($projective:ident, $affine:ident, $inner:ty) => {
+        impl Default for $projective {
+            fn default() -> Self {
+                $projective::identity()
+            }
+        }
+
+        impl Default for $affine {
+            fn default() -> Self {
+                $affine::identity()
+            }
+        }
+
+        impl ConditionallySelectable for $projective {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                if bool::from(choice) {
+                    *b
+                } else {
+                    *a
+                }
+            }
+        }
+
+        impl ConditionallySelectable for $affine {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                if bool::from(choice) {
+                    *b
+                } else {
+                    *a
+                }
+            }
+        }
+
+        impl ConstantTimeEq for $projective {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                Choice::from((self == other) as u8)
+            }
+        }
+
+        impl ConstantTimeEq for $affine {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                Choice::from((self == other) as u8)
+            }
+        }
+
+        impl From<&$affine> for $projective {
+            fn from(p: &$affine) -> $projective {
+                $projective(p.0)
+            }
+        }
+
+        impl From<$affine> for $projective {
+            fn from(p: $affine) -> $projective {
+                $projective::from(&p)
+            }
+        }
+
+        impl From<&$projective> for $affine {
+            fn from(p: &$projective) -> $affine {
+                let mut inner = p.0;
+                bn::Group::normalize(&mut inner);
+                $affine(inner)
+            }
+        }
+
+        impl From<$projective> for $affine {
+            fn from(p: $projective) -> $affine {
+                $affine::from(&p)
+            }
+        }
+
+        impl Neg for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn neg(self) -> $projective {
+                $projective(-self.0)
+            }
+        }
+
+        impl Neg for $projective {
+            type Output = $projective;
+
+            #[inline]
+            fn neg(self) -> $projective {
+                -&self
+            }
+        }
+
+        impl Neg for &$affine {
+            type Output = $affine;
+
+            #[inline]
+            fn neg(self) -> $affine {
+                $affine(-self.0)
+            }
+        }
+
+        impl Neg for $affine {
+            type Output = $affine;
+
+            #[inline]
+            fn neg(self) -> $affine {
+                -&self
+            }
+        }
+
+        impl Add<&$projective> for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn add(self, rhs: &$projective) -> $projective {
+                $projective(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub<&$projective> for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn sub(self, rhs: &$projective) -> $projective {
+                $projective(self.0 - rhs.0)
+            }
+        }
+
+        impl Add<&$affine> for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn add(self, rhs: &$affine) -> $projective {
+                $projective(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub<&$affine> for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn sub(self, rhs: &$affine) -> $projective {
+                $projective(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul<&Fr> for &$projective {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: &Fr) -> $projective {
+                $projective(self.0 * rhs.0)
+            }
+        }
+
+        impl Mul<&Fr> for &$affine {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: &Fr) -> $projective {
+                $projective(self.0 * rhs.0)
+            }
+        }
+
+        impl_binops_additive!($projective, $projective);
+        impl_binops_additive!($projective, $affine);
+        impl_binops_multiplicative!($projective, Fr);
+        impl_binops_multiplicative_mixed!($affine, Fr, $projective);
+        impl_sum!($projective);
+
+        impl Group for $projective {
+            type Scalar = Fr;
+
+            fn random(mut rng: impl RngCore) -> Self {
+                loop {
+                    let s = Fr::random(&mut rng);
+                    if !bool::from(s.is_zero()) {
+                        return $projective::generator() * s;
+                    }
+                }
+            }
+
+            fn identity() -> Self {
+                $projective(<$inner as bn::Group>::zero())
+            }
+
+            fn generator() -> Self {
+                $projective(<$inner as bn::Group>::one())
+            }
+
+            fn is_identity(&self) -> Choice {
+                Choice::from(bn::Group::is_zero(&self.0) as u8)
+            }
+
+            fn double(&self) -> Self {
+                self + self
+            }
+        }
+
+        impl Curve for $projective {
+            type AffineRepr = $affine;
+
+            fn to_affine(&self) -> $affine {
+                $affine::from(self)
+            }
+        }
+
+        impl PrimeGroup for $projective {}
+
+        impl PrimeCurve for $projective {
+            type Affine = $affine;
+        }
+
+        impl PrimeCurveAffine for $affine {
+            type Scalar = Fr;
+            type Curve = $projective;
+
+            fn identity() -> Self {
+                $affine(<$inner as bn::Group>::zero())
+            }
+
+            fn generator() -> Self {
+                $affine(<$inner as bn::Group>::one())
+            }
+
+            fn is_identity(&self) -> Choice {
+                Choice::from(bn::Group::is_zero(&self.0) as u8)
+            }
+
+            fn to_curve(&self) -> $projective {
+                $projective::from(self)
+            }
+        }
+    };
+}
+
+// `Default`, `AsRef`/`AsMut` and `Debug` for the fixed size point encodings.
+macro_rules! impl_bytes_wrapper {
+    ($name:ident, $len:expr) => {
+        impl Default for $name {
+            fn default() -> Self {
+                $name([0u8; $len])
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $name {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0[..].fmt(f)
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                $name(bytes)
+            }
+        }
+    };
+}
+
+// SCALE `Encode`/`Decode` for an affine point as its uncompressed encoding,
+// the layout of `verifier_traits` and of the `borsh` feature, so that a
+// `Proof<Bn256>` is the same bytes under all three. Decoding checks the
+// point like `from_uncompressed`.
+macro_rules! impl_codec_uncompressed {
+    ($affine:ident, $uncompressed:ident, $len:expr) => {
+        impl codec::Encode for $affine {
+            fn size_hint(&self) -> usize {
+                $len
+            }
+
+            fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+                dest.write(self.to_uncompressed().as_ref());
+            }
+        }
+
+        impl codec::EncodeLike for $affine {}
+
+        impl codec::Decode for $affine {
+            fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+                let mut bytes = $uncompressed::default();
+                input.read(bytes.as_mut())?;
+                Option::from($affine::from_uncompressed(&bytes))
+                    .ok_or_else(|| "invalid curve point".into())
+            }
+        }
+    };
+}
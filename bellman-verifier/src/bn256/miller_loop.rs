@@ -0,0 +1,161 @@
+//! The optimal ate Miller loop with G2 line coefficients computed once per
+//! prepared point, as `substrate-bn` does internally.
+
+use bn::{AffineG1, AffineG2, Fq, Fq2};
+
+use super::fq12::Fq12;
+use super::fq6::{fq, fq2, fq2_conjugate, fq2_scale, xi, Limbs};
+use super::{G1Affine, G2Affine};
+
+use sp_std::prelude::*;
+
+/// The non-adjacent form of the ate loop count `6z + 2`, most significant
+/// digit first and without the leading one, with 3 standing for -1.
+const ATE_LOOP_COUNT_NAF: [u8; 64] = [
+    1, 0, 1, 0, 0, 0, 3, 0, 3, 0, 0, 0, 3, 0, 1, 0, 3, 0, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 3, 0, 1,
+    0, 0, 3, 0, 0, 0, 0, 3, 0, 1, 0, 0, 0, 3, 0, 3, 0, 0, 1, 0, 0, 0, 3, 0, 0, 3, 0, 1, 0, 1, 0,
+    0, 0,
+];
+
+/// `xi^((q - 1) / 3)` and `xi^((q - 1) / 2)`, which map the Frobenius of the
+/// twist back onto it.
+const TWIST_MUL_BY_Q_X: (Limbs, Limbs) = (
+    [0x99e3_9557_176f_553d, 0xb78c_c310_c2c3_330c, 0x4c0b_ec3c_f559_b143, 0x2fb3_4798_4f79_11f7],
+    [0x1665_d51c_640f_cba2, 0x32ae_2a1d_0b7c_9dce, 0x4ba4_cc8b_d75a_0794, 0x16c9_e550_61eb_ae20],
+);
+const TWIST_MUL_BY_Q_Y: (Limbs, Limbs) = (
+    [0xdc54_0146_71a0_135a, 0xdbaa_e0ed_a9c9_5998, 0xdc5e_c698_b6e2_f9b9, 0x063c_f305_489a_f5dc],
+    [0x82d3_7f63_2623_b0e3, 0x2180_7dc9_8fa2_5bd2, 0x0704_b5a7_ec79_6f2b, 0x07c0_3cbc_ac41_049a],
+);
+
+/// The coefficients of one line of the Miller loop, to be scaled by the
+/// coordinates of the G1 point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct EllCoeffs {
+    ell_0: Fq2,
+    ell_vw: Fq2,
+    ell_vv: Fq2,
+}
+
+/// A G2 point prepared for the Miller loop: the 87 line coefficients of its
+/// doubling and addition steps, so that every further Miller loop with it
+/// only evaluates them at the G1 point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct G2Prepared {
+    /// Empty for the identity, whose pairing is one.
+    coeffs: Vec<EllCoeffs>,
+}
+
+impl From<G2Affine> for G2Prepared {
+    fn from(q: G2Affine) -> G2Prepared {
+        let q = match AffineG2::from_jacobian(q.0) {
+            Some(q) => (q.x(), q.y()),
+            None => return G2Prepared { coeffs: Vec::new() },
+        };
+        let neg_q = (q.0, -q.1);
+        let two_inv = fq([2, 0, 0, 0]).inverse().expect("two is invertible");
+        let mut r = (q.0, q.1, Fq2::one());
+        let mut coeffs = Vec::with_capacity(87);
+
+        for digit in ATE_LOOP_COUNT_NAF.iter() {
+            coeffs.push(doubling_step(&mut r, two_inv));
+            match digit {
+                1 => coeffs.push(addition_step(&mut r, q)),
+                3 => coeffs.push(addition_step(&mut r, neg_q)),
+                _ => {}
+            }
+        }
+
+        let q1 = mul_by_q(q);
+        let q2 = mul_by_q(q1);
+        coeffs.push(addition_step(&mut r, q1));
+        coeffs.push(addition_step(&mut r, (q2.0, -q2.1)));
+
+        G2Prepared { coeffs }
+    }
+}
+
+/// The untwist-Frobenius-twist endomorphism on affine coordinates.
+fn mul_by_q((x, y): (Fq2, Fq2)) -> (Fq2, Fq2) {
+    (
+        fq2(TWIST_MUL_BY_Q_X) * fq2_conjugate(x),
+        fq2(TWIST_MUL_BY_Q_Y) * fq2_conjugate(y),
+    )
+}
+
+/// Doubles `r`, in homogeneous projective coordinates, and returns the
+/// tangent line.
+fn doubling_step(r: &mut (Fq2, Fq2, Fq2), two_inv: Fq) -> EllCoeffs {
+    let (x, y, z) = *r;
+    let b = bn::G2::b();
+
+    let a = fq2_scale(x * y, two_inv);
+    let bb = y * y;
+    let c = z * z;
+    let d = c + c + c;
+    let e = b * d;
+    let f = e + e + e;
+    let g = fq2_scale(bb + f, two_inv);
+    let h = (y + z) * (y + z) - (bb + c);
+    let i = e - bb;
+    let j = x * x;
+    let e_sq = e * e;
+
+    *r = (a * (bb - f), g * g - (e_sq + e_sq + e_sq), bb * h);
+
+    EllCoeffs {
+        ell_0: xi() * i,
+        ell_vw: -h,
+        ell_vv: j + j + j,
+    }
+}
+
+/// Adds the affine `base` to `r` and returns the line through both.
+fn addition_step(r: &mut (Fq2, Fq2, Fq2), base: (Fq2, Fq2)) -> EllCoeffs {
+    let (x, y, z) = *r;
+    let d = x - z * base.0;
+    let e = y - z * base.1;
+    let f = d * d;
+    let g = e * e;
+    let h = d * f;
+    let i = x * f;
+    let j = z * g + h - (i + i);
+
+    *r = (d * j, e * (i - j) - h * y, z * h);
+
+    EllCoeffs {
+        ell_0: xi() * (e * base.0 - d * base.1),
+        ell_vv: -e,
+        ell_vw: d,
+    }
+}
+
+/// `prod f_{6z+2,Q}(P)` over `terms`, skipping the pairs with an identity.
+pub(super) fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Fq12 {
+    let pairs = terms
+        .iter()
+        .filter(|(_, q)| !q.coeffs.is_empty())
+        .filter_map(|(p, q)| AffineG1::from_jacobian(p.0).map(|p| (p.x(), p.y(), *q)))
+        .collect::<Vec<_>>();
+
+    let ell = |f: Fq12, idx: usize| {
+        pairs.iter().fold(f, |f, (x, y, q)| {
+            let c = &q.coeffs[idx];
+            f.mul_by_024(c.ell_0, fq2_scale(c.ell_vw, *y), fq2_scale(c.ell_vv, *x))
+        })
+    };
+
+    let mut f = Fq12::one();
+    let mut idx = 0;
+    for digit in ATE_LOOP_COUNT_NAF.iter() {
+        f = ell(f.square(), idx);
+        idx += 1;
+        if *digit != 0 {
+            f = ell(f, idx);
+            idx += 1;
+        }
+    }
+
+    f = ell(f, idx);
+    ell(f, idx + 1)
+}
@@ -0,0 +1,369 @@
+//! The BN254 (alt_bn128, "bn128" in snarkjs) engine, implementing the
+//! `pairing`/`group` traits on top of `substrate-bn`, so that the generic
+//! verifier accepts Circom/snarkjs proofs without the pairing_ce fork.
+//!
+//! `substrate-bn` is not constant time. Nothing secret is handled during
+//! verification, but the types here must not be used for proving.
+
+#[macro_use]
+mod macros;
+
+mod fq12;
+mod fq6;
+mod fr;
+mod g1;
+mod g2;
+mod miller_loop;
+mod snarkjs;
+
+pub use self::fq12::Fq12;
+pub use self::fq6::Fq6;
+pub use self::fr::Fr;
+pub use self::g1::{G1Affine, G1Compressed, G1Uncompressed, G1};
+pub use self::g2::{G2Affine, G2Compressed, G2Uncompressed, G2};
+pub use self::miller_loop::G2Prepared;
+pub use self::snarkjs::*;
+
+pub use bn::{Fq, Fq2};
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::Group;
+use pairing::{Engine, MillerLoopResult as _, MultiMillerLoop};
+use rand_core::RngCore;
+use subtle::{Choice, CtOption};
+
+use sp_std::prelude::*;
+
+/// Set in the first byte of a compressed point when `y` is the larger root.
+const SIGN_FLAG: u8 = 0x80;
+/// Set in the first byte of an encoded identity.
+const INFINITY_FLAG: u8 = 0x40;
+const FLAGS_MASK: u8 = SIGN_FLAG | INFINITY_FLAG;
+
+fn ct_option<T: Default>(value: Option<T>) -> CtOption<T> {
+    match value {
+        Some(v) => CtOption::new(v, Choice::from(1)),
+        None => CtOption::new(T::default(), Choice::from(0)),
+    }
+}
+
+/// Reads a canonical big-endian base field element.
+fn fq_from_be(bytes: &[u8]) -> Option<Fq> {
+    Fq::from_slice(bytes).ok()
+}
+
+fn fq_to_be(fq: Fq, out: &mut [u8]) {
+    fq.into_u256()
+        .to_big_endian(out)
+        .expect("buffer is 32 bytes");
+}
+
+/// Whether `y` is lexicographically larger than `-y`.
+fn fq_is_larger(y: Fq) -> bool {
+    y.into_u256() > (-y).into_u256()
+}
+
+/// Reads `c1 || c0`, both big-endian.
+fn fq2_from_be(bytes: &[u8]) -> Option<Fq2> {
+    let c1 = fq_from_be(&bytes[..32])?;
+    let c0 = fq_from_be(&bytes[32..])?;
+    Some(Fq2::new(c0, c1))
+}
+
+fn fq2_to_be(fq2: Fq2, out: &mut [u8]) {
+    fq_to_be(fq2.imaginary(), &mut out[..32]);
+    fq_to_be(fq2.real(), &mut out[32..]);
+}
+
+/// Whether `y` is lexicographically larger than `-y`, comparing `c1` first.
+fn fq2_is_larger(y: Fq2) -> bool {
+    let neg = -y;
+    (y.imaginary().into_u256(), y.real().into_u256())
+        > (neg.imaginary().into_u256(), neg.real().into_u256())
+}
+
+/// The BN254 pairing engine.
+#[derive(Clone, Debug)]
+pub struct Bn256;
+
+impl Engine for Bn256 {
+    type Fr = Fr;
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type Gt = Gt;
+
+    fn pairing(p: &G1Affine, q: &G2Affine) -> Gt {
+        Bn256::multi_miller_loop(&[(p, &G2Prepared::from(*q))]).final_exponentiation()
+    }
+}
+
+impl MultiMillerLoop for Bn256 {
+    type G2Prepared = G2Prepared;
+    type Result = MillerLoopResult;
+
+    fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopResult {
+        MillerLoopResult(miller_loop::multi_miller_loop(terms))
+    }
+}
+
+/// The output of a Miller loop, before the final exponentiation.
+#[derive(Clone, Copy)]
+pub struct MillerLoopResult(Fq12);
+
+impl Default for MillerLoopResult {
+    fn default() -> Self {
+        MillerLoopResult(Fq12::one())
+    }
+}
+
+impl fmt::Debug for MillerLoopResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MillerLoopResult")
+    }
+}
+
+impl Add<&MillerLoopResult> for &MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn add(self, rhs: &MillerLoopResult) -> MillerLoopResult {
+        MillerLoopResult(self.0 * rhs.0)
+    }
+}
+
+impl_add_binop_specify_output!(MillerLoopResult, MillerLoopResult, MillerLoopResult);
+
+impl AddAssign<MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl<'b> AddAssign<&'b MillerLoopResult> for MillerLoopResult {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'b MillerLoopResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl pairing::MillerLoopResult for MillerLoopResult {
+    type Gt = Gt;
+
+    /// A Miller loop over points off the curve, which keys decoded without
+    /// checks can hold, may give zero. It maps to the zero of Fq12, which is
+    /// not in `Gt` and stays zero through its operations, so it is never the
+    /// identity and `verify_proof` rejects it.
+    fn final_exponentiation(&self) -> Gt {
+        Gt(self.0.final_exponentiation().unwrap_or_else(Fq12::zero))
+    }
+}
+
+/// An element of the target group, written additively like the other
+/// `pairing` engines: `+` multiplies in Fq12 and `* Fr` exponentiates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gt(Fq12);
+
+impl Default for Gt {
+    fn default() -> Self {
+        Gt::identity()
+    }
+}
+
+impl Neg for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        // Gt is in the cyclotomic subgroup, where the conjugate is the inverse.
+        Gt(self.0.conjugate())
+    }
+}
+
+impl Neg for Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn neg(self) -> Gt {
+        -&self
+    }
+}
+
+impl Add<&Gt> for &Gt {
+    type Output = Gt;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn add(self, rhs: &Gt) -> Gt {
+        Gt(self.0 * rhs.0)
+    }
+}
+
+impl Sub<&Gt> for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn sub(self, rhs: &Gt) -> Gt {
+        self + (-rhs)
+    }
+}
+
+impl Mul<&Fr> for &Gt {
+    type Output = Gt;
+
+    #[inline]
+    fn mul(self, rhs: &Fr) -> Gt {
+        Gt(self.0.pow(&rhs.to_limbs()))
+    }
+}
+
+impl_binops_additive!(Gt, Gt);
+impl_binops_multiplicative!(Gt, Fr);
+impl_sum!(Gt);
+
+impl Group for Gt {
+    type Scalar = Fr;
+
+    fn random(mut rng: impl RngCore) -> Self {
+        loop {
+            let s = Fr::random(&mut rng);
+            if !bool::from(s.is_zero()) {
+                return Gt::generator() * s;
+            }
+        }
+    }
+
+    fn identity() -> Self {
+        Gt(Fq12::one())
+    }
+
+    /// `e(G1::generator(), G2::generator())`, computed on every call.
+    fn generator() -> Self {
+        Bn256::pairing(&G1Affine::generator(), &G2Affine::generator())
+    }
+
+    fn is_identity(&self) -> Choice {
+        Choice::from((*self == Gt::identity()) as u8)
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use group::prime::PrimeCurveAffine;
+    use group::{Curve, Group};
+    use pairing::{Engine, MillerLoopResult as _, MultiMillerLoop};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{
+        Bn256, Fq12, Fr, G1Affine, G2Affine, G2Prepared, Gt, MillerLoopResult, G1, G2,
+    };
+
+    #[test]
+    fn pairing_is_bilinear() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6e01);
+        let (a, b) = (Fr::random(&mut rng), Fr::random(&mut rng));
+        let (p, q) = (G1Affine::generator(), G2Affine::generator());
+        let e = Bn256::pairing(&p, &q);
+
+        let ab = Bn256::pairing(&(p * a).to_affine(), &(q * b).to_affine());
+        assert_eq!(ab, e * (a * b));
+        assert_eq!(ab, Bn256::pairing(&(p * (a * b)).to_affine(), &q));
+        assert_eq!(
+            Bn256::pairing(&(-p), &q),
+            -e,
+            "e(-P, Q) is the inverse of e(P, Q)"
+        );
+    }
+
+    #[test]
+    fn pairing_is_non_degenerate() {
+        let e = Gt::generator();
+        assert_ne!(e, Gt::identity());
+        assert_eq!(e * -Fr::one(), -e);
+        assert_eq!(e * Fr::zero(), Gt::identity());
+
+        assert_eq!(
+            Bn256::pairing(&G1Affine::identity(), &G2Affine::generator()),
+            Gt::identity()
+        );
+        assert_eq!(
+            Bn256::pairing(&G1Affine::generator(), &G2Affine::identity()),
+            Gt::identity()
+        );
+    }
+
+    #[test]
+    fn multi_miller_loop_is_the_product_of_pairings() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6e02);
+        let points = (0..3)
+            .map(|_| {
+                (
+                    G1::random(&mut rng).to_affine(),
+                    G2::random(&mut rng).to_affine(),
+                )
+            })
+            .chain([(G1Affine::generator(), G2Affine::identity())])
+            .collect::<Vec<_>>();
+        let prepared = points
+            .iter()
+            .map(|(_, q)| G2Prepared::from(*q))
+            .collect::<Vec<_>>();
+        let terms = points
+            .iter()
+            .zip(prepared.iter())
+            .map(|((p, _), q)| (p, q))
+            .collect::<Vec<_>>();
+
+        let expected = points
+            .iter()
+            .map(|(p, q)| Bn256::pairing(p, q))
+            .sum::<Gt>();
+        assert_eq!(
+            Bn256::multi_miller_loop(&terms).final_exponentiation(),
+            expected
+        );
+
+        // A prepared point is reused as is.
+        let again = Bn256::multi_miller_loop(&terms[..1]).final_exponentiation();
+        assert_eq!(again, Bn256::pairing(&points[0].0, &points[0].1));
+        assert_eq!(prepared[0], G2Prepared::from(points[0].1));
+    }
+
+    #[test]
+    fn gt_group_law() {
+        let mut rng = StdRng::seed_from_u64(0x0b25_6e03);
+        let (x, y) = (Gt::random(&mut rng), Gt::random(&mut rng));
+        let (a, b) = (Fr::random(&mut rng), Fr::random(&mut rng));
+
+        assert_eq!(x + y, y + x);
+        assert_eq!(x - x, Gt::identity());
+        assert_eq!(x.double(), x + x);
+        assert_eq!(x * (a + b), x * a + x * b);
+        assert_eq!((x + y) * a, x * a + y * a);
+    }
+
+    #[test]
+    fn zero_miller_loop_result_is_never_the_identity() {
+        let zero = MillerLoopResult(Fq12::zero()).final_exponentiation();
+        let x = Gt::random(StdRng::seed_from_u64(0x0b25_6e04));
+
+        assert!(!bool::from(zero.is_identity()));
+        assert!(!bool::from((zero - zero).is_identity()));
+        assert!(!bool::from((zero - x).is_identity()));
+    }
+}
@@ -0,0 +1,226 @@
+//! Decoding of the decimal string coordinates found in snarkjs'
+//! `verification_key.json` and `proof.json` for the bn128 curve.
+
+use bn::arith::U256;
+use group::prime::PrimeCurveAffine;
+
+use super::{Bn256, Fq, Fq12, Fq2, Fq6, Fr, G1Affine, G2Affine, Gt};
+use crate::Proof;
+
+/// Parses a decimal string with `verifier_traits::u256_from_decimal`.
+fn u256_from_decimal(s: &str) -> Option<U256> {
+    let be = verifier_traits::u256_from_decimal(s).ok()?;
+    U256::from_slice(&be).ok()
+}
+
+/// Parses a canonical decimal scalar, as in snarkjs' `public.json`.
+pub fn fr_from_decimal(s: &str) -> Option<Fr> {
+    u256_from_decimal(s).and_then(|v| bn::Fr::new(v).map(Fr))
+}
+
+/// Parses a canonical decimal base field element.
+pub fn fq_from_decimal(s: &str) -> Option<Fq> {
+    u256_from_decimal(s).and_then(|v| Fq::from_u256(v).ok())
+}
+
+/// Parses `[c0, c1]` decimal strings.
+fn fq2_from_decimal<S: AsRef<str>>(c: &[S]) -> Option<Fq2> {
+    match c {
+        [c0, c1] => Some(Fq2::new(
+            fq_from_decimal(c0.as_ref())?,
+            fq_from_decimal(c1.as_ref())?,
+        )),
+        _ => None,
+    }
+}
+
+/// Decodes a G1 point given as `[x, y, z]` decimal strings. snarkjs always
+/// writes affine points, so `z` must be `"1"`, or `"0"` for the identity.
+pub fn g1_from_snarkjs<S: AsRef<str>>(point: &[S]) -> Option<G1Affine> {
+    if point.len() != 3 {
+        return None;
+    }
+
+    match point[2].as_ref() {
+        "0" => Some(G1Affine::identity()),
+        "1" => G1Affine::from_xy(
+            fq_from_decimal(point[0].as_ref())?,
+            fq_from_decimal(point[1].as_ref())?,
+        ),
+        _ => None,
+    }
+}
+
+/// Decodes a G2 point given as `[[x.c0, x.c1], [y.c0, y.c1], [z.c0, z.c1]]`
+/// decimal strings, with `z` being `["1", "0"]`, or `["0", "0"]` for the
+/// identity. The point is checked to be in the prime order subgroup.
+pub fn g2_from_snarkjs<S: AsRef<str>, C: AsRef<[S]>>(point: &[C]) -> Option<G2Affine> {
+    if point.len() != 3 {
+        return None;
+    }
+
+    let z = fq2_from_decimal(point[2].as_ref())?;
+    if z.is_zero() {
+        Some(G2Affine::identity())
+    } else if z == Fq2::one() {
+        G2Affine::from_xy(
+            fq2_from_decimal(point[0].as_ref())?,
+            fq2_from_decimal(point[1].as_ref())?,
+        )
+    } else {
+        None
+    }
+}
+
+/// Decodes `vk_alphabeta_12`, `e(alpha, beta)` as `[c0, c1]` of Fq6
+/// coefficients `[c0, c1, c2]` of `[c0, c1]` decimal Fq2 strings.
+///
+/// Whether it is a pairing output is not checked; it is meant for
+/// `prepare_verifying_key_with_alpha_beta`, which compares it with the key.
+pub fn gt_from_snarkjs<S: AsRef<str>, C: AsRef<[S]>, F: AsRef<[C]>>(value: &[F]) -> Option<Gt> {
+    let fq6 = |f: &F| -> Option<Fq6> {
+        match f.as_ref() {
+            [c0, c1, c2] => Some(Fq6::new(
+                fq2_from_decimal(c0.as_ref())?,
+                fq2_from_decimal(c1.as_ref())?,
+                fq2_from_decimal(c2.as_ref())?,
+            )),
+            _ => None,
+        }
+    };
+
+    match value {
+        [c0, c1] => Some(Gt(Fq12::new(fq6(c0)?, fq6(c1)?))),
+        _ => None,
+    }
+}
+
+/// Decodes the `pi_a`, `pi_b` and `pi_c` fields of a snarkjs `proof.json`.
+pub fn proof_from_snarkjs<S: AsRef<str>, C: AsRef<[S]>>(
+    pi_a: &[S],
+    pi_b: &[C],
+    pi_c: &[S],
+) -> Option<Proof<Bn256>> {
+    Some(Proof {
+        a: g1_from_snarkjs(pi_a)?,
+        b: g2_from_snarkjs(pi_b)?,
+        c: g1_from_snarkjs(pi_c)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use group::prime::PrimeCurveAffine;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        prepare_verifier_key, prepare_verifying_key_with_alpha_beta, verify_proof,
+        PreparationConfig, VerifierError, VerifierKey,
+    };
+
+    const VKEY: &str = include_str!("../../../fixtures/bn128/verification_key.json");
+    const PROOF: &str = include_str!("../../../fixtures/bn128/proof.json");
+    const PUBLIC: &str = include_str!("../../../fixtures/bn128/public.json");
+
+    fn strs(v: &Value) -> Vec<&str> {
+        v.as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s.as_str().unwrap())
+            .collect()
+    }
+
+    fn strs2(v: &Value) -> Vec<Vec<&str>> {
+        v.as_array().unwrap().iter().map(strs).collect()
+    }
+
+    fn fixture() -> (VerifierKey<Bn256>, Gt, Proof<Bn256>, Vec<Fr>) {
+        let vk: Value = serde_json::from_str(VKEY).unwrap();
+        let proof: Value = serde_json::from_str(PROOF).unwrap();
+        let public: Value = serde_json::from_str(PUBLIC).unwrap();
+
+        let key = VerifierKey {
+            alpha_g1: g1_from_snarkjs(&strs(&vk["vk_alpha_1"])).unwrap(),
+            beta_g2: g2_from_snarkjs(&strs2(&vk["vk_beta_2"])).unwrap(),
+            gamma_g2: g2_from_snarkjs(&strs2(&vk["vk_gamma_2"])).unwrap(),
+            delta_g2: g2_from_snarkjs(&strs2(&vk["vk_delta_2"])).unwrap(),
+            ic: vk["IC"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| g1_from_snarkjs(&strs(p)).unwrap())
+                .collect(),
+        };
+        let alpha_beta = vk["vk_alphabeta_12"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(strs2)
+            .collect::<Vec<_>>();
+        let proof = proof_from_snarkjs(
+            &strs(&proof["pi_a"]),
+            &strs2(&proof["pi_b"]),
+            &strs(&proof["pi_c"]),
+        )
+        .unwrap();
+        let inputs = strs(&public)
+            .into_iter()
+            .map(|s| fr_from_decimal(s).unwrap())
+            .collect();
+
+        (key, gt_from_snarkjs(&alpha_beta).unwrap(), proof, inputs)
+    }
+
+    #[test]
+    fn verifies_a_snarkjs_proof() {
+        let (key, _, proof, inputs) = fixture();
+        let pvk = prepare_verifier_key(&key);
+
+        assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(()));
+        assert_eq!(
+            verify_proof(&pvk, &proof, &[inputs[0] + Fr::one()]),
+            Err(VerifierError::InvalidProof)
+        );
+        let forged = Proof {
+            a: proof.c,
+            ..proof.clone()
+        };
+        assert_eq!(
+            verify_proof(&pvk, &forged, &inputs),
+            Err(VerifierError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn alpha_beta_matches_the_key() {
+        let (key, alpha_beta, proof, inputs) = fixture();
+        let config = PreparationConfig::default();
+
+        let pvk = prepare_verifying_key_with_alpha_beta(&key, alpha_beta, &config, true).unwrap();
+        assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(()));
+
+        assert_eq!(
+            prepare_verifying_key_with_alpha_beta(&key, -alpha_beta, &config, true).err(),
+            Some(VerifierError::InvalidVerifyingKey)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_points() {
+        assert_eq!(
+            g1_from_snarkjs(&["0", "1", "0"]),
+            Some(G1Affine::identity())
+        );
+        assert_eq!(g1_from_snarkjs(&["1", "3", "1"]), None);
+        assert_eq!(g1_from_snarkjs(&["1", "2"]), None);
+        assert_eq!(g1_from_snarkjs(&["0x1", "2", "1"]), None);
+        assert_eq!(
+            fr_from_decimal(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            ),
+            None
+        );
+    }
+}
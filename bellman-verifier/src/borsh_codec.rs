@@ -0,0 +1,250 @@
+//! Borsh encoding of proofs and keys, for NEAR and Solana programs.
+//!
+//! Points are uncompressed, as the adapter writes them, and fields follow in
+//! declaration order:
+//!
+//! * `Proof`: `a || b || c`
+//! * `VerifyingKey`: `alpha_g1 || beta_g1 || beta_g2 || gamma_g2 || delta_g1 || delta_g2 || n || ic[0] || ... || ic[n - 1]`
+//! * `VerifierKey`: `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || n || ic[0] || ... || ic[n - 1]`
+//!
+//! `n` is a little-endian `u32`. Without it, this is the byte layout of
+//! `verifier_traits`, so a proof encodes to the bytes `Groth16Verifier`
+//! decodes, and so does a `VerifierKey` once `n` is dropped. Decoding checks
+//! that points are on the curve and in the prime order subgroup.
+
+use borsh::maybestd::io::{Error, ErrorKind, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+use group::UncompressedEncoding;
+use pairing::Engine;
+use sp_std::prelude::*;
+
+use super::{Proof, VerifierKey, VerifyingKey};
+
+fn write_point<G: UncompressedEncoding, W: Write>(point: &G, writer: &mut W) -> Result<()> {
+    writer.write_all(point.to_uncompressed().as_ref())
+}
+
+fn read_point<G: UncompressedEncoding>(buf: &mut &[u8]) -> Result<G> {
+    let mut repr = G::Uncompressed::default();
+    let len = repr.as_ref().len();
+    if buf.len() < len {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "unexpected end of input",
+        ));
+    }
+    repr.as_mut().copy_from_slice(&buf[..len]);
+    *buf = &buf[len..];
+
+    Option::from(G::from_uncompressed(&repr))
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid curve point"))
+}
+
+fn write_points<G: UncompressedEncoding, W: Write>(points: &[G], writer: &mut W) -> Result<()> {
+    let len = u32::try_from(points.len())
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "more than u32::MAX points"))?;
+    len.serialize(writer)?;
+    points.iter().try_for_each(|p| write_point(p, writer))
+}
+
+fn read_points<G: UncompressedEncoding>(buf: &mut &[u8]) -> Result<Vec<G>> {
+    let len = u32::deserialize(buf)? as usize;
+    // `len` is untrusted, do not allocate more than the input can hold.
+    let size = G::Uncompressed::default().as_ref().len();
+    let mut points = Vec::with_capacity(len.min(buf.len() / size));
+    for _ in 0..len {
+        points.push(read_point(buf)?);
+    }
+    Ok(points)
+}
+
+impl<E: Engine> BorshSerialize for Proof<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.a, writer)?;
+        write_point(&self.b, writer)?;
+        write_point(&self.c, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for Proof<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(Proof {
+            a: read_point(buf)?,
+            b: read_point(buf)?,
+            c: read_point(buf)?,
+        })
+    }
+}
+
+impl<E: Engine> BorshSerialize for VerifyingKey<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.alpha_g1, writer)?;
+        write_point(&self.beta_g1, writer)?;
+        write_point(&self.beta_g2, writer)?;
+        write_point(&self.gamma_g2, writer)?;
+        write_point(&self.delta_g1, writer)?;
+        write_point(&self.delta_g2, writer)?;
+        write_points(&self.ic, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for VerifyingKey<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(VerifyingKey {
+            alpha_g1: read_point(buf)?,
+            beta_g1: read_point(buf)?,
+            beta_g2: read_point(buf)?,
+            gamma_g2: read_point(buf)?,
+            delta_g1: read_point(buf)?,
+            delta_g2: read_point(buf)?,
+            ic: read_points(buf)?,
+        })
+    }
+}
+
+impl<E: Engine> BorshSerialize for VerifierKey<E> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_point(&self.alpha_g1, writer)?;
+        write_point(&self.beta_g2, writer)?;
+        write_point(&self.gamma_g2, writer)?;
+        write_point(&self.delta_g2, writer)?;
+        write_points(&self.ic, writer)
+    }
+}
+
+impl<E: Engine> BorshDeserialize for VerifierKey<E> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        Ok(VerifierKey {
+            alpha_g1: read_point(buf)?,
+            beta_g2: read_point(buf)?,
+            gamma_g2: read_point(buf)?,
+            delta_g2: read_point(buf)?,
+            ic: read_points(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, G1Affine, G2Affine, Scalar};
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use verifier_traits::Groth16Verifier;
+
+    use crate::{BellmanVerifier, Proof, VerifierKey, VerifyingKey};
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    fn g1(k: u64) -> G1Affine {
+        (G1Affine::generator() * Scalar::from(k)).into()
+    }
+
+    fn g2(k: u64) -> G2Affine {
+        (G2Affine::generator() * Scalar::from(k)).into()
+    }
+
+    fn key() -> VerifyingKey<Bls12> {
+        VerifyingKey {
+            alpha_g1: g1(2),
+            beta_g1: g1(3),
+            beta_g2: g2(3),
+            gamma_g2: g2(5),
+            delta_g1: g1(7),
+            delta_g2: g2(7),
+            ic: vec![g1(11), g1(13), g1(17)],
+        }
+    }
+
+    #[test]
+    fn proofs_encode_as_in_verifier_traits() {
+        let encoded = include_bytes!("../../fixtures/bls12381/proof.bin");
+        let proof = Backend::decode_proof(encoded).unwrap();
+
+        let bytes = proof.try_to_vec().unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(Proof::try_from_slice(&bytes).unwrap(), proof);
+    }
+
+    #[test]
+    fn keys_encode_as_in_verifier_traits_but_for_the_ic_length() {
+        let encoded = include_bytes!("../../fixtures/bls12381/vkey.bin");
+        let vk = Backend::decode_verifying_key(encoded).unwrap();
+
+        let bytes = vk.try_to_vec().unwrap();
+        let fixed = 96 + 3 * 192;
+        let n = vk.ic.len() as u32;
+        assert_eq!(bytes[..fixed], encoded[..fixed]);
+        assert_eq!(bytes[fixed..fixed + 4], n.to_le_bytes());
+        assert_eq!(bytes[fixed + 4..], encoded[fixed..]);
+        assert_eq!(VerifierKey::try_from_slice(&bytes).unwrap(), vk);
+
+        let vk = key();
+        let bytes = vk.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 3 * 96 + 3 * 192 + 4 + 3 * 96);
+        assert_eq!(VerifyingKey::try_from_slice(&bytes).unwrap(), vk);
+        let bytes = VerifierKey::from(vk).try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 96 + 3 * 192 + 4 + 3 * 96);
+    }
+
+    #[test]
+    fn rejects_invalid_encodings() {
+        let bytes = key().try_to_vec().unwrap();
+
+        assert!(VerifyingKey::<Bls12>::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+        assert!(VerifyingKey::<Bls12>::try_from_slice(&[&bytes[..], &[0]].concat()).is_err());
+
+        let mut off_curve = bytes.clone();
+        off_curve[95] ^= 1;
+        assert!(VerifyingKey::<Bls12>::try_from_slice(&off_curve).is_err());
+
+        // A huge `ic` length fails on the missing points.
+        let mut long = bytes[..3 * 96 + 3 * 192].to_vec();
+        long.extend(u32::MAX.to_le_bytes());
+        assert!(VerifyingKey::<Bls12>::try_from_slice(&long).is_err());
+    }
+
+    #[cfg(feature = "bn256")]
+    #[test]
+    fn bn254_values_round_trip_through_scale_and_borsh() {
+        use codec::{Decode, Encode};
+
+        use crate::bn256::Bn256;
+
+        type Bn254 = BellmanVerifier<Bn256>;
+
+        // `Proof` derives SCALE, which writes the same bytes as Borsh.
+        let proof = Bn254::decode_proof(include_bytes!("../../fixtures/bn128/proof.bin")).unwrap();
+        let scale = proof.encode();
+        assert_eq!(scale, proof.try_to_vec().unwrap());
+        assert_eq!(Proof::<Bn256>::decode(&mut &scale[..]).unwrap(), proof);
+        assert_eq!(Proof::<Bn256>::try_from_slice(&scale).unwrap(), proof);
+
+        let mut off_curve = scale;
+        off_curve[63] ^= 1;
+        assert!(Proof::<Bn256>::decode(&mut &off_curve[..]).is_err());
+        assert!(Proof::<Bn256>::try_from_slice(&off_curve).is_err());
+
+        // Keys do not derive SCALE, so their fields go through it as a tuple,
+        // with `ic` behind a compact length instead of Borsh's `u32`.
+        let vk =
+            Bn254::decode_verifying_key(include_bytes!("../../fixtures/bn128/vkey.bin")).unwrap();
+        let fields = (
+            vk.alpha_g1,
+            vk.beta_g2,
+            vk.gamma_g2,
+            vk.delta_g2,
+            vk.ic.clone(),
+        );
+        let (alpha_g1, beta_g2, gamma_g2, delta_g2, ic) =
+            Decode::decode(&mut &fields.encode()[..]).unwrap();
+        let through_scale = VerifierKey {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            ic,
+        };
+        let through_borsh = VerifierKey::try_from_slice(&vk.try_to_vec().unwrap()).unwrap();
+        assert_eq!(through_scale, vk);
+        assert_eq!(through_borsh, vk);
+    }
+}
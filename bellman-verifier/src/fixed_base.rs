@@ -0,0 +1,170 @@
+use codec::{Decode, Encode};
+use group::{ff::{PrimeField, PrimeFieldBits}, prime::PrimeCurveAffine, Curve, Group};
+use pairing::Engine;
+
+use sp_std::prelude::*;
+
+/// Largest window accepted by `FixedBaseTable::new`. A table takes
+/// `ceil(NUM_BITS / window) * (2^window - 1)` affine points, at this window
+/// `22 * 4095 = 90,090` of them: about 9 MiB per base for BLS12-381 and
+/// 8 MiB for the `bn256` engine.
+pub const MAX_FIXED_BASE_WINDOW: u32 = 12;
+
+/// Precomputed multiples of a fixed G1 point.
+///
+/// For every `window`-bit digit position `j` of a scalar the table holds
+/// `k * 2^(window * j) * base` for `k` in `1..2^window`, so a scalar
+/// multiplication becomes one table lookup and one mixed addition per window
+/// with no doublings at all.
+///
+/// Decoding checks the window and the table length, so a decoded table is
+/// always one `new` could have built, up to the points themselves.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, Encode, PartialEq, Eq)]
+pub struct FixedBaseTable<E: Engine> {
+    window: u32,
+    table: Vec<E::G1Affine>,
+}
+
+impl<E: Engine> FixedBaseTable<E> {
+    /// Builds the table for `base`. `window` is clamped to
+    /// `1..=MAX_FIXED_BASE_WINDOW`.
+    pub fn new(base: &E::G1Affine, window: u32) -> Self {
+        let window = window.clamp(1, MAX_FIXED_BASE_WINDOW);
+        let per_window = (1usize << window) - 1;
+        let num_windows = num_windows::<E>(window);
+
+        let mut table = Vec::with_capacity(num_windows * per_window);
+        let mut window_base = base.to_curve();
+        for _ in 0..num_windows {
+            let mut multiple = window_base;
+            for _ in 0..per_window {
+                table.push(multiple);
+                multiple += &window_base;
+            }
+            // `multiple` is now 2^window * window_base, the base of the next window.
+            window_base = multiple;
+        }
+
+        let mut affine = vec![E::G1Affine::identity(); table.len()];
+        E::G1::batch_normalize(&table, &mut affine);
+
+        FixedBaseTable {
+            window,
+            table: affine,
+        }
+    }
+
+    /// Checks that `table` has the layout `new` gives `window`.
+    fn from_parts(window: u32, table: Vec<E::G1Affine>) -> Result<Self, &'static str> {
+        if !(1..=MAX_FIXED_BASE_WINDOW).contains(&window) {
+            return Err("fixed-base window out of range");
+        }
+        if table.len() != num_windows::<E>(window) * ((1usize << window) - 1) {
+            return Err("fixed-base table length does not match its window");
+        }
+
+        Ok(FixedBaseTable { window, table })
+    }
+
+    /// Multiplies the base of this table by `scalar`.
+    pub fn mul(&self, scalar: &E::Fr) -> E::G1
+    where
+        E::Fr: PrimeFieldBits,
+    {
+        let window = self.window as usize;
+        let per_window = (1usize << window) - 1;
+        let num_bits = E::Fr::NUM_BITS as usize;
+        let bits = scalar.to_le_bits();
+
+        let mut acc = E::G1::identity();
+        for (j, chunk) in self.table.chunks(per_window).enumerate() {
+            let mut digit = 0usize;
+            for k in 0..window {
+                let bit = j * window + k;
+                if bit < num_bits && bits[bit] {
+                    digit |= 1 << k;
+                }
+            }
+
+            if digit != 0 {
+                acc += &chunk[digit - 1];
+            }
+        }
+
+        acc
+    }
+}
+
+impl<E: Engine> Decode for FixedBaseTable<E>
+where
+    E::G1Affine: Decode,
+{
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let window = u32::decode(input)?;
+        let table = Vec::decode(input)?;
+
+        Self::from_parts(window, table).map_err(Into::into)
+    }
+}
+
+fn num_windows<E: Engine>(window: u32) -> usize {
+    let window = window as usize;
+    (E::Fr::NUM_BITS as usize + window - 1) / window
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, G1Affine, G1Projective, Scalar};
+    use group::ff::Field;
+    use group::{Curve, Group};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{FixedBaseTable, MAX_FIXED_BASE_WINDOW};
+
+    #[test]
+    fn mul_matches_scalar_multiplication() {
+        let mut rng = StdRng::seed_from_u64(0xf1ed_ba5e);
+        let base = G1Projective::random(&mut rng).to_affine();
+        let scalars = [Scalar::zero(), Scalar::one(), -Scalar::one()]
+            .into_iter()
+            .chain((0..8).map(|_| Scalar::random(&mut rng)))
+            .collect::<Vec<_>>();
+
+        for window in [1, 3, 4, 8] {
+            let table = FixedBaseTable::<Bls12>::new(&base, window);
+            for scalar in &scalars {
+                assert_eq!(table.mul(scalar), base * scalar, "window {}", window);
+            }
+        }
+
+        let identity = FixedBaseTable::<Bls12>::new(&G1Affine::identity(), 4);
+        assert_eq!(identity.mul(&scalars[3]), G1Projective::identity());
+    }
+
+    #[test]
+    fn new_clamps_the_window() {
+        let base = G1Affine::generator();
+        assert_eq!(FixedBaseTable::<Bls12>::new(&base, 0).window, 1);
+        assert_eq!(
+            FixedBaseTable::<Bls12>::new(&base, 64).window,
+            MAX_FIXED_BASE_WINDOW
+        );
+    }
+
+    #[test]
+    fn from_parts_checks_the_layout() {
+        let table = FixedBaseTable::<Bls12>::new(&G1Affine::generator(), 2);
+        let rebuilt = FixedBaseTable::<Bls12>::from_parts(2, table.table.clone()).unwrap();
+        assert_eq!((rebuilt.window, &rebuilt.table), (2, &table.table));
+
+        for window in [0, MAX_FIXED_BASE_WINDOW + 1, 64, u32::MAX] {
+            assert!(FixedBaseTable::<Bls12>::from_parts(window, table.table.clone()).is_err());
+        }
+        assert!(FixedBaseTable::<Bls12>::from_parts(3, table.table.clone()).is_err());
+        let short = table.table[1..].to_vec();
+        assert!(FixedBaseTable::<Bls12>::from_parts(2, short).is_err());
+        assert!(FixedBaseTable::<Bls12>::from_parts(2, Vec::new()).is_err());
+    }
+}
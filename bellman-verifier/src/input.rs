@@ -0,0 +1,159 @@
+//! Conversion of 256-bit public inputs into scalars, checked against or
+//! reduced modulo the field modulus as chosen by `InputMode`.
+//!
+//! `PrimeField::Repr` is assumed to be little-endian, as for
+//! `bls12_381::Scalar` and `bn256::Fr`.
+
+use group::ff::PrimeField;
+use verifier_traits::{u256_from_decimal, u256_from_hex};
+
+pub use verifier_traits::{InputError, InputMode};
+
+/// Converts 32 big-endian bytes, e.g. a Solidity `uint256`.
+pub fn public_input_from_be_bytes<F: PrimeField>(
+    bytes: &[u8; 32],
+    mode: InputMode,
+) -> Result<F, InputError> {
+    match mode {
+        InputMode::Strict => from_be_strict(bytes),
+        InputMode::Reduce => Ok(from_be_reduced(bytes)),
+    }
+}
+
+/// Converts 32 little-endian bytes.
+pub fn public_input_from_le_bytes<F: PrimeField>(
+    bytes: &[u8; 32],
+    mode: InputMode,
+) -> Result<F, InputError> {
+    let mut be = *bytes;
+    be.reverse();
+    public_input_from_be_bytes(&be, mode)
+}
+
+/// Converts a decimal string, such as an entry of snarkjs' `public.json`.
+pub fn public_input_from_decimal<F: PrimeField>(
+    s: &str,
+    mode: InputMode,
+) -> Result<F, InputError> {
+    public_input_from_be_bytes(&u256_from_decimal(s)?, mode)
+}
+
+/// Converts a `0x` prefixed hex string.
+pub fn public_input_from_hex<F: PrimeField>(s: &str, mode: InputMode) -> Result<F, InputError> {
+    public_input_from_be_bytes(&u256_from_hex(s)?, mode)
+}
+
+fn from_be_strict<F: PrimeField>(bytes: &[u8; 32]) -> Result<F, InputError> {
+    let mut repr = F::Repr::default();
+    let len = repr.as_ref().len();
+    if len < bytes.len() && bytes[..bytes.len() - len].iter().any(|b| *b != 0) {
+        return Err(InputError::OutOfRange);
+    }
+    for (r, b) in repr.as_mut().iter_mut().zip(bytes.iter().rev()) {
+        *r = *b;
+    }
+
+    Option::from(F::from_repr(repr)).ok_or(InputError::OutOfRange)
+}
+
+fn from_be_reduced<F: PrimeField>(bytes: &[u8; 32]) -> F {
+    let base = F::from(256);
+    bytes
+        .iter()
+        .fold(F::zero(), |acc, b| acc * base + F::from(*b as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::Scalar;
+
+    use super::*;
+
+    const R_HEX: &str = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+    const R_DECIMAL: &str =
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+    const R_MINUS_1_DECIMAL: &str =
+        "52435875175126190479447740508185965837690552500527637822603658699938581184512";
+    const MAX_DECIMAL: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    /// `(2^256 - 1) mod r`.
+    const MAX_MOD_R_HEX: &str =
+        "0x1824b159acc5056f998c4fefecbc4ff55884b7fa0003480200000001fffffffd";
+
+    fn parse(s: &str, mode: InputMode) -> Result<Scalar, InputError> {
+        if s.starts_with("0x") {
+            public_input_from_hex(s, mode)
+        } else {
+            public_input_from_decimal(s, mode)
+        }
+    }
+
+    #[test]
+    fn converts_r_minus_1_in_both_modes() {
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            assert_eq!(parse(R_MINUS_1_DECIMAL, mode), Ok(-Scalar::one()));
+        }
+    }
+
+    #[test]
+    fn rejects_or_reduces_r() {
+        for s in [R_HEX, R_DECIMAL] {
+            assert_eq!(parse(s, InputMode::Strict), Err(InputError::OutOfRange));
+            assert_eq!(parse(s, InputMode::Reduce), Ok(Scalar::zero()));
+        }
+    }
+
+    #[test]
+    fn rejects_or_reduces_2_256_minus_1() {
+        let reduced = parse(MAX_MOD_R_HEX, InputMode::Strict).unwrap();
+
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            let expected = match mode {
+                InputMode::Strict => Err(InputError::OutOfRange),
+                InputMode::Reduce => Ok(reduced),
+            };
+            assert_eq!(public_input_from_be_bytes(&[0xff; 32], mode), expected);
+            assert_eq!(public_input_from_le_bytes(&[0xff; 32], mode), expected);
+        }
+        assert_eq!(parse(MAX_DECIMAL, InputMode::Reduce), Ok(reduced));
+        assert_eq!(
+            parse(&format!("0x{}", "f".repeat(64)), InputMode::Reduce),
+            Ok(reduced)
+        );
+    }
+
+    #[test]
+    fn hex_decimal_and_bytes_agree() {
+        let be = u256_from_decimal(R_MINUS_1_DECIMAL).unwrap();
+        let mut le = be;
+        le.reverse();
+
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            let expected = parse(R_MINUS_1_DECIMAL, mode);
+            assert_eq!(parse(&format!("0x{}", hex(&be)), mode), expected);
+            assert_eq!(public_input_from_be_bytes(&be, mode), expected);
+            assert_eq!(public_input_from_le_bytes(&le, mode), expected);
+        }
+
+        assert_eq!(parse("0x2a", InputMode::Strict), Ok(Scalar::from(42)));
+        assert_eq!(parse("42", InputMode::Strict), Ok(Scalar::from(42)));
+    }
+
+    #[test]
+    fn passes_on_format_errors() {
+        for mode in [InputMode::Strict, InputMode::Reduce] {
+            assert_eq!(
+                public_input_from_decimal::<Scalar>("0x2a", mode),
+                Err(InputError::InvalidFormat)
+            );
+            assert_eq!(
+                public_input_from_hex::<Scalar>(&format!("0x1{}", "0".repeat(64)), mode),
+                Err(InputError::Overflow)
+            );
+        }
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
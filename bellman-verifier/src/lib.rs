@@ -1,14 +1,34 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use group::Group;
 use pairing::{Engine, MultiMillerLoop};
 
 use codec::{ Encode, Decode };
 use sp_std::sync::Arc;
 use sp_std::prelude::*;
 
+mod backend;
+mod batch;
+#[cfg(feature = "borsh")]
+mod borsh_codec;
+#[cfg(feature = "bn256")]
+pub mod bn256;
+mod fixed_base;
+mod input;
+mod multiexp;
+#[cfg(feature = "std")]
+mod report;
 mod verifier;
 
+pub use self::backend::*;
+pub use self::batch::*;
+pub use self::fixed_base::*;
+pub use self::input::*;
+pub use self::multiexp::*;
+#[cfg(feature = "std")]
+pub use self::report::*;
 pub use self::verifier::*;
+pub use verifier_traits::VerifierError;
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Clone, Encode, Decode, Default, Eq)]
@@ -65,6 +85,53 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
     }
 }
 
+/// The part of a `VerifyingKey` that verification needs, without the
+/// prover-only `beta_g1` and `delta_g1`. snarkjs verification keys carry
+/// exactly these points.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone)]
+pub struct VerifierKey<E: Engine> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g2: E::G2Affine,
+    pub ic: Vec<E::G1Affine>,
+}
+
+impl<E: Engine> PartialEq for VerifierKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.alpha_g1 == other.alpha_g1
+            && self.beta_g2 == other.beta_g2
+            && self.gamma_g2 == other.gamma_g2
+            && self.delta_g2 == other.delta_g2
+            && self.ic == other.ic
+    }
+}
+
+impl<E: Engine> From<&VerifyingKey<E>> for VerifierKey<E> {
+    fn from(vk: &VerifyingKey<E>) -> Self {
+        VerifierKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.ic.clone(),
+        }
+    }
+}
+
+impl<E: Engine> From<VerifyingKey<E>> for VerifierKey<E> {
+    fn from(vk: VerifyingKey<E>) -> Self {
+        VerifierKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.ic,
+        }
+    }
+}
+
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Clone)]
 pub struct Parameters<E: Engine> {
@@ -102,16 +169,73 @@ impl<E: Engine> PartialEq for Parameters<E> {
     }
 }
 
-#[derive(Clone, Encode, Decode, Default, PartialEq, Eq)]
+/// `e(alpha_g1, beta_g2)` of a prepared key.
+#[derive(Clone, Encode, Decode, PartialEq, Eq)]
+pub(crate) enum AlphaBeta<E: MultiMillerLoop> {
+    /// Computed once, when the key was prepared.
+    Pairing(E::Gt),
+    /// `-alpha_g1` and `beta_g2`, paired in the Miller loop of every
+    /// verification instead, see `prepare_verifying_key_deferred`.
+    Deferred(E::G1Affine, E::G2Prepared),
+}
+
+impl<E: MultiMillerLoop> Default for AlphaBeta<E> {
+    fn default() -> Self {
+        AlphaBeta::Pairing(E::Gt::identity())
+    }
+}
+
+/// The SCALE encoding ends with `ic_tables`, so keys encoded before the
+/// fixed-base tables were added no longer decode. Decoding rejects a key
+/// whose `ic_tables` is neither empty nor one table per public input.
+#[derive(Clone, Encode, Default, PartialEq, Eq)]
 pub struct PreparedVerifyingKey<E: MultiMillerLoop> {
     /// Pairing result of alpha*beta
-    alpha_g1_beta_g2: E::Gt,
+    alpha_g1_beta_g2: AlphaBeta<E>,
     /// -gamma in G2
     neg_gamma_g2: E::G2Prepared,
     /// -delta in G2
     neg_delta_g2: E::G2Prepared,
     /// Copy of IC from `VerifiyingKey`.
     ic: Vec<E::G1Affine>,
+    /// Fixed-base tables for `ic[1..]`, empty unless requested through
+    /// `PreparationConfig::fixed_base_window`.
+    ic_tables: Vec<FixedBaseTable<E>>,
+}
+
+impl<E: MultiMillerLoop> Decode for PreparedVerifyingKey<E>
+where
+    AlphaBeta<E>: Decode,
+    E::G2Prepared: Decode,
+    E::G1Affine: Decode,
+{
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let pvk = PreparedVerifyingKey {
+            alpha_g1_beta_g2: Decode::decode(input)?,
+            neg_gamma_g2: Decode::decode(input)?,
+            neg_delta_g2: Decode::decode(input)?,
+            ic: Decode::decode(input)?,
+            ic_tables: Decode::decode(input)?,
+        };
+
+        if !pvk.ic_tables.is_empty() && pvk.ic_tables.len() + 1 != pvk.ic.len() {
+            return Err("fixed-base tables do not match the public inputs".into());
+        }
+
+        Ok(pvk)
+    }
+}
+
+/// Options for `prepare_verifying_key_with_config`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PreparationConfig {
+    /// When set, `FixedBaseTable`s with this window are built for every
+    /// public input's IC point. They make each verification under the key
+    /// cheaper but take `ceil(255 / w) * (2^w - 1)` G1 points per input, e.g.
+    /// `64 * 15 = 960` points, about 100 KiB per input in memory for `w = 4`
+    /// on BLS12-381, and 9 MiB at `MAX_FIXED_BASE_WINDOW`. Worth it only for
+    /// long-lived keys that verify many proofs.
+    pub fixed_base_window: Option<u32>,
 }
 
 /// This is an error that could occur during circuit synthesis contexts,
@@ -135,11 +259,78 @@ pub enum SynthesisError {
     UnconstrainedVariable,
 }
 
-/// An error during verification.
-#[derive(Debug, Clone)]
-pub enum VerificationError {
-    /// Verification was attempted with a malformed verifying key.
-    InvalidVerifyingKey,
-    /// Proof verification failed.
-    InvalidProof,
+/// Support for `groth16-macros`, not public API.
+#[doc(hidden)]
+pub mod __private {
+    use pairing::MultiMillerLoop;
+
+    use super::backend::{decode_key, decode_point_unchecked};
+    use super::VerifierKey;
+
+    /// The `static` an `include_vkey!` expansion keeps its key in.
+    #[cfg(feature = "std")]
+    pub use once_cell::sync::Lazy;
+
+    /// Decodes a key in the byte layout of `verifier_traits` whose points
+    /// `include_vkey!` checked at compile time, skipping the subgroup
+    /// checks.
+    ///
+    /// Panics if `bytes` is not such a key, which cannot happen for the
+    /// bytes the macro embeds.
+    pub fn verifier_key_unchecked<E: MultiMillerLoop>(bytes: &[u8]) -> VerifierKey<E> {
+        decode_key(bytes, decode_point_unchecked, decode_point_unchecked)
+            .expect("checked by include_vkey!")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, G1Affine};
+    use verifier_traits::Groth16Verifier;
+
+    use super::{prepare_verifying_key, verify_proof, BellmanVerifier, VerifierKey, VerifyingKey};
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    fn verifier_key() -> VerifierKey<Bls12> {
+        Backend::decode_verifying_key(include_bytes!("../../fixtures/bls12381/vkey.bin")).unwrap()
+    }
+
+    fn full_key(vk: &VerifierKey<Bls12>) -> VerifyingKey<Bls12> {
+        VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g1: G1Affine::generator(),
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g1: G1Affine::generator(),
+            delta_g2: vk.delta_g2,
+            ic: vk.ic.clone(),
+        }
+    }
+
+    #[test]
+    fn verifier_keys_drop_the_prover_points() {
+        let vk = verifier_key();
+        let full = full_key(&vk);
+
+        assert_eq!(VerifierKey::from(&full), vk);
+        assert_eq!(VerifierKey::from(full), vk);
+
+        let mut other = vk.clone();
+        other.ic.pop();
+        assert!(other != vk);
+    }
+
+    #[test]
+    fn full_keys_verify_like_verifier_keys() {
+        let vk = verifier_key();
+        let proof =
+            Backend::decode_proof(include_bytes!("../../fixtures/bls12381/proof.bin")).unwrap();
+        let inputs =
+            Backend::decode_public_inputs(include_bytes!("../../fixtures/bls12381/public.bin"))
+                .unwrap();
+
+        let pvk = prepare_verifying_key(&full_key(&vk));
+        assert_eq!(verify_proof(&pvk, &proof, &inputs), Ok(()));
+    }
 }
@@ -0,0 +1,158 @@
+use group::{ff::{PrimeField, PrimeFieldBits}, Group};
+use pairing::Engine;
+
+use sp_std::prelude::*;
+
+/// Computes `sum(bases[i] * scalars[i])` with Pippenger's bucket method.
+///
+/// Scalars are split into windows of `c` bits. For every window the bases are
+/// sorted into `2^c - 1` buckets by the value of their digit, the buckets are
+/// summed with a running sum, and the window results are combined with `c`
+/// doublings each. This costs roughly `255 / c * (n + 2^c)` additions instead
+/// of the `255` doublings and additions per base of a scalar multiplication.
+///
+/// Below `NAIVE_THRESHOLD` bases the bucket overhead outweighs the savings,
+/// so plain scalar multiplications are used instead.
+///
+/// `bases` and `scalars` are zipped, so any surplus elements of the longer
+/// slice are ignored.
+pub fn multiexp<E: Engine>(bases: &[E::G1Affine], scalars: &[E::Fr]) -> E::G1
+where
+    E::Fr: PrimeFieldBits,
+{
+    let n = bases.len().min(scalars.len());
+    if n < NAIVE_THRESHOLD {
+        return bases
+            .iter()
+            .zip(scalars.iter())
+            .fold(E::G1::identity(), |acc, (base, scalar)| acc + *base * scalar);
+    }
+
+    let c = window_size(n);
+    let num_bits = E::Fr::NUM_BITS as usize;
+    let bits = scalars[..n].iter().map(|s| s.to_le_bits()).collect::<Vec<_>>();
+
+    let mut buckets = vec![E::G1::identity(); (1 << c) - 1];
+    let mut acc = E::G1::identity();
+
+    // Walk the windows from the most significant one down, so that the
+    // accumulator only needs `c` doublings between two windows.
+    let mut start = ((num_bits - 1) / c) * c;
+    loop {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+
+        for bucket in buckets.iter_mut() {
+            *bucket = E::G1::identity();
+        }
+
+        for (base, scalar_bits) in bases.iter().zip(bits.iter()) {
+            let mut digit = 0usize;
+            for j in 0..c {
+                let bit = start + j;
+                if bit < num_bits && scalar_bits[bit] {
+                    digit |= 1 << j;
+                }
+            }
+
+            if digit != 0 {
+                buckets[digit - 1] += base;
+            }
+        }
+
+        // sum(i * bucket[i - 1]) as a running sum over the buckets, highest first.
+        let mut running_sum = E::G1::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum += bucket;
+            acc += &running_sum;
+        }
+
+        if start == 0 {
+            break;
+        }
+        start -= c;
+    }
+
+    acc
+}
+
+const NAIVE_THRESHOLD: usize = 4;
+
+/// Picks the window size for `n` bases, roughly `log2(n)` as in bellman's
+/// multiexp, and a small constant window for short inputs.
+fn window_size(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        (usize::BITS - n.leading_zeros()) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, G1Affine, G1Projective, Scalar};
+    use group::ff::Field;
+    use group::{Curve, Group};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{multiexp, NAIVE_THRESHOLD};
+
+    fn naive(bases: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(base, scalar)| base * scalar)
+            .sum()
+    }
+
+    fn bases(rng: &mut StdRng, n: usize) -> Vec<G1Affine> {
+        (0..n)
+            .map(|_| G1Projective::random(&mut *rng).to_affine())
+            .collect()
+    }
+
+    #[test]
+    fn matches_the_naive_loop() {
+        let mut rng = StdRng::seed_from_u64(0x5dbe_6259);
+
+        // Both sides of `NAIVE_THRESHOLD` and of the constant window size.
+        for n in [0, 1, NAIVE_THRESHOLD - 1, NAIVE_THRESHOLD, 5, 31, 32, 100] {
+            let bases = bases(&mut rng, n);
+            let mut scalars = (0..n)
+                .map(|_| Scalar::random(&mut rng))
+                .collect::<Vec<_>>();
+
+            // Digits of all zeros and all ones in every window.
+            if n > 2 {
+                scalars[..3].copy_from_slice(&[Scalar::zero(), Scalar::one(), -Scalar::one()]);
+            }
+
+            assert_eq!(
+                multiexp::<Bls12>(&bases, &scalars),
+                naive(&bases, &scalars),
+                "{} bases",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_the_surplus_of_the_longer_slice() {
+        let mut rng = StdRng::seed_from_u64(0x3dbe_6259);
+        let bases = bases(&mut rng, 40);
+        let scalars = (0..33)
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            multiexp::<Bls12>(&bases, &scalars),
+            naive(&bases, &scalars)
+        );
+        assert_eq!(
+            multiexp::<Bls12>(&bases[..3], &scalars),
+            naive(&bases[..3], &scalars)
+        );
+    }
+}
@@ -1,21 +1,91 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use group::{prime::PrimeCurveAffine, Curve};
+use group::{ff::PrimeFieldBits, prime::PrimeCurveAffine, Curve, Group};
 use pairing::{MillerLoopResult, MultiMillerLoop};
-use super::{PreparedVerifyingKey, Proof, VerifyingKey, VerificationError};
+use super::{
+    multiexp, AlphaBeta, FixedBaseTable, PreparationConfig, PreparedVerifyingKey, Proof,
+    VerifierError, VerifierKey, VerifyingKey,
+};
 
 use sp_std::ops::{AddAssign, Neg};
+use sp_std::prelude::*;
 
 pub fn prepare_verifying_key<E: MultiMillerLoop>(vk: &VerifyingKey<E>) -> PreparedVerifyingKey<E> {
+    prepare_verifier_key(&VerifierKey::from(vk))
+}
+
+pub fn prepare_verifier_key<E: MultiMillerLoop>(vk: &VerifierKey<E>) -> PreparedVerifyingKey<E> {
+    prepare_verifying_key_with_config(vk, &PreparationConfig::default())
+}
+
+/// Full `VerifyingKey`s are converted with `VerifierKey::from(&vk)`.
+pub fn prepare_verifying_key_with_config<E: MultiMillerLoop>(
+    vk: &VerifierKey<E>,
+    config: &PreparationConfig,
+) -> PreparedVerifyingKey<E> {
+    let alpha_beta = AlphaBeta::Pairing(E::pairing(&vk.alpha_g1, &vk.beta_g2));
+    prepare_with_alpha_beta(vk, alpha_beta, config)
+}
+
+/// Prepares `vk` with a caller supplied `e(alpha_g1, beta_g2)`, such as the
+/// `vk_alphabeta_12` of a snarkjs verification key, which saves the pairing
+/// otherwise computed during preparation.
+///
+/// With `check` set the value is compared against the recomputed pairing and
+/// `VerifierError::InvalidVerifyingKey` is returned on mismatch. Without
+/// it a wrong value is not detected here, it only makes every proof under the
+/// key fail to verify.
+pub fn prepare_verifying_key_with_alpha_beta<E: MultiMillerLoop>(
+    vk: &VerifierKey<E>,
+    alpha_g1_beta_g2: E::Gt,
+    config: &PreparationConfig,
+    check: bool,
+) -> Result<PreparedVerifyingKey<E>, VerifierError> {
+    if check && alpha_g1_beta_g2 != E::pairing(&vk.alpha_g1, &vk.beta_g2) {
+        return Err(VerifierError::InvalidVerifyingKey);
+    }
+
+    Ok(prepare_with_alpha_beta(vk, AlphaBeta::Pairing(alpha_g1_beta_g2), config))
+}
+
+/// Prepares `vk` without computing `e(alpha_g1, beta_g2)`. Every
+/// verification under the key pairs `-alpha_g1` with `beta_g2` in its Miller
+/// loop instead, which costs less than the pairing as long as the key only
+/// verifies a few proofs, e.g. when it is read back from storage for each
+/// proof.
+pub fn prepare_verifying_key_deferred<E: MultiMillerLoop>(
+    vk: &VerifierKey<E>,
+    config: &PreparationConfig,
+) -> PreparedVerifyingKey<E> {
+    let alpha_beta = AlphaBeta::Deferred(vk.alpha_g1.neg(), vk.beta_g2.into());
+    prepare_with_alpha_beta(vk, alpha_beta, config)
+}
+
+fn prepare_with_alpha_beta<E: MultiMillerLoop>(
+    vk: &VerifierKey<E>,
+    alpha_g1_beta_g2: AlphaBeta<E>,
+    config: &PreparationConfig,
+) -> PreparedVerifyingKey<E> {
 
     let gamma = vk.gamma_g2.neg();
     let delta = vk.delta_g2.neg();
 
+    let ic_tables = match config.fixed_base_window {
+        Some(window) => vk
+            .ic
+            .iter()
+            .skip(1)
+            .map(|ic| FixedBaseTable::new(ic, window))
+            .collect(),
+        None => Vec::new(),
+    };
+
     PreparedVerifyingKey {
-        alpha_g1_beta_g2: E::pairing(&vk.alpha_g1, &vk.beta_g2),
+        alpha_g1_beta_g2,
         neg_gamma_g2: gamma.into(),
         neg_delta_g2: delta.into(),
         ic: vk.ic.clone(),
+        ic_tables,
     }
 }
 
@@ -23,16 +93,11 @@ pub fn verify_proof<'a, E: MultiMillerLoop>(
     pvk: &'a PreparedVerifyingKey<E>,
     proof: &Proof<E>,
     public_inputs: &[E::Fr],
-) -> Result<(), VerificationError> {
-    if (public_inputs.len() + 1) != pvk.ic.len() {
-        return Err(VerificationError::InvalidVerifyingKey);
-    }
-
-    let mut acc = pvk.ic[0].to_curve();
-
-    for (i, b) in public_inputs.iter().zip(pvk.ic.iter().skip(1)) {
-        AddAssign::<&E::G1>::add_assign(&mut acc, &(*b * i));
-    }
+) -> Result<(), VerifierError>
+where
+    E::Fr: PrimeFieldBits,
+{
+    let acc = accumulate_inputs(pvk, public_inputs)?;
 
     // The original verification equation is:
     // A * B = alpha * beta + inputs * gamma + C * delta
@@ -42,16 +107,192 @@ pub fn verify_proof<'a, E: MultiMillerLoop>(
     // A * B + inputs * (-gamma) + C * (-delta) = alpha * beta
     // which allows us to do a single final exponentiation.
 
-    if pvk.alpha_g1_beta_g2
-        == E::multi_miller_loop(&[
-            (&proof.a, &proof.b.into()),
-            (&acc.to_affine(), &pvk.neg_gamma_g2),
-            (&proof.c, &pvk.neg_delta_g2),
-        ])
-        .final_exponentiation()
-    {
+    let b = proof.b.into();
+    let acc = acc.to_affine();
+    let mut terms = vec![
+        (&proof.a, &b),
+        (&acc, &pvk.neg_gamma_g2),
+        (&proof.c, &pvk.neg_delta_g2),
+    ];
+    // With a deferred `alpha * beta` the equation becomes
+    // A * B + inputs * (-gamma) + C * (-delta) + (-alpha) * beta = 0
+    let expected = match &pvk.alpha_g1_beta_g2 {
+        AlphaBeta::Pairing(alpha_beta) => *alpha_beta,
+        AlphaBeta::Deferred(neg_alpha, beta) => {
+            terms.push((neg_alpha, beta));
+            E::Gt::identity()
+        }
+    };
+
+    // Compared through the difference, which is never the identity for a
+    // Miller loop result outside `Gt`, e.g. the zero `bn256` gives for points
+    // off the curve.
+    let result = E::multi_miller_loop(&terms).final_exponentiation();
+    if bool::from((result - expected).is_identity()) {
         Ok(())
     } else {
-        Err(VerificationError::InvalidProof)
+        Err(VerifierError::InvalidProof)
+    }
+}
+
+/// Computes `ic[0] + sum(ic[i + 1] * public_inputs[i])`, the point which is
+/// paired with `-gamma` during verification.
+pub(crate) fn accumulate_inputs<E: MultiMillerLoop>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[E::Fr],
+) -> Result<E::G1, VerifierError>
+where
+    E::Fr: PrimeFieldBits,
+{
+    if pvk.ic.is_empty() {
+        return Err(VerifierError::InvalidVerifyingKey);
+    }
+    if (public_inputs.len() + 1) != pvk.ic.len() {
+        return Err(VerifierError::public_input_count(
+            pvk.ic.len() - 1,
+            public_inputs.len(),
+        ));
+    }
+
+    Ok(fold_leading_inputs(pvk, public_inputs))
+}
+
+/// Computes `ic[0] + sum(ic[i + 1] * inputs[i])` for the first
+/// `inputs.len()` public inputs. The caller checks that `inputs` is not
+/// longer than the key's public inputs.
+fn fold_leading_inputs<E: MultiMillerLoop>(
+    pvk: &PreparedVerifyingKey<E>,
+    inputs: &[E::Fr],
+) -> E::G1
+where
+    E::Fr: PrimeFieldBits,
+{
+    let mut acc = pvk.ic[0].to_curve();
+
+    if pvk.ic_tables.is_empty() {
+        AddAssign::<&E::G1>::add_assign(&mut acc, &multiexp::<E>(&pvk.ic[1..], inputs));
+    } else {
+        for (table, input) in pvk.ic_tables.iter().zip(inputs.iter()) {
+            AddAssign::<&E::G1>::add_assign(&mut acc, &table.mul(input));
+        }
+    }
+
+    acc
+}
+
+impl<E: MultiMillerLoop> PreparedVerifyingKey<E> {
+    /// Returns a key with `inputs` applied as the leading public inputs.
+    ///
+    /// The contribution of the fixed inputs is folded into `ic[0]`, so the
+    /// derived key verifies the same proofs as `self` but expects only the
+    /// remaining public inputs, e.g. everything after a Merkle root or round
+    /// ID that is constant for a deployment. Fixed-base tables of the
+    /// remaining inputs are kept.
+    ///
+    /// Fixing more inputs than the key has returns
+    /// `VerifierError::PublicInputCount` with the key's input count as
+    /// `expected`.
+    pub fn with_fixed_inputs(&self, inputs: &[E::Fr]) -> Result<Self, VerifierError>
+    where
+        E::Fr: PrimeFieldBits,
+    {
+        if self.ic.is_empty() {
+            return Err(VerifierError::InvalidVerifyingKey);
+        }
+        if inputs.len() >= self.ic.len() {
+            return Err(VerifierError::public_input_count(
+                self.ic.len() - 1,
+                inputs.len(),
+            ));
+        }
+
+        let mut ic = Vec::with_capacity(self.ic.len() - inputs.len());
+        ic.push(fold_leading_inputs(self, inputs).to_affine());
+        ic.extend_from_slice(&self.ic[inputs.len() + 1..]);
+
+        Ok(PreparedVerifyingKey {
+            alpha_g1_beta_g2: self.alpha_g1_beta_g2.clone(),
+            neg_gamma_g2: self.neg_gamma_g2.clone(),
+            neg_delta_g2: self.neg_delta_g2.clone(),
+            ic,
+            ic_tables: self.ic_tables.iter().skip(inputs.len()).cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{Bls12, Scalar};
+    use verifier_traits::Groth16Verifier;
+
+    use super::{prepare_verifying_key_deferred, prepare_verifying_key_with_config, verify_proof};
+    use crate::{
+        BellmanVerifier, PreparationConfig, PreparedVerifyingKey, Proof, VerifierError,
+    };
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    /// The fixture key prepared without and with fixed-base tables, and with
+    /// tables and a deferred `e(alpha, beta)`, with its proof and inputs.
+    fn fixture() -> (Vec<PreparedVerifyingKey<Bls12>>, Proof<Bls12>, Vec<Scalar>) {
+        let vk = Backend::decode_verifying_key(include_bytes!("../../fixtures/bls12381/vkey.bin"))
+            .unwrap();
+        let proof =
+            Backend::decode_proof(include_bytes!("../../fixtures/bls12381/proof.bin")).unwrap();
+        let inputs =
+            Backend::decode_public_inputs(include_bytes!("../../fixtures/bls12381/public.bin"))
+                .unwrap();
+        let tables = PreparationConfig {
+            fixed_base_window: Some(4),
+        };
+        let keys = vec![
+            prepare_verifying_key_with_config(&vk, &PreparationConfig::default()),
+            prepare_verifying_key_with_config(&vk, &tables),
+            prepare_verifying_key_deferred(&vk, &tables),
+        ];
+        (keys, proof, inputs)
+    }
+
+    #[test]
+    fn folded_keys_verify_like_the_full_key() {
+        let (keys, proof, inputs) = fixture();
+
+        for pvk in &keys {
+            assert_eq!(verify_proof(pvk, &proof, &inputs), Ok(()));
+
+            let unchanged = pvk.with_fixed_inputs(&[]).unwrap();
+            assert_eq!(verify_proof(&unchanged, &proof, &inputs), Ok(()));
+
+            let folded = pvk.with_fixed_inputs(&inputs).unwrap();
+            assert_eq!(verify_proof(&folded, &proof, &[]), Ok(()));
+            assert_eq!(
+                verify_proof(&folded, &proof, &inputs),
+                Err(VerifierError::PublicInputCount {
+                    expected: 0,
+                    actual: 1
+                })
+            );
+
+            let wrong = pvk.with_fixed_inputs(&[inputs[0] + Scalar::one()]).unwrap();
+            assert_eq!(
+                verify_proof(&wrong, &proof, &[]),
+                Err(VerifierError::InvalidProof)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_fixing_too_many_inputs() {
+        let (keys, _, inputs) = fixture();
+
+        for pvk in &keys {
+            assert_eq!(
+                pvk.with_fixed_inputs(&[inputs[0], inputs[0]]).err(),
+                Some(VerifierError::PublicInputCount {
+                    expected: 1,
+                    actual: 2
+                })
+            );
+        }
     }
 }
@@ -0,0 +1,191 @@
+use bellman_verifier::{bn256::Bn256, BellmanVerifier};
+use bls12_381::Bls12;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
+use cw2::set_contract_version;
+use verifier_traits::{u256_from_decimal, u256_from_hex, Groth16Verifier, VerifierError};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, KeyResponse, ProofHex, QueryMsg, VerifyResponse, VkeyHex,
+};
+use crate::state::{Config, Curve, Verification, CONFIG, NEXT_VERIFICATION_ID, VERIFICATIONS};
+
+const CONTRACT_NAME: &str = "crates.io:cosmwasm-groth16-verifier";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let key = key_bytes(&msg.vkey)?;
+    let key = match msg.curve {
+        Curve::Bn254 => prepared_key::<BellmanVerifier<Bn256>>(&key)?,
+        Curve::Bls12381 => prepared_key::<BellmanVerifier<Bls12>>(&key)?,
+    };
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            curve: msg.curve,
+            key: key.into(),
+        },
+    )?;
+    NEXT_VERIFICATION_ID.save(deps.storage, &0)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::VerifyProof {
+            proof,
+            public_inputs,
+        } => execute_verify_proof(deps, info, proof, public_inputs),
+    }
+}
+
+fn execute_verify_proof(
+    deps: DepsMut,
+    info: MessageInfo,
+    proof: ProofHex,
+    public_inputs: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let verified = verify(&config, &proof, &public_inputs)?;
+
+    let id = NEXT_VERIFICATION_ID.load(deps.storage)?;
+    NEXT_VERIFICATION_ID.save(deps.storage, &(id + 1))?;
+    VERIFICATIONS.save(
+        deps.storage,
+        id,
+        &Verification {
+            submitter: info.sender,
+            public_inputs,
+            verified,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "verify_proof")
+        .add_attribute("verification_id", id.to_string())
+        .add_attribute("verified", verified.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Key {} => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(to_binary(&KeyResponse {
+                curve: config.curve,
+                key: config.key,
+            })?)
+        }
+        QueryMsg::Verification { id } => Ok(to_binary(&VERIFICATIONS.load(deps.storage, id)?)?),
+        QueryMsg::VerifyProof {
+            proof,
+            public_inputs,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let verified = verify(&config, &proof, &public_inputs)?;
+            Ok(to_binary(&VerifyResponse { verified })?)
+        }
+    }
+}
+
+/// Whether `proof` verifies, with errors only for undecodable input.
+fn verify(
+    config: &Config,
+    proof: &ProofHex,
+    public_inputs: &[String],
+) -> Result<bool, ContractError> {
+    let proof = proof_bytes(proof)?;
+    let public_inputs = public_input_bytes(public_inputs)?;
+
+    let result = match config.curve {
+        Curve::Bn254 => verify_with::<BellmanVerifier<Bn256>>(&config.key, &proof, &public_inputs),
+        Curve::Bls12381 => {
+            verify_with::<BellmanVerifier<Bls12>>(&config.key, &proof, &public_inputs)
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(true),
+        Err(VerifierError::InvalidProof) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Checks `key` and encodes it for `Config::key`.
+fn prepared_key<V: Groth16Verifier>(key: &[u8]) -> Result<Vec<u8>, VerifierError> {
+    let vk = V::decode_verifying_key(key)?;
+    Ok(V::encode_prepared_verifying_key(&vk))
+}
+
+fn verify_with<V: Groth16Verifier>(
+    key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<(), VerifierError> {
+    let pvk = V::decode_prepared_verifying_key(key)?;
+
+    V::verify_encoded(&pvk, proof, public_inputs)
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, ContractError> {
+    value
+        .strip_prefix("0x")
+        .and_then(|h| hex::decode(h).ok())
+        .ok_or_else(|| ContractError::InvalidHex {
+            field: field.to_string(),
+        })
+}
+
+/// The key in the `Groth16Verifier` layout.
+fn key_bytes(vkey: &VkeyHex) -> Result<Vec<u8>, ContractError> {
+    let mut key = Vec::new();
+    for (field, value) in [
+        ("alpha_1", &vkey.alpha_1),
+        ("beta_2", &vkey.beta_2),
+        ("gamma_2", &vkey.gamma_2),
+        ("delta_2", &vkey.delta_2),
+        ("ic0", &vkey.ic0),
+        ("ic1", &vkey.ic1),
+    ] {
+        key.extend(decode_hex(field, value)?);
+    }
+    Ok(key)
+}
+
+fn proof_bytes(proof: &ProofHex) -> Result<Vec<u8>, ContractError> {
+    let mut bytes = decode_hex("pi_a", &proof.pi_a)?;
+    bytes.extend(decode_hex("pi_b", &proof.pi_b)?);
+    bytes.extend(decode_hex("pi_c", &proof.pi_c)?);
+    Ok(bytes)
+}
+
+fn public_input_bytes(public_inputs: &[String]) -> Result<Vec<u8>, ContractError> {
+    let mut bytes = Vec::new();
+    for (index, input) in public_inputs.iter().enumerate() {
+        let input = if input.starts_with("0x") {
+            u256_from_hex(input)
+        } else {
+            u256_from_decimal(input)
+        };
+        bytes.extend(input.map_err(|error| ContractError::InvalidPublicInput { index, error })?);
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+use verifier_traits::{InputError, VerifierError};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{field} is not 0x prefixed hex")]
+    InvalidHex { field: String },
+
+    #[error("public input {index}: {error}")]
+    InvalidPublicInput { index: usize, error: InputError },
+
+    #[error("malformed verifying key")]
+    InvalidVerifyingKey,
+
+    #[error("proof or public inputs do not encode curve points and scalars")]
+    InvalidEncoding,
+
+    #[error("expected {expected} public inputs, got {actual}")]
+    PublicInputCount { expected: u32, actual: u32 },
+}
+
+impl From<VerifierError> for ContractError {
+    fn from(e: VerifierError) -> Self {
+        match e {
+            VerifierError::InvalidVerifyingKey => ContractError::InvalidVerifyingKey,
+            VerifierError::PublicInputCount { expected, actual } => {
+                ContractError::PublicInputCount { expected, actual }
+            }
+            // Proofs that do not verify are recorded, not returned as errors.
+            VerifierError::InvalidProof | VerifierError::InvalidEncoding => {
+                ContractError::InvalidEncoding
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! A CosmWasm contract verifying snarkjs Groth16 proofs, so that MACI rounds
+//! can settle on Cosmos chains.
+//!
+//! The contract is instantiated with the `vkey_hex.json` written by
+//! `prove/src/adapt_maci.js` and verifies proofs given as its
+//! `proof_hex.json`. Every proof submitted through `ExecuteMsg::VerifyProof`
+//! is recorded with its outcome and can be queried by id.
+
+pub mod contract;
+mod error;
+pub mod msg;
+pub mod state;
+
+pub use crate::error::ContractError;
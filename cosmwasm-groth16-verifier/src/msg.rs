@@ -0,0 +1,70 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
+
+use crate::state::{Curve, Verification};
+
+/// A verifying key as in `vkey_hex.json`, each point uncompressed and `0x`
+/// prefixed. `adapt_maci.js` writes keys with one public input.
+#[cw_serde]
+pub struct VkeyHex {
+    pub alpha_1: String,
+    pub beta_2: String,
+    pub gamma_2: String,
+    pub delta_2: String,
+    pub ic0: String,
+    pub ic1: String,
+}
+
+/// A proof as in `proof_hex.json`.
+#[cw_serde]
+pub struct ProofHex {
+    pub pi_a: String,
+    pub pi_b: String,
+    pub pi_c: String,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// `Bn254` for snarkjs' bn128, which MACI uses.
+    pub curve: Curve,
+    pub vkey: VkeyHex,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Verifies a proof and records the outcome under the id returned in
+    /// the `verification_id` attribute. Proofs that do not verify are
+    /// recorded too, only undecodable ones fail the transaction.
+    VerifyProof {
+        proof: ProofHex,
+        /// Decimal as in snarkjs' `public.json`, or `0x` prefixed hex.
+        public_inputs: Vec<String>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(KeyResponse)]
+    Key {},
+    #[returns(Verification)]
+    Verification { id: u64 },
+    /// Verifies a proof without recording it.
+    #[returns(VerifyResponse)]
+    VerifyProof {
+        proof: ProofHex,
+        public_inputs: Vec<String>,
+    },
+}
+
+#[cw_serde]
+pub struct KeyResponse {
+    pub curve: Curve,
+    /// The key in the `verifier_traits::Groth16Verifier` layout.
+    pub key: Binary,
+}
+
+#[cw_serde]
+pub struct VerifyResponse {
+    pub verified: bool,
+}
@@ -0,0 +1,33 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary};
+use cw_storage_plus::{Item, Map};
+
+/// The curve of the verifying key.
+#[cw_serde]
+pub enum Curve {
+    /// BN254, snarkjs' bn128.
+    Bn254,
+    Bls12381,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub curve: Curve,
+    /// The key as `Groth16Verifier::encode_prepared_verifying_key` writes
+    /// it, checked at instantiation so that verification neither checks nor
+    /// prepares it again. For `BellmanVerifier` this is the
+    /// `verifier_traits::Groth16Verifier` key layout.
+    pub key: Binary,
+}
+
+/// A proof submitted through `ExecuteMsg::VerifyProof`.
+#[cw_serde]
+pub struct Verification {
+    pub submitter: Addr,
+    pub public_inputs: Vec<String>,
+    pub verified: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const NEXT_VERIFICATION_ID: Item<u64> = Item::new("next_verification_id");
+pub const VERIFICATIONS: Map<u64, Verification> = Map::new("verifications");
@@ -0,0 +1,193 @@
+use cosmwasm_groth16_verifier::contract::{execute, instantiate, query};
+use cosmwasm_groth16_verifier::msg::{
+    ExecuteMsg, InstantiateMsg, KeyResponse, ProofHex, QueryMsg, VerifyResponse, VkeyHex,
+};
+use cosmwasm_groth16_verifier::state::{Curve, Verification};
+use cosmwasm_groth16_verifier::ContractError;
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+
+const ADMIN: &str = "admin";
+const SUBMITTER: &str = "coordinator";
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+struct Fixture {
+    vkey: VkeyHex,
+    proof: ProofHex,
+    public_inputs: Vec<String>,
+}
+
+fn bn128() -> Fixture {
+    Fixture {
+        vkey: serde_json::from_str(include_str!("../../fixtures/bn128/vkey_hex.json")).unwrap(),
+        proof: serde_json::from_str(include_str!("../../fixtures/bn128/proof_hex.json")).unwrap(),
+        public_inputs: serde_json::from_str(include_str!("../../fixtures/bn128/public.json")).unwrap(),
+    }
+}
+
+fn bls12381() -> Fixture {
+    Fixture {
+        vkey: serde_json::from_str(include_str!("../../fixtures/bls12381/vkey_hex.json")).unwrap(),
+        proof: serde_json::from_str(include_str!("../../fixtures/bls12381/proof_hex.json")).unwrap(),
+        public_inputs: serde_json::from_str(include_str!("../../fixtures/bls12381/public.json"))
+            .unwrap(),
+    }
+}
+
+fn instantiate_verifier(app: &mut App, curve: Curve, vkey: VkeyHex) -> anyhow::Result<Addr> {
+    let code_id = app.store_code(contract());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN),
+        &InstantiateMsg { curve, vkey },
+        &[],
+        "groth16-verifier",
+        None,
+    )
+}
+
+fn verify_proof(
+    app: &mut App,
+    verifier: &Addr,
+    proof: ProofHex,
+    public_inputs: Vec<String>,
+) -> anyhow::Result<AppResponse> {
+    app.execute_contract(
+        Addr::unchecked(SUBMITTER),
+        verifier.clone(),
+        &ExecuteMsg::VerifyProof {
+            proof,
+            public_inputs,
+        },
+        &[],
+    )
+}
+
+fn attribute(response: &AppResponse, key: &str) -> String {
+    response
+        .events
+        .iter()
+        .flat_map(|e| &e.attributes)
+        .find(|a| a.key == key)
+        .unwrap()
+        .value
+        .clone()
+}
+
+fn verification(app: &App, verifier: &Addr, id: u64) -> Verification {
+    app.wrap()
+        .query_wasm_smart(verifier, &QueryMsg::Verification { id })
+        .unwrap()
+}
+
+#[test]
+fn verifies_and_records_maci_proofs() {
+    let mut app = App::default();
+    let fixture = bn128();
+    let verifier = instantiate_verifier(&mut app, Curve::Bn254, fixture.vkey).unwrap();
+
+    let key: KeyResponse = app
+        .wrap()
+        .query_wasm_smart(&verifier, &QueryMsg::Key {})
+        .unwrap();
+    assert_eq!(key.curve, Curve::Bn254);
+    assert_eq!(key.key.len(), 64 + 3 * 128 + 2 * 64);
+
+    let response = verify_proof(
+        &mut app,
+        &verifier,
+        fixture.proof.clone(),
+        fixture.public_inputs.clone(),
+    )
+    .unwrap();
+    assert_eq!(attribute(&response, "verification_id"), "0");
+    assert_eq!(attribute(&response, "verified"), "true");
+
+    // A wrong public input is recorded as rejected, not failed.
+    let response = verify_proof(&mut app, &verifier, fixture.proof, vec!["0x22".into()]).unwrap();
+    assert_eq!(attribute(&response, "verification_id"), "1");
+    assert_eq!(attribute(&response, "verified"), "false");
+
+    assert_eq!(
+        verification(&app, &verifier, 0),
+        Verification {
+            submitter: Addr::unchecked(SUBMITTER),
+            public_inputs: fixture.public_inputs,
+            verified: true,
+        }
+    );
+    assert!(!verification(&app, &verifier, 1).verified);
+}
+
+#[test]
+fn queries_proofs_without_recording() {
+    let mut app = App::default();
+    let fixture = bls12381();
+    let verifier = instantiate_verifier(&mut app, Curve::Bls12381, fixture.vkey).unwrap();
+
+    let response: VerifyResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &verifier,
+            &QueryMsg::VerifyProof {
+                proof: fixture.proof,
+                public_inputs: fixture.public_inputs,
+            },
+        )
+        .unwrap();
+    assert!(response.verified);
+
+    assert!(app
+        .wrap()
+        .query_wasm_smart::<Verification>(&verifier, &QueryMsg::Verification { id: 0 })
+        .is_err());
+}
+
+#[test]
+fn rejects_malformed_messages() {
+    let mut app = App::default();
+
+    // A bn128 key is not a BLS12-381 key.
+    let err = instantiate_verifier(&mut app, Curve::Bls12381, bn128().vkey).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidEncoding
+    );
+
+    let mut vkey = bn128().vkey;
+    vkey.ic1 = vkey.ic1.trim_start_matches("0x").to_string();
+    let err = instantiate_verifier(&mut app, Curve::Bn254, vkey).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidHex {
+            field: "ic1".into()
+        }
+    );
+
+    let fixture = bn128();
+    let verifier = instantiate_verifier(&mut app, Curve::Bn254, fixture.vkey).unwrap();
+
+    let err = verify_proof(&mut app, &verifier, fixture.proof.clone(), vec![]).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PublicInputCount {
+            expected: 1,
+            actual: 0
+        }
+    );
+
+    let err = verify_proof(&mut app, &verifier, fixture.proof, vec!["33a".into()]).unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidPublicInput { index: 0, .. }
+    ));
+
+    // Failed transactions are not recorded.
+    assert!(app
+        .wrap()
+        .query_wasm_smart::<Verification>(&verifier, &QueryMsg::Verification { id: 0 })
+        .is_err());
+}
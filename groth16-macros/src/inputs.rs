@@ -0,0 +1,160 @@
+//! Expansion of `#[public_inputs]`.
+
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::quote;
+use syn::{AttributeArgs, Fields, ItemStruct, Lit, LitStr, Meta, NestedMeta};
+
+use crate::sym::{self, Field};
+
+/// `inputHash` as `input_hash`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+fn field_ident(field: &Field, span: Span) -> syn::Result<Ident> {
+    let name = snake_case(&field.name);
+    if syn::parse_str::<Ident>(&name).is_ok() {
+        return Ok(Ident::new(&name, span));
+    }
+    match name.as_str() {
+        "self" | "super" | "crate" | "_" => Err(syn::Error::new(
+            span,
+            format!("`main.{}` cannot be a field name", field.name),
+        )),
+        // Keywords such as `in` become raw identifiers.
+        _ => Ok(Ident::new_raw(&name, span)),
+    }
+}
+
+fn field_doc(field: &Field) -> String {
+    match field.len() {
+        1 if field.shape.is_empty() => {
+            format!(" `main.{}`, public input {}.", field.name, field.witness)
+        }
+        n => format!(
+            " `main.{}`, public inputs {} to {}.",
+            field.name,
+            field.witness,
+            field.witness + n - 1
+        ),
+    }
+}
+
+/// The `sym` and `vkey` paths of the attribute.
+fn paths(args: AttributeArgs) -> syn::Result<(LitStr, LitStr)> {
+    let mut sym = None;
+    let mut vkey = None;
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("sym") => match nv.lit {
+                Lit::Str(path) => sym = Some(path),
+                lit => return Err(syn::Error::new_spanned(lit, "expected a path")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("vkey") => match nv.lit {
+                Lit::Str(path) => vkey = Some(path),
+                lit => return Err(syn::Error::new_spanned(lit, "expected a path")),
+            },
+            arg => {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "expected `sym = \"...\"` or `vkey = \"...\"`",
+                ))
+            }
+        }
+    }
+
+    let missing =
+        |name| syn::Error::new(Span::call_site(), format!("missing `{} = \"...\"`", name));
+    Ok((
+        sym.ok_or_else(|| missing("sym"))?,
+        vkey.ok_or_else(|| missing("vkey"))?,
+    ))
+}
+
+pub fn expand(args: AttributeArgs, item: ItemStruct) -> syn::Result<TokenStream> {
+    let (sym_path, vkey_path) = paths(args)?;
+    if !matches!(item.fields, Fields::Unit) || !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item,
+            "expected a unit struct, the fields are generated",
+        ));
+    }
+
+    let (sym_file, sym) = crate::read(&sym_path)?;
+    let (vkey_file, key) = crate::read_key(&vkey_path)?;
+    let n_public = key.ic.len() - 1;
+    let fields = sym::public_fields(&sym, n_public)
+        .map_err(|e| syn::Error::new(sym_path.span(), format!("{}: {}", sym_file, e)))?;
+
+    let ItemStruct {
+        attrs, vis, ident, ..
+    } = item;
+    let names = fields
+        .iter()
+        .map(|f| field_ident(f, sym_path.span()))
+        .collect::<syn::Result<Vec<_>>>()?;
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            return Err(syn::Error::new(
+                sym_path.span(),
+                format!("two public signals are named `{}`", name),
+            ));
+        }
+    }
+    let docs = fields.iter().map(field_doc);
+    let types = fields.iter().map(|f| {
+        f.shape.iter().rev().fold(quote!(F), |ty, &n| {
+            let n = Literal::usize_unsuffixed(n);
+            quote!([#ty; #n])
+        })
+    });
+    let pushes = fields.iter().zip(&names).map(|(f, name)| {
+        if f.shape.is_empty() {
+            return quote!(inputs.push(self.#name););
+        }
+        let flatten = (1..f.shape.len()).map(|_| quote!(.flatten()));
+        quote! {
+            inputs.extend(::core::iter::IntoIterator::into_iter(self.#name) #(#flatten)*);
+        }
+    });
+    let len = Literal::usize_unsuffixed(n_public);
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #ident<F> {
+            #(
+                #[doc = #docs]
+                #vis #names: #types,
+            )*
+        }
+
+        const _: () = {
+            extern crate alloc;
+
+            // Rebuilds the calling crate when the circuit changes.
+            const _: &[u8] = include_bytes!(#sym_file);
+            const _: &[u8] = include_bytes!(#vkey_file);
+
+            impl<F> #ident<F> {
+                /// The number of public inputs.
+                pub const LEN: usize = #len;
+
+                /// The public inputs in the order the verifier takes them.
+                pub fn into_vec(self) -> alloc::vec::Vec<F> {
+                    let mut inputs = alloc::vec::Vec::with_capacity(Self::LEN);
+                    #(#pushes)*
+                    inputs
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,274 @@
+//! Reading and checking verification keys at compile time.
+
+use bellman_verifier::bn256::Bn256;
+use bls12_381::Bls12;
+use group::prime::PrimeCurveAffine;
+use group::UncompressedEncoding;
+use pairing::Engine;
+use serde_json::Value;
+use verifier_traits::{be_from_decimal, InputError};
+
+/// The curve of a key, which decides the engine it is embedded for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Bn254,
+    Bls12_381,
+}
+
+impl Curve {
+    /// Bytes of a base field element.
+    fn fq_size(self) -> usize {
+        match self {
+            Curve::Bn254 => 32,
+            Curve::Bls12_381 => 48,
+        }
+    }
+}
+
+/// A key with its points in the uncompressed encoding of `curve`.
+#[derive(Debug)]
+pub struct Key {
+    pub curve: Curve,
+    pub alpha_g1: Vec<u8>,
+    pub beta_g2: Vec<u8>,
+    pub gamma_g2: Vec<u8>,
+    pub delta_g2: Vec<u8>,
+    pub ic: Vec<Vec<u8>>,
+}
+
+/// Reads a snarkjs `verification_key.json` or an adapter
+/// `vkey_uncompressed.json`, and checks that all points are valid and not
+/// the identity.
+pub fn parse(json: &str) -> Result<Key, String> {
+    let v: Value = serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    let key = if v.get("vk_alpha_1").is_some() {
+        from_snarkjs(&v)?
+    } else if v.get("alpha_1").is_some() {
+        from_uncompressed(&v)?
+    } else {
+        return Err("neither a snarkjs verification key nor a vkey_uncompressed.json".into());
+    };
+
+    match key.curve {
+        Curve::Bn254 => check::<Bn256>(&key)?,
+        Curve::Bls12_381 => check::<Bls12>(&key)?,
+    }
+    Ok(key)
+}
+
+fn from_snarkjs(v: &Value) -> Result<Key, String> {
+    let curve = match v["curve"].as_str() {
+        Some("bn128") => Curve::Bn254,
+        Some("bls12381") => Curve::Bls12_381,
+        Some(other) => return Err(format!("unsupported curve `{}`", other)),
+        None => return Err("missing `curve`".into()),
+    };
+
+    let ic = v["IC"]
+        .as_array()
+        .ok_or("missing `IC`")?
+        .iter()
+        .enumerate()
+        .map(|(i, p)| g1_from_snarkjs(curve, &format!("IC[{}]", i), p))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(n) = v["nPublic"].as_u64() {
+        if ic.len() as u64 != n + 1 {
+            return Err(format!("{} IC points for {} public inputs", ic.len(), n));
+        }
+    }
+
+    Ok(Key {
+        curve,
+        alpha_g1: g1_from_snarkjs(curve, "vk_alpha_1", &v["vk_alpha_1"])?,
+        beta_g2: g2_from_snarkjs(curve, "vk_beta_2", &v["vk_beta_2"])?,
+        gamma_g2: g2_from_snarkjs(curve, "vk_gamma_2", &v["vk_gamma_2"])?,
+        delta_g2: g2_from_snarkjs(curve, "vk_delta_2", &v["vk_delta_2"])?,
+        ic,
+    })
+}
+
+/// A decimal string as `size` big-endian bytes.
+fn fq_from_decimal(name: &str, v: &Value, size: usize) -> Result<Vec<u8>, String> {
+    let mut be = vec![0u8; size];
+    match v.as_str().map(|s| be_from_decimal(s, &mut be)) {
+        Some(Ok(())) => Ok(be),
+        Some(Err(InputError::Overflow)) => Err(format!("`{}`: coordinate out of range", name)),
+        _ => Err(format!("`{}`: expected a decimal string", name)),
+    }
+}
+
+/// `[x, y, "1"]` as `x || y`.
+fn g1_from_snarkjs(curve: Curve, name: &str, v: &Value) -> Result<Vec<u8>, String> {
+    match v.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if z == "1" => {
+            let size = curve.fq_size();
+            Ok([
+                fq_from_decimal(name, x, size)?,
+                fq_from_decimal(name, y, size)?,
+            ]
+            .concat())
+        }
+        _ => Err(format!(
+            "`{}`: expected an affine G1 point [x, y, \"1\"]",
+            name
+        )),
+    }
+}
+
+/// `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]` as
+/// `x.c1 || x.c0 || y.c1 || y.c0`.
+fn g2_from_snarkjs(curve: Curve, name: &str, v: &Value) -> Result<Vec<u8>, String> {
+    let coords = |c: &Value| match c.as_array().map(Vec::as_slice) {
+        Some([c0, c1]) => Some((c0.clone(), c1.clone())),
+        _ => None,
+    };
+    let err = || {
+        format!(
+            "`{}`: expected an affine G2 point [x, y, [\"1\", \"0\"]]",
+            name
+        )
+    };
+
+    match v.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if coords(z) == Some(("1".into(), "0".into())) => {
+            let (x0, x1) = coords(x).ok_or_else(err)?;
+            let (y0, y1) = coords(y).ok_or_else(err)?;
+            let size = curve.fq_size();
+            Ok([
+                fq_from_decimal(name, &x1, size)?,
+                fq_from_decimal(name, &x0, size)?,
+                fq_from_decimal(name, &y1, size)?,
+                fq_from_decimal(name, &y0, size)?,
+            ]
+            .concat())
+        }
+        _ => Err(err()),
+    }
+}
+
+fn from_uncompressed(v: &Value) -> Result<Key, String> {
+    let bytes = |name: &str, v: &Value| -> Result<Vec<u8>, String> {
+        v.as_array()
+            .and_then(|a| a.iter().map(|b| b.as_u64()?.try_into().ok()).collect())
+            .ok_or_else(|| format!("`{}`: expected an array of bytes", name))
+    };
+
+    let alpha_g1 = bytes("alpha_1", &v["alpha_1"])?;
+    let curve = match alpha_g1.len() {
+        64 => Curve::Bn254,
+        96 => Curve::Bls12_381,
+        n => {
+            return Err(format!(
+                "`alpha_1`: {} bytes is no uncompressed G1 point",
+                n
+            ))
+        }
+    };
+
+    Ok(Key {
+        curve,
+        alpha_g1,
+        beta_g2: bytes("beta_2", &v["beta_2"])?,
+        gamma_g2: bytes("gamma_2", &v["gamma_2"])?,
+        delta_g2: bytes("delta_2", &v["delta_2"])?,
+        ic: v["ic"]
+            .as_array()
+            .ok_or("missing `ic`")?
+            .iter()
+            .enumerate()
+            .map(|(i, p)| bytes(&format!("ic[{}]", i), p))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn check_point<G: UncompressedEncoding + PrimeCurveAffine>(
+    name: &str,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let mut repr = G::Uncompressed::default();
+    if repr.as_ref().len() != bytes.len() {
+        return Err(format!(
+            "`{}`: expected {} bytes",
+            name,
+            repr.as_ref().len()
+        ));
+    }
+    repr.as_mut().copy_from_slice(bytes);
+
+    let point: Option<G> = G::from_uncompressed(&repr).into();
+    match point {
+        None => Err(format!(
+            "`{}`: not a point of the prime order subgroup",
+            name
+        )),
+        Some(p) if bool::from(p.is_identity()) => Err(format!("`{}`: point at infinity", name)),
+        Some(_) => Ok(()),
+    }
+}
+
+fn check<E: Engine>(key: &Key) -> Result<(), String> {
+    check_point::<E::G1Affine>("alpha_1", &key.alpha_g1)?;
+    check_point::<E::G2Affine>("beta_2", &key.beta_g2)?;
+    check_point::<E::G2Affine>("gamma_2", &key.gamma_g2)?;
+    check_point::<E::G2Affine>("delta_2", &key.delta_g2)?;
+    if key.ic.is_empty() {
+        return Err("no IC points".into());
+    }
+    for (i, p) in key.ic.iter().enumerate() {
+        check_point::<E::G1Affine>(&format!("IC[{}]", i), p)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snarkjs() -> Value {
+        serde_json::from_str(include_str!("../../fixtures/bn128/verification_key.json")).unwrap()
+    }
+
+    fn parse_value(v: &Value) -> Result<Key, String> {
+        parse(&v.to_string())
+    }
+
+    #[test]
+    fn reads_both_formats() {
+        let key = parse_value(&snarkjs()).unwrap();
+        assert_eq!(key.curve, Curve::Bn254);
+        assert_eq!(key.ic.len(), 2);
+
+        let key = parse(include_str!(
+            "../../fixtures/bls12381/vkey_uncompressed.json"
+        ))
+        .unwrap();
+        assert_eq!(key.curve, Curve::Bls12_381);
+        assert_eq!(key.beta_g2.len(), 192);
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut v = snarkjs();
+        v["vk_alpha_1"][1] = "1".into();
+        assert_eq!(
+            parse_value(&v).unwrap_err(),
+            "`alpha_1`: not a point of the prime order subgroup"
+        );
+
+        let mut v = snarkjs();
+        v["vk_beta_2"][2] = serde_json::json!(["0", "0"]);
+        assert!(parse_value(&v).unwrap_err().starts_with("`vk_beta_2`"));
+
+        let mut v = snarkjs();
+        v["nPublic"] = 2.into();
+        assert_eq!(
+            parse_value(&v).unwrap_err(),
+            "2 IC points for 2 public inputs"
+        );
+
+        let mut v = snarkjs();
+        v["curve"] = "bls12377".into();
+        assert_eq!(parse_value(&v).unwrap_err(), "unsupported curve `bls12377`");
+    }
+}
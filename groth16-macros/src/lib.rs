@@ -0,0 +1,135 @@
+//! `include_vkey!` embeds a Groth16 verification key in the binary, and
+//! `#[public_inputs]` names the public inputs of a circuit, both from the
+//! circuit's files at compile time.
+//!
+//! ```ignore
+//! use bellman_verifier::{prepare_verifier_key, VerifierKey};
+//! use groth16_macros::include_vkey;
+//!
+//! fn key() -> &'static VerifierKey<bellman_verifier::bn256::Bn256> {
+//!     include_vkey!("circuit/Multiplication/verification_key.json")
+//! }
+//! ```
+//!
+//! The path is relative to the crate's `Cargo.toml`. The file is either a
+//! snarkjs `verification_key.json`, whose `curve` picks the engine, or a
+//! `vkey_uncompressed.json` written by the adapter, whose point sizes pick
+//! it. `bn128` keys expand to a
+//! `&'static VerifierKey<bellman_verifier::bn256::Bn256>` and `bls12381`
+//! keys to a `&'static VerifierKey<bls12_381::Bls12>`, so the calling crate
+//! depends on `bellman-verifier` with its default `std` feature and, for
+//! BLS12-381, `bls12_381`.
+//!
+//! All points are checked to be in the prime order subgroup and not the
+//! identity while compiling, and a bad key is a compile error. The checked
+//! key is embedded as a `static` byte array in the layout of
+//! `verifier_traits`.
+//!
+//! The points of these curves have no `const` constructors, so the key
+//! cannot be a `const`. Each use of the macro instead owns a `static` that
+//! decodes the embedded bytes the first time the expression is evaluated,
+//! without JSON parsing or subgroup checks, and every later evaluation
+//! returns a reference to the same key. The decode panics only if the
+//! embedded bytes are not the key that was checked, which cannot happen.
+//! Preparing the key is still up to the caller.
+
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, AttributeArgs, ItemStruct, LitStr};
+
+mod inputs;
+mod key;
+mod sym;
+
+use crate::key::{Curve, Key};
+
+/// Embeds the verification key at a path relative to `CARGO_MANIFEST_DIR`
+/// and expands to a `&'static bellman_verifier::VerifierKey`, decoded on
+/// first use, see the crate documentation.
+#[proc_macro]
+pub fn include_vkey(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    match expand(&path) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Generates the fields of a unit struct from the public signals of a
+/// circuit.
+///
+/// ```ignore
+/// #[public_inputs(sym = "circuit.sym", vkey = "verification_key.json")]
+/// pub struct MultiplierInputs;
+///
+/// let inputs = MultiplierInputs { c: Fr::from(33) }.into_vec();
+/// ```
+///
+/// The signals come from the circom `.sym` file and their number from the
+/// key, both at paths relative to `CARGO_MANIFEST_DIR`. Each field holds a
+/// public signal or array of signals of the main component, named in snake
+/// case, and `into_vec` lists them in witness order, as `verify_proof` takes
+/// them. The struct is generic over the scalar type, so it serves both
+/// curves.
+#[proc_macro_attribute]
+pub fn public_inputs(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let item = parse_macro_input!(item as ItemStruct);
+    match inputs::expand(args, item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Reads a file at a path relative to `CARGO_MANIFEST_DIR`, returning its
+/// absolute path and contents.
+fn read(path: &LitStr) -> syn::Result<(String, String)> {
+    let error = |msg: String| syn::Error::new(path.span(), msg);
+
+    let dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error("CARGO_MANIFEST_DIR is not set".into()))?;
+    let file = PathBuf::from(dir).join(path.value());
+    let contents = std::fs::read_to_string(&file)
+        .map_err(|e| error(format!("cannot read {}: {}", file.display(), e)))?;
+    Ok((file.to_string_lossy().into_owned(), contents))
+}
+
+fn read_key(path: &LitStr) -> syn::Result<(String, Key)> {
+    let (file, json) = read(path)?;
+    let key =
+        key::parse(&json).map_err(|e| syn::Error::new(path.span(), format!("{}: {}", file, e)))?;
+    Ok((file, key))
+}
+
+fn expand(path: &LitStr) -> syn::Result<TokenStream2> {
+    let (file, key) = read_key(path)?;
+
+    let engine = match key.curve {
+        Curve::Bn254 => quote!(::bellman_verifier::bn256::Bn256),
+        Curve::Bls12_381 => quote!(::bls12_381::Bls12),
+    };
+    let mut bytes = Vec::new();
+    for point in [&key.alpha_g1, &key.beta_g2, &key.gamma_g2, &key.delta_g2]
+        .into_iter()
+        .chain(&key.ic)
+    {
+        bytes.extend_from_slice(point);
+    }
+    let len = bytes.len();
+    let bytes = Literal::byte_string(&bytes);
+
+    Ok(quote! {{
+        // Rebuilds the calling crate when the key changes.
+        const _: &[u8] = include_bytes!(#file);
+        static KEY: ::bellman_verifier::__private::Lazy<::bellman_verifier::VerifierKey<#engine>> =
+            ::bellman_verifier::__private::Lazy::new(|| {
+                static BYTES: [u8; #len] = *#bytes;
+                ::bellman_verifier::__private::verifier_key_unchecked(&BYTES)
+            });
+
+        &*KEY
+    }})
+}
@@ -0,0 +1,221 @@
+//! Reading the public signals of a circuit from a circom `.sym` file.
+//!
+//! Each line of a `.sym` file is `label,witness,component,name`, where
+//! `witness` is the index of the signal in the witness, or -1 if the compiler
+//! removed it. Witness 0 is the constant 1 and the public signals follow at
+//! 1 to `n_public`, outputs first, in the order `public.json` lists them.
+//! `.sym` files do not mark signals as public, so `n_public` comes from the
+//! verification key.
+
+/// A group of public signals sharing a name, one field of the generated
+/// struct.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Field {
+    /// The name in the circuit, without the `main.` prefix.
+    pub name: String,
+    /// Array dimensions, empty for a single signal.
+    pub shape: Vec<usize>,
+    /// The witness index of the first signal.
+    pub witness: usize,
+}
+
+impl Field {
+    /// The number of signals in the field.
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+/// `main.name[i][j]` as `("name", [i, j])`, or `None` for signals of other
+/// components.
+fn split_name(name: &str) -> Result<Option<(&str, Vec<usize>)>, String> {
+    let name = match name.strip_prefix("main.") {
+        Some(name) if !name.contains('.') => name,
+        _ => return Ok(None),
+    };
+    let (base, mut rest) = name.split_at(name.find('[').unwrap_or(name.len()));
+
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        let index = rest
+            .strip_prefix('[')
+            .and_then(|r| r.split_once(']'))
+            .and_then(|(i, r)| Some((i.parse().ok()?, r)));
+        match index {
+            Some((i, r)) => {
+                indices.push(i);
+                rest = r;
+            }
+            None => return Err(format!("invalid signal name `main.{}`", name)),
+        }
+    }
+    Ok(Some((base, indices)))
+}
+
+/// The public signals of the main component in witness order, grouped into
+/// fields.
+pub fn public_fields(sym: &str, n_public: usize) -> Result<Vec<Field>, String> {
+    let mut signals: Vec<Option<(&str, Vec<usize>)>> = vec![None; n_public];
+
+    for (number, line) in sym
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+    {
+        let columns: Vec<&str> = line.trim().splitn(4, ',').collect();
+        let (witness, name) = match columns[..] {
+            [_, witness, _, name] => match witness.parse::<i64>() {
+                Ok(witness) => (witness, name),
+                Err(_) => return Err(format!("line {}: invalid witness index", number + 1)),
+            },
+            _ => {
+                return Err(format!(
+                    "line {}: expected `label,witness,component,name`",
+                    number + 1
+                ))
+            }
+        };
+        if witness < 1 || witness as usize > n_public {
+            continue;
+        }
+
+        // Signals of subcomponents may share the witness of a public signal.
+        if let Some(signal) = split_name(name)? {
+            let slot = &mut signals[witness as usize - 1];
+            if slot.is_some() {
+                return Err(format!("several public signals at witness {}", witness));
+            }
+            *slot = Some(signal);
+        }
+    }
+
+    let mut fields: Vec<Field> = Vec::new();
+    let mut indices: Vec<Vec<Vec<usize>>> = Vec::new();
+    for (i, signal) in signals.into_iter().enumerate() {
+        let (base, index) =
+            signal.ok_or_else(|| format!("no signal of `main` at witness {}", i + 1))?;
+        match fields.last() {
+            Some(field) if field.name == base => indices.last_mut().unwrap().push(index),
+            _ => {
+                if fields.iter().any(|f| f.name == base) {
+                    return Err(format!("the signals of `main.{}` are not contiguous", base));
+                }
+                fields.push(Field {
+                    name: base.to_string(),
+                    shape: Vec::new(),
+                    witness: i + 1,
+                });
+                indices.push(vec![index]);
+            }
+        }
+    }
+
+    for (field, indices) in fields.iter_mut().zip(&indices) {
+        field.shape = shape(&field.name, indices)?;
+    }
+    Ok(fields)
+}
+
+/// The dimensions of an array of signals, which must list every element once
+/// in row-major order.
+fn shape(name: &str, indices: &[Vec<usize>]) -> Result<Vec<usize>, String> {
+    let dims = indices[0].len();
+    let mut shape = vec![0; dims];
+    for index in indices {
+        if index.len() != dims {
+            return Err(format!("`main.{}` mixes dimensions", name));
+        }
+        for (d, &i) in shape.iter_mut().zip(index) {
+            *d = (*d).max(i + 1);
+        }
+    }
+
+    let mut expected = vec![0; dims];
+    for index in indices {
+        if *index != expected {
+            return Err(format!(
+                "`main.{}` is not a complete array in row-major order",
+                name
+            ));
+        }
+        // Increments `expected` as a mixed-radix number.
+        for k in (0..dims).rev() {
+            expected[k] += 1;
+            if expected[k] < shape[k] {
+                break;
+            }
+            expected[k] = 0;
+        }
+    }
+    if indices.len() != shape.iter().product::<usize>() {
+        return Err(format!(
+            "`main.{}` is not a complete array in row-major order",
+            name
+        ));
+    }
+    Ok(shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYM: &str = "\
+1,1,0,main.newStateRoot
+2,2,0,main.root[0]
+3,3,0,main.root[1]
+4,4,0,main.votes[0][0]
+5,5,0,main.votes[0][1]
+6,6,0,main.votes[1][0]
+7,7,0,main.votes[1][1]
+8,8,0,main.salt
+9,-1,0,main.unused
+10,1,1,main.hasher.out
+11,9,1,main.hasher.in[0]
+";
+
+    fn field(name: &str, shape: &[usize], witness: usize) -> Field {
+        Field {
+            name: name.into(),
+            shape: shape.to_vec(),
+            witness,
+        }
+    }
+
+    #[test]
+    fn groups_public_signals() {
+        assert_eq!(
+            public_fields(SYM, 7).unwrap(),
+            vec![
+                field("newStateRoot", &[], 1),
+                field("root", &[2], 2),
+                field("votes", &[2, 2], 4),
+            ]
+        );
+        assert_eq!(public_fields(SYM, 8).unwrap()[3], field("salt", &[], 8));
+    }
+
+    #[test]
+    fn rejects_inconsistent_files() {
+        assert_eq!(
+            public_fields(SYM, 9).unwrap_err(),
+            "no signal of `main` at witness 9"
+        );
+        assert_eq!(
+            public_fields(SYM, 6).unwrap_err(),
+            "`main.votes` is not a complete array in row-major order"
+        );
+        assert_eq!(
+            public_fields("1,1,0,main.a\n2,2,0,main.b\n3,3,0,main.a\n", 3).unwrap_err(),
+            "the signals of `main.a` are not contiguous"
+        );
+        assert_eq!(
+            public_fields("1,1,0,main.a[x]\n", 1).unwrap_err(),
+            "invalid signal name `main.a[x]`"
+        );
+        assert_eq!(
+            public_fields("1,1,main.a\n", 1).unwrap_err(),
+            "line 1: expected `label,witness,component,name`"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use bellman_verifier::bn256::{proof_from_snarkjs, Bn256};
+use bellman_verifier::{prepare_verifier_key, verify_proof, Proof, VerifierKey};
+use bls12_381::{Bls12, G1Affine, G2Affine};
+use groth16_macros::include_vkey;
+use serde_json::Value;
+
+fn strings(v: &Value) -> Vec<&str> {
+    v.as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s.as_str().unwrap())
+        .collect()
+}
+
+fn bytes<const N: usize>(v: &Value) -> [u8; N] {
+    let bytes: Vec<u8> = v
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b.as_u64().unwrap() as u8)
+        .collect();
+    bytes.try_into().unwrap()
+}
+
+#[test]
+fn embeds_snarkjs_keys() {
+    let vk: &VerifierKey<Bn256> = include_vkey!("../fixtures/bn128/verification_key.json");
+    assert_eq!(vk.ic.len(), 2);
+
+    let proof: Value =
+        serde_json::from_str(include_str!("../../fixtures/bn128/proof.json")).unwrap();
+    let g2: Vec<Vec<&str>> = proof["pi_b"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(strings)
+        .collect();
+    let proof =
+        proof_from_snarkjs(&strings(&proof["pi_a"]), &g2, &strings(&proof["pi_c"])).unwrap();
+
+    let pvk = prepare_verifier_key(vk);
+    assert_eq!(verify_proof(&pvk, &proof, &[33.into()]), Ok(()));
+}
+
+#[test]
+fn embeds_uncompressed_keys() {
+    let vk: &VerifierKey<Bls12> = include_vkey!("../fixtures/bls12381/vkey_uncompressed.json");
+
+    let proof: Value = serde_json::from_str(include_str!(
+        "../../fixtures/bls12381/proof_uncompressed.json"
+    ))
+    .unwrap();
+    let proof = Proof::<Bls12> {
+        a: G1Affine::from_uncompressed(&bytes(&proof["pi_a"])).unwrap(),
+        b: G2Affine::from_uncompressed(&bytes(&proof["pi_b"])).unwrap(),
+        c: G1Affine::from_uncompressed(&bytes(&proof["pi_c"])).unwrap(),
+    };
+
+    let pvk = prepare_verifier_key(vk);
+    assert_eq!(verify_proof(&pvk, &proof, &[33.into()]), Ok(()));
+}
+
+#[test]
+fn decodes_each_key_once() {
+    fn key() -> &'static VerifierKey<Bn256> {
+        include_vkey!("../fixtures/bn128/verification_key.json")
+    }
+
+    assert!(std::ptr::eq(key(), key()));
+}
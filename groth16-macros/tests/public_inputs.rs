@@ -0,0 +1,41 @@
+use bellman_verifier::bn256::{proof_from_snarkjs, Bn256, Fr};
+use bellman_verifier::{prepare_verifier_key, verify_proof, VerifierKey};
+use groth16_macros::{include_vkey, public_inputs};
+use serde_json::Value;
+
+/// The public signals of `circuit/Multiplication`.
+#[public_inputs(
+    sym = "../fixtures/multiplication.sym",
+    vkey = "../fixtures/bn128/verification_key.json"
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiplicationInputs;
+
+fn strings(v: &Value) -> Vec<&str> {
+    v.as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s.as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn orders_named_inputs() {
+    assert_eq!(MultiplicationInputs::<Fr>::LEN, 1);
+
+    let vk: &VerifierKey<Bn256> = include_vkey!("../fixtures/bn128/verification_key.json");
+    let proof: Value =
+        serde_json::from_str(include_str!("../../fixtures/bn128/proof.json")).unwrap();
+    let g2: Vec<Vec<&str>> = proof["pi_b"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(strings)
+        .collect();
+    let proof =
+        proof_from_snarkjs(&strings(&proof["pi_a"]), &g2, &strings(&proof["pi_c"])).unwrap();
+
+    let inputs = MultiplicationInputs { c: Fr::from(33) };
+    let pvk = prepare_verifier_key(vk);
+    assert_eq!(verify_proof(&pvk, &proof, &inputs.into_vec()), Ok(()));
+}
@@ -0,0 +1,187 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Host functions verifying Groth16 proofs natively instead of in the Wasm
+//! runtime.
+//!
+//! `groth_16` holds the runtime interface. Built with `std` it calls into
+//! `bellman-verifier` directly, in the runtime it is a stub that calls
+//! through to the node. A node registers the host functions in its executor,
+//! e.g.
+//!
+//! ```ignore
+//! pub struct ExecutorDispatch;
+//!
+//! impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
+//!     type ExtendHostFunctions = (
+//!         frame_benchmarking::benchmarking::HostFunctions,
+//!         groth16_runtime_interface::groth_16::HostFunctions,
+//!     );
+//!     // ...
+//! }
+//! ```
+//!
+//! `HostBls12381` and `HostBn254` implement `Groth16Verifier` on top of the
+//! host functions, so `pallet-groth16` gets native verification by using
+//! them as `Config::Verifier`. Runtimes doing so only run on nodes that
+//! register the host functions.
+//!
+//! Encodings are those of `verifier_traits::Groth16Verifier`.
+
+use sp_runtime_interface::runtime_interface;
+use sp_std::prelude::*;
+use verifier_traits::{Groth16Verifier, VerifierError, PUBLIC_INPUT_SIZE};
+
+#[cfg(feature = "std")]
+use bellman_verifier::{bn256::Bn256, BellmanVerifier};
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "std")]
+fn check_key<V: Groth16Verifier>(key: &[u8]) -> Result<(), VerifierError> {
+    V::decode_verifying_key(key).map(|_| ())
+}
+
+#[cfg(feature = "std")]
+fn verify<V: Groth16Verifier>(
+    key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<(), VerifierError> {
+    let vk = V::decode_verifying_key(key)?;
+    let pvk = V::prepare_verifying_key(&vk);
+
+    V::verify_encoded(&pvk, proof, public_inputs)
+}
+
+/// Native Groth16 verification over BLS12-381 and BN254.
+#[runtime_interface]
+pub trait Groth16 {
+    /// Checks that `key` decodes to a BLS12-381 verifying key.
+    fn check_key_bls12_381(key: &[u8]) -> Result<(), VerifierError> {
+        check_key::<BellmanVerifier<bls12_381::Bls12>>(key)
+    }
+
+    /// Verifies a BLS12-381 proof, decoding and preparing `key` first.
+    fn verify_bls12_381(
+        key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<(), VerifierError> {
+        verify::<BellmanVerifier<bls12_381::Bls12>>(key, proof, public_inputs)
+    }
+
+    /// Checks that `key` decodes to a BN254 verifying key.
+    fn check_key_bn254(key: &[u8]) -> Result<(), VerifierError> {
+        check_key::<BellmanVerifier<Bn256>>(key)
+    }
+
+    /// Verifies a BN254 (snarkjs' bn128) proof, decoding and preparing `key`
+    /// first.
+    fn verify_bn254(
+        key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<(), VerifierError> {
+        verify::<BellmanVerifier<Bn256>>(key, proof, public_inputs)
+    }
+}
+
+macro_rules! host_verifier {
+    (
+        $name:ident,
+        $curve:literal,
+        $check_key:ident,
+        $verify:ident,
+        $native:ty,
+        $g1_size:literal,
+        $g2_size:literal
+    ) => {
+        #[doc = concat!("A `Groth16Verifier` over ", $curve, " that verifies through the")]
+        #[doc = concat!("`groth_16::", stringify!($verify), "` host function.")]
+        ///
+        /// Keys, proofs and public inputs stay encoded, the host decodes them.
+        /// `decode_verifying_key` checks keys on the host, proofs and public
+        /// inputs are only checked when verifying. Stored prepared keys are the
+        /// encoded keys, which the host checks again on every verification.
+        pub struct $name;
+
+        impl Groth16Verifier for $name {
+            type Fr = [u8; PUBLIC_INPUT_SIZE];
+            type Proof = Vec<u8>;
+            type VerifyingKey = Vec<u8>;
+            type PreparedVerifyingKey = Vec<u8>;
+
+            fn prepare_verifying_key(vk: &Vec<u8>) -> Vec<u8> {
+                vk.clone()
+            }
+
+            fn verify_proof(
+                pvk: &Vec<u8>,
+                proof: &Vec<u8>,
+                public_inputs: &[Self::Fr],
+            ) -> Result<(), VerifierError> {
+                groth_16::$verify(pvk, proof, &public_inputs.concat())
+            }
+
+            fn decode_proof(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+                Ok(bytes.to_vec())
+            }
+
+            fn decode_verifying_key(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+                groth_16::$check_key(bytes)?;
+                Ok(bytes.to_vec())
+            }
+
+            fn public_input_count(vk: &Vec<u8>) -> usize {
+                (vk.len().saturating_sub($g1_size + 3 * $g2_size) / $g1_size).saturating_sub(1)
+            }
+
+            fn encode_prepared_verifying_key(vk: &Vec<u8>) -> Vec<u8> {
+                vk.clone()
+            }
+
+            fn decode_prepared_verifying_key(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+                Ok(bytes.to_vec())
+            }
+
+            fn decode_public_input(bytes: &[u8]) -> Result<Self::Fr, VerifierError> {
+                bytes.try_into().map_err(|_| VerifierError::InvalidEncoding)
+            }
+
+            fn verify_encoded(
+                pvk: &Vec<u8>,
+                proof: &[u8],
+                public_inputs: &[u8],
+            ) -> Result<(), VerifierError> {
+                groth_16::$verify(pvk, proof, public_inputs)
+            }
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        impl verifier_traits::BenchmarkSetup for $name {
+            fn benchmark_setup(num_inputs: usize, seed: u64) -> verifier_traits::BenchmarkData {
+                <$native as verifier_traits::BenchmarkSetup>::benchmark_setup(num_inputs, seed)
+            }
+        }
+    };
+}
+
+host_verifier!(
+    HostBls12381,
+    "BLS12-381",
+    check_key_bls12_381,
+    verify_bls12_381,
+    bellman_verifier::BellmanVerifier<bls12_381::Bls12>,
+    96,
+    192
+);
+host_verifier!(
+    HostBn254,
+    "BN254",
+    check_key_bn254,
+    verify_bn254,
+    bellman_verifier::BellmanVerifier<bellman_verifier::bn256::Bn256>,
+    64,
+    128
+);
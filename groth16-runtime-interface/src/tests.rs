@@ -0,0 +1,85 @@
+use crate::{groth_16, HostBls12381, HostBn254};
+use bellman_verifier::{bn256::Bn256, BellmanVerifier};
+use sp_io::TestExternalities;
+use verifier_traits::{BenchmarkData, BenchmarkSetup, Groth16Verifier, VerifierError};
+
+type Verify = fn(&[u8], &[u8], &[u8]) -> Result<(), VerifierError>;
+
+fn check_host_function(verify: Verify, setup: fn(usize, u64) -> BenchmarkData) {
+    TestExternalities::default().execute_with(|| {
+        for n in [0, 1, 3] {
+            let data = setup(n, 7);
+            assert_eq!(verify(&data.key, &data.proof, &data.public_inputs), Ok(()));
+        }
+
+        let data = setup(2, 7);
+        let mut wrong_input = data.public_inputs.clone();
+        wrong_input[..32].copy_from_slice(&[0; 32]);
+        assert_eq!(
+            verify(&data.key, &data.proof, &wrong_input),
+            Err(VerifierError::InvalidProof)
+        );
+
+        assert_eq!(
+            verify(&data.key, &data.proof, &data.public_inputs[32..]),
+            Err(VerifierError::PublicInputCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+
+        assert_eq!(
+            verify(&data.key, &data.proof[1..], &data.public_inputs),
+            Err(VerifierError::InvalidEncoding)
+        );
+
+        let mut bad_key = data.key.clone();
+        bad_key.pop();
+        assert_eq!(
+            verify(&bad_key, &data.proof, &data.public_inputs),
+            Err(VerifierError::InvalidEncoding)
+        );
+    });
+}
+
+#[test]
+fn verify_bls12_381() {
+    check_host_function(
+        groth_16::verify_bls12_381,
+        BellmanVerifier::<bls12_381::Bls12>::benchmark_setup,
+    );
+}
+
+#[test]
+fn verify_bn254() {
+    check_host_function(groth_16::verify_bn254, BellmanVerifier::<Bn256>::benchmark_setup);
+}
+
+#[test]
+fn host_verifiers_match_native() {
+    TestExternalities::default().execute_with(|| {
+        let data = BellmanVerifier::<Bn256>::benchmark_setup(2, 3);
+        let vk = HostBn254::decode_verifying_key(&data.key).unwrap();
+        assert_eq!(HostBn254::public_input_count(&vk), 2);
+        let pvk = HostBn254::prepare_verifying_key(&vk);
+        let proof = HostBn254::decode_proof(&data.proof).unwrap();
+        let inputs = HostBn254::decode_public_inputs(&data.public_inputs).unwrap();
+        assert_eq!(HostBn254::verify_proof(&pvk, &proof, &inputs), Ok(()));
+
+        // The curves do not share key encodings.
+        assert_eq!(
+            HostBls12381::decode_verifying_key(&data.key),
+            Err(VerifierError::InvalidEncoding)
+        );
+
+        let data = BellmanVerifier::<bls12_381::Bls12>::benchmark_setup(2, 3);
+        let vk = HostBls12381::decode_verifying_key(&data.key).unwrap();
+        assert_eq!(HostBls12381::public_input_count(&vk), 2);
+        let stored = HostBls12381::encode_prepared_verifying_key(&vk);
+        let pvk = HostBls12381::decode_prepared_verifying_key(&stored).unwrap();
+        assert_eq!(
+            HostBls12381::verify_encoded(&pvk, &data.proof, &data.public_inputs),
+            Ok(())
+        );
+    });
+}
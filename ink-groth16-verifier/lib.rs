@@ -0,0 +1,145 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! An ink! contract verifying snarkjs BLS12-381 Groth16 proofs with
+//! `bellman-verifier`.
+//!
+//! The verifying key is fixed at instantiation, one contract instance per
+//! circuit. Keys, proofs and public inputs use the uncompressed byte layout
+//! of `verifier_traits::Groth16Verifier`. The key is checked once and stored
+//! in the form `Groth16Verifier::encode_prepared_verifying_key` writes, so
+//! that messages neither check nor prepare it again.
+
+use ink_lang as ink;
+
+#[ink::contract]
+mod groth16_verifier {
+    use bellman_verifier::BellmanVerifier;
+    use bls12_381::Bls12;
+    use ink_prelude::vec::Vec;
+    use verifier_traits::{Groth16Verifier, VerifierError};
+
+    type Backend = BellmanVerifier<Bls12>;
+
+    #[ink(storage)]
+    pub struct Verifier {
+        /// The verifying key as `Backend::encode_prepared_verifying_key`
+        /// writes it, checked at instantiation.
+        key: Vec<u8>,
+    }
+
+    /// Why a proof was rejected.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// The proof or public inputs could not be decoded.
+        InvalidEncoding,
+        /// The number of public inputs does not match the key.
+        PublicInputCount { expected: u32, actual: u32 },
+        /// The proof did not verify.
+        InvalidProof,
+    }
+
+    impl From<VerifierError> for Error {
+        fn from(e: VerifierError) -> Self {
+            match e {
+                // The stored key was checked at instantiation.
+                VerifierError::InvalidVerifyingKey | VerifierError::InvalidEncoding => {
+                    Error::InvalidEncoding
+                }
+                VerifierError::PublicInputCount { expected, actual } => {
+                    Error::PublicInputCount { expected, actual }
+                }
+                VerifierError::InvalidProof => Error::InvalidProof,
+            }
+        }
+    }
+
+    impl Verifier {
+        /// Instantiates the contract for `key`.
+        ///
+        /// # Panics
+        ///
+        /// If `key` does not decode to a verifying key.
+        #[ink(constructor)]
+        pub fn new(key: Vec<u8>) -> Self {
+            let vk = Backend::decode_verifying_key(&key).expect("invalid verifying key");
+            Self {
+                key: Backend::encode_prepared_verifying_key(&vk),
+            }
+        }
+
+        /// Verifies `proof` against the key. `public_inputs` are consecutive
+        /// 32 byte big-endian scalars.
+        #[ink(message)]
+        pub fn verify(&self, proof: Vec<u8>, public_inputs: Vec<u8>) -> Result<(), Error> {
+            let pvk = Backend::decode_prepared_verifying_key(&self.key)?;
+
+            Ok(Backend::verify_encoded(&pvk, &proof, &public_inputs)?)
+        }
+
+        /// The stored verifying key, which for `BellmanVerifier` is in the
+        /// layout the constructor takes.
+        #[ink(message)]
+        pub fn key(&self) -> Vec<u8> {
+            self.key.clone()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_lang as ink;
+
+        fn key() -> Vec<u8> {
+            include_bytes!("../fixtures/bls12381/vkey.bin").to_vec()
+        }
+
+        fn proof() -> Vec<u8> {
+            include_bytes!("../fixtures/bls12381/proof.bin").to_vec()
+        }
+
+        fn public_inputs() -> Vec<u8> {
+            include_bytes!("../fixtures/bls12381/public.bin").to_vec()
+        }
+
+        #[ink::test]
+        fn verifies_snarkjs_proof() {
+            let verifier = Verifier::new(key());
+            assert_eq!(verifier.key(), key());
+            assert_eq!(verifier.verify(proof(), public_inputs()), Ok(()));
+        }
+
+        #[ink::test]
+        fn rejects_bad_proofs() {
+            let verifier = Verifier::new(key());
+
+            let mut wrong_input = public_inputs();
+            wrong_input[31] ^= 1;
+            assert_eq!(
+                verifier.verify(proof(), wrong_input),
+                Err(Error::InvalidProof)
+            );
+
+            assert_eq!(
+                verifier.verify(proof(), vec![]),
+                Err(Error::PublicInputCount {
+                    expected: 1,
+                    actual: 0
+                })
+            );
+
+            assert_eq!(
+                verifier.verify(proof()[1..].to_vec(), public_inputs()),
+                Err(Error::InvalidEncoding)
+            );
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "invalid verifying key")]
+        fn rejects_bad_keys() {
+            let mut key = key();
+            key.pop();
+            Verifier::new(key);
+        }
+    }
+}
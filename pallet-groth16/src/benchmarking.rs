@@ -0,0 +1,89 @@
+//! Benchmarks for pallet-groth16.
+//!
+//! Keys and proofs come from `verifier_traits::BenchmarkSetup`, so the
+//! runtime's verifier crate needs its `runtime-benchmarks` feature. Run them
+//! once per curve, i.e. for every `Config::Verifier` a runtime uses.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::{EnsureOrigin, Get};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+use verifier_traits::{BenchmarkSetup, Groth16Verifier};
+
+use crate::Pallet as Groth16;
+
+/// The most public inputs benchmarked.
+const MAX_INPUTS: u32 = 32;
+
+/// The benchmark key with the most inputs that is at most `len` bytes long,
+/// and no inputs if there is none.
+fn key_of_len<T: Config>(len: u32) -> Vec<u8>
+where
+    T::Verifier: BenchmarkSetup,
+{
+    let base = T::Verifier::benchmark_setup(0, 0).key.len();
+    let per_input = T::Verifier::benchmark_setup(1, 0).key.len() - base;
+    let inputs = (len as usize).saturating_sub(base) / per_input;
+
+    T::Verifier::benchmark_setup(inputs, 0).key
+}
+
+fn register<T: Config>(key: Vec<u8>) -> KeyId {
+    let id = NextKeyId::<T>::get();
+    Groth16::<T>::register_key(T::KeyOrigin::successful_origin(), key)
+        .expect("benchmark keys are valid");
+    id
+}
+
+benchmarks! {
+    where_clause { where T::Verifier: BenchmarkSetup }
+
+    register_key {
+        let l in 0 .. T::MaxKeyLen::get();
+        let key = key_of_len::<T>(l);
+        let origin = T::KeyOrigin::successful_origin();
+    }: _<T::Origin>(origin, key)
+    verify {
+        assert!(Keys::<T>::contains_key(0));
+    }
+
+    retire_key {
+        let id = register::<T>(T::Verifier::benchmark_setup(1, 0).key);
+        let origin = T::KeyOrigin::successful_origin();
+    }: _<T::Origin>(origin, id)
+    verify {
+        assert!(!Keys::<T>::contains_key(id));
+    }
+
+    // `verify` cannot name a benchmark, the macro reserves it.
+    verify_call {
+        let n in 0 .. MAX_INPUTS;
+        let data = T::Verifier::benchmark_setup(n as usize, 0);
+        let id = register::<T>(data.key);
+        let caller: T::AccountId = whitelisted_caller();
+    }: verify(RawOrigin::Signed(caller), id, data.proof, data.public_inputs)
+
+    verify_proof {
+        let n in 0 .. MAX_INPUTS;
+        let data = T::Verifier::benchmark_setup(n as usize, 0);
+        let id = register::<T>(data.key);
+    }: {
+        Groth16::<T>::verify_proof(id, &data.proof, &data.public_inputs)
+            .expect("benchmark proofs verify");
+    }
+
+    // Reading back the key `register_key` stored, as `verify_proof` does.
+    prepare_key {
+        let n in 0 .. MAX_INPUTS;
+        let key = T::Verifier::benchmark_setup(n as usize, 0).key;
+        let vk = T::Verifier::decode_verifying_key(&key).expect("benchmark keys are valid");
+        let prepared = T::Verifier::encode_prepared_verifying_key(&vk);
+    }: {
+        T::Verifier::decode_prepared_verifying_key(&prepared).expect("stored keys decode");
+    }
+
+    impl_benchmark_test_suite!(Groth16, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,246 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Verification of snarkjs Groth16 proofs against a registry of keys.
+//!
+//! `Config::KeyOrigin` registers verification keys with `register_key` and
+//! retires them with `retire_key`. A key is addressed by the `KeyId` assigned
+//! at registration, or looked up by its fingerprint, the `blake2_256` of its
+//! stored encoding, through `key_id`.
+//!
+//! Anyone can check a proof with the `verify` dispatchable. Other pallets
+//! call `Pallet::verify_proof` instead, which verifies without emitting an
+//! event.
+//!
+//! Keys, proofs and public inputs use the uncompressed byte layout of
+//! `verifier_traits::Groth16Verifier`, the one the adapter writes from
+//! snarkjs' `verification_key.json`, `proof.json` and `public.json`.
+//!
+//! `register_key` checks the key once and stores it in the form
+//! `Groth16Verifier::decode_prepared_verifying_key` reads, along with its
+//! number of public inputs, so that verification does not check and prepare
+//! the key again. For the bellman backends that form is the registered
+//! encoding itself.
+//!
+//! Verification cost depends on the curve and grows with the number of
+//! public inputs, which `verify` is charged for by the length of the
+//! submitted `public_inputs`. Payloads whose count differs from the key's
+//! are rejected before any decoding. `weights` has `WeightInfo`s for BN254
+//! and BLS12-381.
+
+pub use pallet::*;
+pub use weights::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_std::prelude::*;
+    use verifier_traits::{Groth16Verifier, VerifierError, PUBLIC_INPUT_SIZE};
+
+    use super::WeightInfo;
+
+    /// Identifies a registered verification key.
+    pub type KeyId = u32;
+
+    /// The `blake2_256` of a verification key's encoding.
+    pub type Fingerprint = [u8; 32];
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The curve backend, e.g.
+        /// `bellman_verifier::BellmanVerifier<bellman_verifier::bn256::Bn256>`
+        /// for snarkjs' bn128.
+        type Verifier: Groth16Verifier;
+
+        /// The origin allowed to register and retire keys.
+        type KeyOrigin: EnsureOrigin<Self::Origin>;
+
+        /// The longest key encoding accepted, which bounds the number of
+        /// public inputs.
+        #[pallet::constant]
+        type MaxKeyLen: Get<u32>;
+
+        /// Weights for the curve of `Verifier`, e.g. `Bn254Weight`.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    pub struct Pallet<T>(_);
+
+    /// The id the next registered key gets.
+    #[pallet::storage]
+    pub type NextKeyId<T> = StorageValue<_, KeyId, ValueQuery>;
+
+    /// The registered keys as encoded by
+    /// `Groth16Verifier::encode_prepared_verifying_key`.
+    #[pallet::storage]
+    #[pallet::getter(fn key)]
+    pub type Keys<T: Config> =
+        StorageMap<_, Blake2_128Concat, KeyId, BoundedVec<u8, T::MaxKeyLen>>;
+
+    /// The number of public inputs of the registered keys.
+    #[pallet::storage]
+    #[pallet::getter(fn key_inputs)]
+    pub type KeyInputs<T> = StorageMap<_, Blake2_128Concat, KeyId, u32>;
+
+    /// Ids of the registered keys by fingerprint.
+    #[pallet::storage]
+    #[pallet::getter(fn key_id)]
+    pub type KeyIds<T> = StorageMap<_, Identity, Fingerprint, KeyId>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A verification key was registered.
+        KeyRegistered { id: KeyId, fingerprint: Fingerprint },
+        /// A verification key was retired.
+        KeyRetired { id: KeyId },
+        /// A proof verified through the `verify` dispatchable.
+        ProofVerified { who: T::AccountId, id: KeyId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The key encoding is longer than `MaxKeyLen`.
+        KeyTooLong,
+        /// The key could not be decoded.
+        InvalidKey,
+        /// A key with the same fingerprint is already registered.
+        KeyAlreadyRegistered,
+        /// All key ids are used up.
+        NoKeyIdLeft,
+        /// No key is registered under the id.
+        UnknownKey,
+        /// The proof or public inputs could not be decoded.
+        InvalidEncoding,
+        /// The number of public inputs does not match the key.
+        PublicInputCount,
+        /// The proof did not verify.
+        InvalidProof,
+    }
+
+    impl<T> From<VerifierError> for Error<T> {
+        fn from(e: VerifierError) -> Self {
+            match e {
+                VerifierError::InvalidVerifyingKey => Error::InvalidKey,
+                VerifierError::PublicInputCount { .. } => Error::PublicInputCount,
+                VerifierError::InvalidProof => Error::InvalidProof,
+                VerifierError::InvalidEncoding => Error::InvalidEncoding,
+            }
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Registers a verification key under the next free id.
+        #[pallet::weight(T::WeightInfo::register_key(key.len() as u32))]
+        pub fn register_key(origin: OriginFor<T>, key: Vec<u8>) -> DispatchResult {
+            T::KeyOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                key.len() <= T::MaxKeyLen::get() as usize,
+                Error::<T>::KeyTooLong
+            );
+            let vk = T::Verifier::decode_verifying_key(&key).map_err(|_| Error::<T>::InvalidKey)?;
+            let key: BoundedVec<u8, T::MaxKeyLen> =
+                T::Verifier::encode_prepared_verifying_key(&vk)
+                    .try_into()
+                    .map_err(|_| Error::<T>::KeyTooLong)?;
+            let inputs = u32::try_from(T::Verifier::public_input_count(&vk))
+                .map_err(|_| Error::<T>::KeyTooLong)?;
+
+            let fingerprint = sp_io::hashing::blake2_256(&key);
+            ensure!(
+                !KeyIds::<T>::contains_key(fingerprint),
+                Error::<T>::KeyAlreadyRegistered
+            );
+
+            let id = NextKeyId::<T>::get();
+            NextKeyId::<T>::put(id.checked_add(1).ok_or(Error::<T>::NoKeyIdLeft)?);
+            Keys::<T>::insert(id, key);
+            KeyInputs::<T>::insert(id, inputs);
+            KeyIds::<T>::insert(fingerprint, id);
+
+            Self::deposit_event(Event::KeyRegistered { id, fingerprint });
+            Ok(())
+        }
+
+        /// Removes a verification key. Its id is not reused.
+        #[pallet::weight(T::WeightInfo::retire_key())]
+        pub fn retire_key(origin: OriginFor<T>, id: KeyId) -> DispatchResult {
+            T::KeyOrigin::ensure_origin(origin)?;
+
+            let key = Keys::<T>::take(id).ok_or(Error::<T>::UnknownKey)?;
+            KeyInputs::<T>::remove(id);
+            KeyIds::<T>::remove(sp_io::hashing::blake2_256(&key));
+
+            Self::deposit_event(Event::KeyRetired { id });
+            Ok(())
+        }
+
+        /// Verifies a proof against the key `id` and emits `ProofVerified`.
+        ///
+        /// Charged for the public inputs submitted, which are rejected
+        /// unless their number is the key's.
+        #[pallet::weight(T::WeightInfo::verify_call(input_count(public_inputs)))]
+        pub fn verify(
+            origin: OriginFor<T>,
+            id: KeyId,
+            proof: Vec<u8>,
+            public_inputs: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::verify_proof(id, &proof, &public_inputs)?;
+
+            Self::deposit_event(Event::ProofVerified { who, id });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Verifies `proof` against the registered key `id`, for use by other
+        /// pallets. `public_inputs` are consecutive 32 byte big-endian
+        /// scalars.
+        ///
+        /// Callers charge `T::WeightInfo::verify_proof(n)` for the `n`
+        /// public inputs submitted, `public_inputs.len() / 32`. Counts other
+        /// than the key's, `key_inputs(id)`, fail before the key is read.
+        pub fn verify_proof(
+            id: KeyId,
+            proof: &[u8],
+            public_inputs: &[u8],
+        ) -> Result<(), Error<T>> {
+            let inputs = KeyInputs::<T>::get(id).ok_or(Error::<T>::UnknownKey)?;
+            ensure!(
+                input_count(public_inputs) == inputs,
+                Error::<T>::PublicInputCount
+            );
+
+            let key = Keys::<T>::get(id).ok_or(Error::<T>::UnknownKey)?;
+            let pvk = T::Verifier::decode_prepared_verifying_key(&key)?;
+
+            Ok(T::Verifier::verify_encoded(&pvk, proof, public_inputs)?)
+        }
+    }
+
+    /// The number of 32 byte public inputs in `public_inputs`, saturated at
+    /// `u32::MAX`.
+    fn input_count(public_inputs: &[u8]) -> u32 {
+        u32::try_from(public_inputs.len() / PUBLIC_INPUT_SIZE).unwrap_or(u32::MAX)
+    }
+}
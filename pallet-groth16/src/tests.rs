@@ -0,0 +1,186 @@
+use crate::{mock::*, Call, Error, Event, KeyIds, KeyInputs, Keys, WeightInfo};
+use bellman_verifier::bn256::{g1_from_snarkjs, g2_from_snarkjs};
+use frame_support::{assert_noop, assert_ok, weights::GetDispatchInfo};
+use group::UncompressedEncoding;
+use serde_json::Value;
+use sp_runtime::traits::BadOrigin;
+use verifier_traits::u256_from_decimal;
+
+fn json(s: &str) -> Value {
+    serde_json::from_str(s).unwrap()
+}
+
+fn strings(v: &Value) -> Vec<&str> {
+    v.as_array().unwrap().iter().map(|s| s.as_str().unwrap()).collect()
+}
+
+fn g1(v: &Value) -> Vec<u8> {
+    let p = g1_from_snarkjs(&strings(v)).unwrap();
+    p.to_uncompressed().as_ref().to_vec()
+}
+
+fn g2(v: &Value) -> Vec<u8> {
+    let coords: Vec<Vec<&str>> = v.as_array().unwrap().iter().map(strings).collect();
+    let p = g2_from_snarkjs(&coords).unwrap();
+    p.to_uncompressed().as_ref().to_vec()
+}
+
+/// `verification_key.json` in the `Groth16Verifier` layout.
+fn key() -> Vec<u8> {
+    let vk = json(include_str!("../../fixtures/bn128/verification_key.json"));
+    let mut key = g1(&vk["vk_alpha_1"]);
+    key.extend(g2(&vk["vk_beta_2"]));
+    key.extend(g2(&vk["vk_gamma_2"]));
+    key.extend(g2(&vk["vk_delta_2"]));
+    for ic in vk["IC"].as_array().unwrap() {
+        key.extend(g1(ic));
+    }
+    key
+}
+
+fn proof() -> Vec<u8> {
+    let proof = json(include_str!("../../fixtures/bn128/proof.json"));
+    let mut bytes = g1(&proof["pi_a"]);
+    bytes.extend(g2(&proof["pi_b"]));
+    bytes.extend(g1(&proof["pi_c"]));
+    bytes
+}
+
+fn public_inputs() -> Vec<u8> {
+    let public = json(include_str!("../../fixtures/bn128/public.json"));
+    strings(&public)
+        .into_iter()
+        .flat_map(|s| u256_from_decimal(s).unwrap())
+        .collect()
+}
+
+#[test]
+fn register_and_retire_key() {
+    new_test_ext().execute_with(|| {
+        let fingerprint = sp_io::hashing::blake2_256(&key());
+
+        assert_ok!(Groth16::register_key(Origin::root(), key()));
+        assert_eq!(Groth16::key(0).unwrap().to_vec(), key());
+        assert_eq!(Groth16::key_id(fingerprint), Some(0));
+        assert_eq!(Groth16::key_inputs(0), Some(1));
+        System::assert_last_event(Event::KeyRegistered { id: 0, fingerprint }.into());
+
+        assert_noop!(
+            Groth16::register_key(Origin::root(), key()),
+            Error::<Test>::KeyAlreadyRegistered
+        );
+
+        assert_ok!(Groth16::retire_key(Origin::root(), 0));
+        assert!(!Keys::<Test>::contains_key(0));
+        assert!(!KeyInputs::<Test>::contains_key(0));
+        assert!(!KeyIds::<Test>::contains_key(fingerprint));
+        System::assert_last_event(Event::KeyRetired { id: 0 }.into());
+
+        assert_noop!(
+            Groth16::retire_key(Origin::root(), 0),
+            Error::<Test>::UnknownKey
+        );
+
+        // Ids are not reused.
+        assert_ok!(Groth16::register_key(Origin::root(), key()));
+        assert_eq!(Groth16::key_id(fingerprint), Some(1));
+    });
+}
+
+#[test]
+fn register_key_checks_origin_and_key() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Groth16::register_key(Origin::signed(1), key()), BadOrigin);
+
+        let mut bad = key();
+        bad.pop();
+        assert_noop!(
+            Groth16::register_key(Origin::root(), bad),
+            Error::<Test>::InvalidKey
+        );
+
+        assert_noop!(
+            Groth16::register_key(Origin::root(), vec![0; 4097]),
+            Error::<Test>::KeyTooLong
+        );
+    });
+}
+
+#[test]
+fn verify_snarkjs_proof() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Groth16::register_key(Origin::root(), key()));
+
+        assert_ok!(Groth16::verify(
+            Origin::signed(1),
+            0,
+            proof(),
+            public_inputs()
+        ));
+        System::assert_last_event(Event::ProofVerified { who: 1, id: 0 }.into());
+
+        assert_ok!(Groth16::verify_proof(0, &proof(), &public_inputs()));
+    });
+}
+
+#[test]
+fn verify_rejects_bad_proofs() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof(), public_inputs()),
+            Error::<Test>::UnknownKey
+        );
+
+        assert_ok!(Groth16::register_key(Origin::root(), key()));
+
+        let mut wrong_input = public_inputs();
+        wrong_input[31] ^= 1;
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof(), wrong_input),
+            Error::<Test>::InvalidProof
+        );
+
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof(), vec![]),
+            Error::<Test>::PublicInputCount
+        );
+
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof(), vec![0; 32 * 64]),
+            Error::<Test>::PublicInputCount
+        );
+
+        let mut ragged = public_inputs();
+        ragged.push(0);
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof(), ragged),
+            Error::<Test>::InvalidEncoding
+        );
+
+        assert_noop!(
+            Groth16::verify(Origin::signed(1), 0, proof()[1..].to_vec(), public_inputs()),
+            Error::<Test>::InvalidEncoding
+        );
+    });
+}
+
+#[test]
+fn verify_is_charged_for_the_submitted_inputs() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Groth16::register_key(Origin::root(), key()));
+
+        let weight = |public_inputs: Vec<u8>| {
+            Call::<Test>::verify {
+                id: 0,
+                proof: proof(),
+                public_inputs,
+            }
+            .get_dispatch_info()
+            .weight
+        };
+        let expected = <Test as crate::Config>::WeightInfo::verify_call;
+        assert_eq!(weight(public_inputs()), expected(1));
+        assert_eq!(weight(vec![]), expected(0));
+        assert_eq!(weight(vec![0; 32 * 64]), expected(64));
+    });
+}
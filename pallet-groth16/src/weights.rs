@@ -0,0 +1,176 @@
+//! Placeholder weights for pallet_groth16.
+//!
+//! These are not the output of `benchmark pallet`, which needs a node this
+//! repository does not have. They were measured once on a single core VM
+//! (`Intel(R) Xeon(R) Processor`, DATE: 2026-10-19, STEPS: `50`, REPEAT: 20)
+//! by running the `benchmarks!` of `benchmarking.rs` natively against the
+//! mock runtime, with `BellmanVerifier<Bn256>` for `Bn254Weight` and
+//! `BellmanVerifier<Bls12>` for `Bls12Weight`, and fitting the results with
+//! `Analysis::min_squares_iqr`. That harness is not part of the repository,
+//! so the numbers cannot be reproduced from it. The in-memory backend does
+//! not count database accesses, so reads and writes are those of the storage
+//! items listed for each function.
+//!
+//! They show how the calls compare and grow, but a runtime must regenerate
+//! them for its curve and hardware with the command below, run from its own
+//! node, which also counts the database accesses.
+//!
+//! The `()` weights are for tests and runtimes that have not benchmarked
+//! yet. Each of their terms is twice the larger of the `Bn254Weight` and
+//! `Bls12Weight` terms, so that they overcharge either curve rather than
+//! undercharge it.
+//!
+//! `l` is the length of a key encoding in bytes, `n` the number of public
+//! inputs.
+
+// Regenerate, in the node of a runtime using the pallet, with:
+// ./target/release/node
+// benchmark
+// pallet
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_groth16
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --output=./pallet-groth16/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_groth16.
+pub trait WeightInfo {
+	fn register_key(l: u32, ) -> Weight;
+	fn retire_key() -> Weight;
+	fn verify_call(n: u32, ) -> Weight;
+	fn verify_proof(n: u32, ) -> Weight;
+	fn prepare_key(n: u32, ) -> Weight;
+}
+
+/// Weights for pallet_groth16 with a BN254 `Config::Verifier`, as for
+/// snarkjs' bn128.
+pub struct Bn254Weight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for Bn254Weight<T> {
+	// Storage: Groth16 KeyIds (r:1 w:1)
+	// Storage: Groth16 NextKeyId (r:1 w:1)
+	// Storage: Groth16 Keys (r:0 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	fn register_key(l: u32, ) -> Weight {
+		(4_037_319_000 as Weight)
+			.saturating_add((36_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Groth16 Keys (r:1 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	// Storage: Groth16 KeyIds (r:0 w:1)
+	fn retire_key() -> Weight {
+		(35_657_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_call(n: u32, ) -> Weight {
+		(9_445_122_000 as Weight)
+			.saturating_add((102_231_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_proof(n: u32, ) -> Weight {
+		(9_213_651_000 as Weight)
+			.saturating_add((110_570_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	fn prepare_key(n: u32, ) -> Weight {
+		(1_370_131_000 as Weight)
+			.saturating_add((652_000 as Weight).saturating_mul(n as Weight))
+	}
+}
+
+/// Weights for pallet_groth16 with a BLS12-381 `Config::Verifier`.
+pub struct Bls12Weight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for Bls12Weight<T> {
+	// Storage: Groth16 KeyIds (r:1 w:1)
+	// Storage: Groth16 NextKeyId (r:1 w:1)
+	// Storage: Groth16 Keys (r:0 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	fn register_key(l: u32, ) -> Weight {
+		(409_366_000 as Weight)
+			.saturating_add((1_757_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Groth16 Keys (r:1 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	// Storage: Groth16 KeyIds (r:0 w:1)
+	fn retire_key() -> Weight {
+		(31_013_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_call(n: u32, ) -> Weight {
+		(7_770_428_000 as Weight)
+			.saturating_add((199_778_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_proof(n: u32, ) -> Weight {
+		(6_769_895_000 as Weight)
+			.saturating_add((210_913_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	}
+	fn prepare_key(n: u32, ) -> Weight {
+		(877_543_000 as Weight)
+			.saturating_add((0 as Weight).saturating_mul(n as Weight))
+	}
+}
+
+// For backwards compatibility and tests. Conservative, see the module docs.
+impl WeightInfo for () {
+	// Storage: Groth16 KeyIds (r:1 w:1)
+	// Storage: Groth16 NextKeyId (r:1 w:1)
+	// Storage: Groth16 Keys (r:0 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	fn register_key(l: u32, ) -> Weight {
+		(8_074_638_000 as Weight)
+			.saturating_add((3_514_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Groth16 Keys (r:1 w:1)
+	// Storage: Groth16 KeyInputs (r:0 w:1)
+	// Storage: Groth16 KeyIds (r:0 w:1)
+	fn retire_key() -> Weight {
+		(71_314_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_call(n: u32, ) -> Weight {
+		(18_890_244_000 as Weight)
+			.saturating_add((399_556_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	// Storage: Groth16 KeyInputs (r:1 w:0)
+	// Storage: Groth16 Keys (r:1 w:0)
+	fn verify_proof(n: u32, ) -> Weight {
+		(18_427_302_000 as Weight)
+			.saturating_add((421_826_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	}
+	fn prepare_key(n: u32, ) -> Weight {
+		(2_740_262_000 as Weight)
+			.saturating_add((1_304_000 as Weight).saturating_mul(n as Weight))
+	}
+}
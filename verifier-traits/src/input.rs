@@ -0,0 +1,201 @@
+//! Parsing of 256-bit public inputs as submitted by EVM or JavaScript
+//! callers. The curve crates map the parsed integers into their scalar
+//! fields according to an `InputMode`.
+
+use codec::{Decode, Encode};
+
+use super::VerifierError;
+
+/// How a 256-bit integer that is not below the scalar field modulus `r` is
+/// treated.
+#[derive(Clone, Copy, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum InputMode {
+    /// Reject values `>= r`, as snarkjs and the Solidity verifiers do.
+    Strict,
+    /// Reduce values modulo `r`. Several integers then map to the same
+    /// public input, so only use this where that cannot be abused, e.g. not
+    /// for nullifiers.
+    Reduce,
+}
+
+/// An error converting a public input.
+#[derive(Clone, Copy, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum InputError {
+    /// Not a decimal or `0x` hex number.
+    InvalidFormat,
+    /// The value does not fit in 256 bits, or the output of
+    /// `be_from_decimal`.
+    Overflow,
+    /// The value is not below the scalar field modulus, in `InputMode::Strict`.
+    OutOfRange,
+}
+
+impl From<InputError> for VerifierError {
+    fn from(_: InputError) -> Self {
+        VerifierError::InvalidEncoding
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::InvalidFormat => write!(f, "not a decimal or 0x hex number"),
+            InputError::Overflow => write!(f, "value is too large"),
+            InputError::OutOfRange => write!(f, "value is not below the field modulus"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InputError {}
+
+/// Sets the big-endian `acc` to `acc * base + digit`, failing with
+/// `InputError::Overflow` if the result does not fit.
+fn mul_add(acc: &mut [u8], base: u32, digit: u32) -> Result<(), InputError> {
+    let mut carry = digit;
+    for byte in acc.iter_mut().rev() {
+        let v = (*byte as u32) * base + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+
+    if carry == 0 {
+        Ok(())
+    } else {
+        Err(InputError::Overflow)
+    }
+}
+
+/// Parses a decimal string, such as an entry of snarkjs' `public.json`, into
+/// 32 big-endian bytes. Leading zeros are allowed, signs and whitespace are
+/// not.
+pub fn u256_from_decimal(s: &str) -> Result<[u8; 32], InputError> {
+    let mut acc = [0u8; 32];
+    be_from_decimal(s, &mut acc)?;
+    Ok(acc)
+}
+
+/// Parses a decimal string into `out` as a big-endian integer of its width,
+/// e.g. a 48 byte BLS12-381 coordinate of a snarkjs key. The same syntax as
+/// `u256_from_decimal` is accepted. `out` is zeroed first and left
+/// unspecified on error.
+pub fn be_from_decimal(s: &str, out: &mut [u8]) -> Result<(), InputError> {
+    if s.is_empty() {
+        return Err(InputError::InvalidFormat);
+    }
+
+    out.fill(0);
+    for c in s.bytes() {
+        if !c.is_ascii_digit() {
+            return Err(InputError::InvalidFormat);
+        }
+        mul_add(out, 10, (c - b'0') as u32)?;
+    }
+
+    Ok(())
+}
+
+/// Parses a `0x` prefixed hex string, as produced by ethers or web3, into 32
+/// big-endian bytes. Digits may be of either case and need not be padded.
+pub fn u256_from_hex(s: &str) -> Result<[u8; 32], InputError> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .ok_or(InputError::InvalidFormat)?;
+    if digits.is_empty() {
+        return Err(InputError::InvalidFormat);
+    }
+
+    let mut acc = [0u8; 32];
+    for c in digits.chars() {
+        let digit = c.to_digit(16).ok_or(InputError::InvalidFormat)?;
+        mul_add(&mut acc, 16, digit)?;
+    }
+
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_DECIMAL: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    fn be(low: &[u8]) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes[32 - low.len()..].copy_from_slice(low);
+        bytes
+    }
+
+    #[test]
+    fn parses_decimal_and_hex() {
+        assert_eq!(u256_from_decimal("0"), Ok([0; 32]));
+        assert_eq!(u256_from_decimal("000258"), Ok(be(&[1, 2])));
+        assert_eq!(u256_from_hex("0x102"), Ok(be(&[1, 2])));
+        assert_eq!(u256_from_hex("0XaBcD"), Ok(be(&[0xab, 0xcd])));
+        // Leading zeros do not count towards the 256 bits.
+        assert_eq!(
+            u256_from_hex(&format!("0x{}1", "0".repeat(80))),
+            Ok(be(&[1]))
+        );
+    }
+
+    #[test]
+    fn parses_up_to_2_256_minus_1() {
+        assert_eq!(u256_from_decimal(MAX_DECIMAL), Ok([0xff; 32]));
+        assert_eq!(
+            u256_from_hex(&format!("0x{}", "f".repeat(64))),
+            Ok([0xff; 32])
+        );
+
+        assert_eq!(
+            u256_from_decimal(&MAX_DECIMAL.replace("935", "936")),
+            Err(InputError::Overflow)
+        );
+        assert_eq!(
+            u256_from_hex(&format!("0x1{}", "0".repeat(64))),
+            Err(InputError::Overflow)
+        );
+    }
+
+    #[test]
+    fn parses_decimal_of_any_width() {
+        let mut out = [0xaa; 48];
+        assert_eq!(be_from_decimal("258", &mut out), Ok(()));
+        assert_eq!(out[..46], [0; 46]);
+        assert_eq!(out[46..], [1, 2]);
+
+        // 2^256 fits in 48 bytes but not in 32.
+        let two_256 = MAX_DECIMAL.replace("935", "936");
+        assert_eq!(be_from_decimal(&two_256, &mut out), Ok(()));
+        assert_eq!(out[15..17], [1, 0]);
+        assert_eq!(be_from_decimal(&two_256, &mut [0; 32]), Err(InputError::Overflow));
+        assert_eq!(be_from_decimal("1", &mut []), Err(InputError::Overflow));
+        assert_eq!(be_from_decimal("0", &mut []), Ok(()));
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        for s in ["", "-1", "+1", " 1", "1 ", "0x1", "1e3", "1_000"] {
+            assert_eq!(
+                u256_from_decimal(s),
+                Err(InputError::InvalidFormat),
+                "{:?}",
+                s
+            );
+        }
+        for s in ["", "0x", "12", "x12", "0x-1", "0x 1", "0xg"] {
+            assert_eq!(u256_from_hex(s), Err(InputError::InvalidFormat), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn input_errors_are_encoding_errors() {
+        assert_eq!(
+            VerifierError::from(InputError::OutOfRange),
+            VerifierError::InvalidEncoding
+        );
+    }
+}
@@ -0,0 +1,356 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! The interface shared by `bellman-verifier` (BLS12-381, and BN254 with its
+//! `bn256` feature) and `bellman-ce-verifier` (BN254 through pairing_ce), so
+//! that application code can be written once against `Groth16Verifier` and
+//! pick the curve backend by type parameter.
+//!
+//! Byte encodings follow the uncompressed points the adapter produces from
+//! snarkjs output:
+//!
+//! * proof: `a || b || c`
+//! * verifying key: `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || ic[0] || ic[1] || ...`
+//! * public input: 32 bytes, big-endian, canonical
+
+use codec::{Decode, Encode};
+use sp_std::prelude::*;
+
+mod input;
+
+pub use self::input::*;
+
+/// Size of an encoded public input.
+pub const PUBLIC_INPUT_SIZE: usize = 32;
+
+/// An error returned through `Groth16Verifier`.
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum VerifierError {
+    /// The verifying key is malformed.
+    InvalidVerifyingKey,
+    /// The number of public inputs does not match the verifying key. When
+    /// fixing inputs of a prepared key, `expected` is the most inputs that
+    /// can be fixed.
+    PublicInputCount { expected: u32, actual: u32 },
+    /// The pairing check failed, the proof is not valid for the key and
+    /// public inputs.
+    InvalidProof,
+    /// Bytes could not be decoded into a point or scalar of the curve.
+    InvalidEncoding,
+}
+
+impl VerifierError {
+    /// `PublicInputCount`, with counts beyond `u32::MAX` saturated.
+    pub fn public_input_count(expected: usize, actual: usize) -> Self {
+        VerifierError::PublicInputCount {
+            expected: u32::try_from(expected).unwrap_or(u32::MAX),
+            actual: u32::try_from(actual).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifierError::InvalidVerifyingKey => write!(f, "malformed verifying key"),
+            VerifierError::PublicInputCount { expected, actual } => {
+                write!(f, "expected {} public inputs, got {}", expected, actual)
+            }
+            VerifierError::InvalidProof => write!(f, "pairing check failed"),
+            VerifierError::InvalidEncoding => write!(f, "invalid point or scalar encoding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifierError {}
+
+/// A Groth16 verifier for one curve backend.
+pub trait Groth16Verifier {
+    /// Scalars of the curve, i.e. public inputs.
+    type Fr;
+    type Proof;
+    type VerifyingKey;
+    type PreparedVerifyingKey;
+
+    fn prepare_verifying_key(vk: &Self::VerifyingKey) -> Self::PreparedVerifyingKey;
+
+    fn verify_proof(
+        pvk: &Self::PreparedVerifyingKey,
+        proof: &Self::Proof,
+        public_inputs: &[Self::Fr],
+    ) -> Result<(), VerifierError>;
+
+    /// Decodes `a || b || c` in uncompressed form.
+    fn decode_proof(bytes: &[u8]) -> Result<Self::Proof, VerifierError>;
+
+    /// Decodes `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || ic...` in
+    /// uncompressed form.
+    fn decode_verifying_key(bytes: &[u8]) -> Result<Self::VerifyingKey, VerifierError>;
+
+    /// The number of public inputs `vk` takes.
+    fn public_input_count(vk: &Self::VerifyingKey) -> usize;
+
+    /// Encodes `vk` in the form `decode_prepared_verifying_key` reads, for
+    /// pallets and contracts to store when a key is registered so that
+    /// verifying with it skips the checks and work done once per key.
+    fn encode_prepared_verifying_key(vk: &Self::VerifyingKey) -> Vec<u8>;
+
+    /// Prepares a key stored by `encode_prepared_verifying_key`. Backends
+    /// may trust the bytes and skip the point checks of
+    /// `decode_verifying_key`, so they must not come from anywhere else.
+    fn decode_prepared_verifying_key(
+        bytes: &[u8],
+    ) -> Result<Self::PreparedVerifyingKey, VerifierError>;
+
+    /// Decodes a canonical 32 byte big-endian scalar.
+    fn decode_public_input(bytes: &[u8]) -> Result<Self::Fr, VerifierError>;
+
+    /// Decodes consecutive 32 byte public inputs.
+    fn decode_public_inputs(bytes: &[u8]) -> Result<Vec<Self::Fr>, VerifierError> {
+        if bytes.len() % PUBLIC_INPUT_SIZE != 0 {
+            return Err(VerifierError::InvalidEncoding);
+        }
+
+        bytes
+            .chunks(PUBLIC_INPUT_SIZE)
+            .map(Self::decode_public_input)
+            .collect()
+    }
+
+    /// Decodes and verifies in one go.
+    fn verify_encoded(
+        pvk: &Self::PreparedVerifyingKey,
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<(), VerifierError> {
+        let proof = Self::decode_proof(proof)?;
+        let public_inputs = Self::decode_public_inputs(public_inputs)?;
+        Self::verify_proof(pvk, &proof, &public_inputs)
+    }
+}
+
+/// An encoded key with a proof and public inputs that verify under it.
+pub struct BenchmarkData {
+    pub key: Vec<u8>,
+    pub proof: Vec<u8>,
+    pub public_inputs: Vec<u8>,
+}
+
+/// Creates keys and proofs of any size for benchmarks.
+///
+/// The keys come from a trapdoor derived from `seed`, with which anything
+/// can be proven, so they must never be registered on a live chain. The
+/// verifier crates implement this with their `runtime-benchmarks` feature.
+pub trait BenchmarkSetup: Groth16Verifier {
+    /// Returns a key for `num_inputs` public inputs, which are all close to
+    /// the modulus so that they cost as much as any input.
+    fn benchmark_setup(num_inputs: usize, seed: u64) -> BenchmarkData;
+}
+
+/// The points of an encoded proof.
+pub struct ProofBytes<'a> {
+    pub a: &'a [u8],
+    pub b: &'a [u8],
+    pub c: &'a [u8],
+}
+
+/// Splits an encoded proof into its points.
+pub fn split_proof(
+    bytes: &[u8],
+    g1_size: usize,
+    g2_size: usize,
+) -> Result<ProofBytes<'_>, VerifierError> {
+    if bytes.len() != 2 * g1_size + g2_size {
+        return Err(VerifierError::InvalidEncoding);
+    }
+
+    let (a, rest) = bytes.split_at(g1_size);
+    let (b, c) = rest.split_at(g2_size);
+    Ok(ProofBytes { a, b, c })
+}
+
+/// The points of an encoded verifying key.
+pub struct VerifyingKeyBytes<'a> {
+    pub alpha_g1: &'a [u8],
+    pub beta_g2: &'a [u8],
+    pub gamma_g2: &'a [u8],
+    pub delta_g2: &'a [u8],
+    pub ic: Vec<&'a [u8]>,
+}
+
+/// Splits an encoded verifying key into its points. At least `ic[0]` must
+/// be present.
+pub fn split_verifying_key(
+    bytes: &[u8],
+    g1_size: usize,
+    g2_size: usize,
+) -> Result<VerifyingKeyBytes<'_>, VerifierError> {
+    let fixed = g1_size + 3 * g2_size;
+    if bytes.len() < fixed + g1_size || (bytes.len() - fixed) % g1_size != 0 {
+        return Err(VerifierError::InvalidEncoding);
+    }
+
+    let (alpha_g1, rest) = bytes.split_at(g1_size);
+    let (beta_g2, rest) = rest.split_at(g2_size);
+    let (gamma_g2, rest) = rest.split_at(g2_size);
+    let (delta_g2, rest) = rest.split_at(g2_size);
+
+    Ok(VerifyingKeyBytes {
+        alpha_g1,
+        beta_g2,
+        gamma_g2,
+        delta_g2,
+        ic: rest.chunks(g1_size).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scalars are the first byte of an input, proofs and keys their bytes.
+    /// A proof verifies if it equals the sum of the inputs.
+    struct Bytes;
+
+    impl Groth16Verifier for Bytes {
+        type Fr = u8;
+        type Proof = Vec<u8>;
+        type VerifyingKey = Vec<u8>;
+        type PreparedVerifyingKey = Vec<u8>;
+
+        fn prepare_verifying_key(vk: &Vec<u8>) -> Vec<u8> {
+            vk.clone()
+        }
+
+        fn verify_proof(
+            pvk: &Vec<u8>,
+            proof: &Vec<u8>,
+            public_inputs: &[u8],
+        ) -> Result<(), VerifierError> {
+            if public_inputs.len() != pvk.len() {
+                return Err(VerifierError::public_input_count(
+                    pvk.len(),
+                    public_inputs.len(),
+                ));
+            }
+            if proof[..] == [public_inputs.iter().sum::<u8>()] {
+                Ok(())
+            } else {
+                Err(VerifierError::InvalidProof)
+            }
+        }
+
+        fn decode_proof(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+            Ok(bytes.to_vec())
+        }
+
+        fn decode_verifying_key(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+            Ok(bytes.to_vec())
+        }
+
+        fn public_input_count(vk: &Vec<u8>) -> usize {
+            vk.len()
+        }
+
+        fn encode_prepared_verifying_key(vk: &Vec<u8>) -> Vec<u8> {
+            vk.clone()
+        }
+
+        fn decode_prepared_verifying_key(bytes: &[u8]) -> Result<Vec<u8>, VerifierError> {
+            Ok(bytes.to_vec())
+        }
+
+        fn decode_public_input(bytes: &[u8]) -> Result<u8, VerifierError> {
+            Ok(bytes[0])
+        }
+    }
+
+    fn input(x: u8) -> [u8; PUBLIC_INPUT_SIZE] {
+        let mut bytes = [0; PUBLIC_INPUT_SIZE];
+        bytes[0] = x;
+        bytes
+    }
+
+    #[test]
+    fn verify_encoded_decodes_every_input() {
+        let inputs = [input(2), input(3)].concat();
+
+        assert_eq!(Bytes::decode_public_inputs(&inputs), Ok(vec![2, 3]));
+        assert_eq!(Bytes::decode_public_inputs(&[]), Ok(vec![]));
+        assert_eq!(Bytes::verify_encoded(&vec![0, 0], &[5], &inputs), Ok(()));
+        assert_eq!(
+            Bytes::verify_encoded(&vec![0, 0], &[6], &inputs),
+            Err(VerifierError::InvalidProof)
+        );
+        assert_eq!(
+            Bytes::verify_encoded(&vec![0], &[5], &inputs),
+            Err(VerifierError::PublicInputCount {
+                expected: 1,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            Bytes::verify_encoded(&vec![0, 0], &[5], &inputs[1..]),
+            Err(VerifierError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn public_input_counts_saturate() {
+        assert_eq!(
+            VerifierError::public_input_count(usize::MAX, 3),
+            VerifierError::PublicInputCount {
+                expected: u32::MAX,
+                actual: 3
+            }
+        );
+    }
+
+    #[test]
+    fn splits_proofs() {
+        let bytes: Vec<u8> = (0..8).collect();
+
+        let proof = split_proof(&bytes, 2, 4).unwrap();
+        assert_eq!(
+            (proof.a, proof.b, proof.c),
+            (&bytes[..2], &bytes[2..6], &bytes[6..])
+        );
+        assert!(split_proof(&bytes[1..], 2, 4).is_err());
+        assert!(split_proof(&[bytes.clone(), vec![0]].concat(), 2, 4).is_err());
+    }
+
+    #[test]
+    fn splits_verifying_keys() {
+        let bytes: Vec<u8> = (0..18).collect();
+
+        let vk = split_verifying_key(&bytes, 2, 4).unwrap();
+        assert_eq!(vk.alpha_g1, &bytes[..2]);
+        assert_eq!(vk.beta_g2, &bytes[2..6]);
+        assert_eq!(vk.gamma_g2, &bytes[6..10]);
+        assert_eq!(vk.delta_g2, &bytes[10..14]);
+        assert_eq!(vk.ic, [&bytes[14..16], &bytes[16..]]);
+
+        // `ic[0]` is required and IC points are whole.
+        assert_eq!(
+            split_verifying_key(&bytes[..14], 2, 4).err(),
+            Some(VerifierError::InvalidEncoding)
+        );
+        assert_eq!(
+            split_verifying_key(&bytes[..17], 2, 4).err(),
+            Some(VerifierError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn errors_display() {
+        assert_eq!(
+            VerifierError::public_input_count(2, 1).to_string(),
+            "expected 2 public inputs, got 1"
+        );
+        assert_eq!(
+            VerifierError::InvalidProof.to_string(),
+            "pairing check failed"
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! Converting proofs, keys and public inputs between the encodings used
+//! around this repository. Everything is decoded into the byte layout of
+//! `verifier_traits`, as in `json`, and encoded from it.
+//!
+//! | Format         | Proofs and keys                                 | Public inputs        |
+//! |----------------|-------------------------------------------------|----------------------|
+//! | `snarkjs`      | `proof.json`, `verification_key.json`           | `public.json`        |
+//! | `uncompressed` | `*_uncompressed.json` of `prove/src/adapter.js` |                      |
+//! | `hex`          | `*_hex.json` of `prove/src/adapter.js`          | `0x` strings         |
+//! | `bytes`        | the layout itself                               | the layout itself    |
+//! | `scale`        | the layout as SCALE `Vec<u8>`, as calls take it | the same             |
+//! | `compressed`   | the layout with compressed points               |                      |
+//! | `evm`          | proofs for snarkjs' Solidity verifier           | the same             |
+//!
+//! `bytes`, `scale` and `compressed` are written as `0x` hex. `evm` is the
+//! call data of `snarkjs generatecall`, which only exists for BN254. The
+//! formats without a curve name take it from the size of the points, or
+//! need it given where both curves fit. Keys are written for snarkjs
+//! without `vk_alphabeta_12`.
+//!
+//! Decoded proofs and keys are checked like the verifier checks them, so
+//! that a conversion never writes an invalid point.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bellman_verifier::bn256::{self, Bn256};
+use bellman_verifier::BellmanVerifier;
+use bls12_381::Bls12;
+use codec::{DecodeAll, Encode};
+use group::{GroupEncoding, UncompressedEncoding};
+use serde_json::{json, Value};
+use thiserror::Error;
+use verifier_traits::{u256_from_hex, Groth16Verifier, VerifierError, PUBLIC_INPUT_SIZE};
+
+use super::json::{self, invalid, Curve, Encoded, JsonError};
+use super::registry::describe;
+
+/// The encodings of the module documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Snarkjs,
+    Uncompressed,
+    Hex,
+    Bytes,
+    Scale,
+    Compressed,
+    Evm,
+}
+
+impl Format {
+    pub const ALL: [Format; 7] = [
+        Format::Snarkjs,
+        Format::Uncompressed,
+        Format::Hex,
+        Format::Bytes,
+        Format::Scale,
+        Format::Compressed,
+        Format::Evm,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Snarkjs => "snarkjs",
+            Format::Uncompressed => "uncompressed",
+            Format::Hex => "hex",
+            Format::Bytes => "bytes",
+            Format::Scale => "scale",
+            Format::Compressed => "compressed",
+            Format::Evm => "evm",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, EncodingError> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| EncodingError::UnknownFormat(s.to_string()))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EncodingError {
+    #[error(transparent)]
+    Json(#[from] JsonError),
+    #[error("unknown format `{0}`")]
+    UnknownFormat(String),
+    #[error("the {format} format has no {what}")]
+    Unsupported { format: Format, what: &'static str },
+    #[error("expected `0x` and hex digits")]
+    Hex,
+    #[error("invalid SCALE encoding: {0}")]
+    Scale(codec::Error),
+    #[error("{len} bytes are not a {what}")]
+    Length { what: &'static str, len: usize },
+    #[error("{len} bytes could be a {what} on either curve, the curve must be given")]
+    Ambiguous { what: &'static str, len: usize },
+    #[error("expected a {what} on {expected}, found one on {found}")]
+    CurveMismatch {
+        what: &'static str,
+        expected: Curve,
+        found: Curve,
+    },
+    #[error("the {0} format cannot hold the point at infinity")]
+    Infinity(Format),
+    #[error("{}", describe(.0))]
+    Invalid(VerifierError),
+}
+
+impl From<VerifierError> for EncodingError {
+    fn from(e: VerifierError) -> Self {
+        EncodingError::Invalid(e)
+    }
+}
+
+/// Proofs and keys, which consist of points.
+#[derive(Clone, Copy)]
+enum Item {
+    Proof,
+    Key,
+}
+
+#[derive(Clone, Copy)]
+enum Point {
+    G1,
+    G2,
+}
+
+impl Point {
+    fn size(self, curve: Curve, compressed: bool) -> usize {
+        let size = match self {
+            Point::G1 => curve.g1_size(),
+            Point::G2 => curve.g2_size(),
+        };
+        if compressed {
+            size / 2
+        } else {
+            size
+        }
+    }
+}
+
+impl Item {
+    fn what(self) -> &'static str {
+        match self {
+            Item::Proof => "proof",
+            Item::Key => "verification key",
+        }
+    }
+
+    /// The points in `len` bytes, if they are an item on `curve`.
+    fn layout(self, curve: Curve, len: usize, compressed: bool) -> Option<Vec<Point>> {
+        let mut points = match self {
+            Item::Proof => vec![Point::G1, Point::G2, Point::G1],
+            Item::Key => vec![Point::G1, Point::G2, Point::G2, Point::G2],
+        };
+        let fixed: usize = points.iter().map(|p| p.size(curve, compressed)).sum();
+        match self {
+            Item::Proof => (len == fixed).then_some(points),
+            Item::Key => {
+                let ic = len.checked_sub(fixed)?;
+                let g1 = Point::G1.size(curve, compressed);
+                if ic == 0 || ic % g1 != 0 {
+                    return None;
+                }
+                points.resize(points.len() + ic / g1, Point::G1);
+                Some(points)
+            }
+        }
+    }
+
+    /// The curve on which `len` bytes are an item, out of `curve` if given.
+    fn curve(
+        self,
+        len: usize,
+        compressed: bool,
+        curve: Option<Curve>,
+    ) -> Result<Curve, EncodingError> {
+        let fits: Vec<Curve> = [Curve::Bn254, Curve::Bls12_381]
+            .into_iter()
+            .filter(|&c| curve.is_none() || curve == Some(c))
+            .filter(|&c| self.layout(c, len, compressed).is_some())
+            .collect();
+        match fits[..] {
+            [curve] => Ok(curve),
+            [] => Err(EncodingError::Length {
+                what: self.what(),
+                len,
+            }),
+            _ => Err(EncodingError::Ambiguous {
+                what: self.what(),
+                len,
+            }),
+        }
+    }
+
+    /// The points of an item in the layout of `verifier_traits`.
+    fn points(self, item: &Encoded) -> Vec<(Point, &[u8])> {
+        let layout = self
+            .layout(item.curve, item.bytes.len(), false)
+            .expect("checked by the caller");
+        let mut rest = &item.bytes[..];
+        layout
+            .into_iter()
+            .map(|p| {
+                let (point, tail) = rest.split_at(p.size(item.curve, false));
+                rest = tail;
+                (p, point)
+            })
+            .collect()
+    }
+
+    /// Checks an item like the verifier does.
+    fn check(self, item: &Encoded) -> Result<(), EncodingError> {
+        match (self, item.curve) {
+            (Item::Proof, Curve::Bn254) => {
+                BellmanVerifier::<Bn256>::decode_proof(&item.bytes)?;
+            }
+            (Item::Proof, Curve::Bls12_381) => {
+                BellmanVerifier::<Bls12>::decode_proof(&item.bytes)?;
+            }
+            (Item::Key, Curve::Bn254) => {
+                BellmanVerifier::<Bn256>::decode_verifying_key(&item.bytes)?;
+            }
+            (Item::Key, Curve::Bls12_381) => {
+                BellmanVerifier::<Bls12>::decode_verifying_key(&item.bytes)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, EncodingError> {
+    s.trim()
+        .strip_prefix("0x")
+        .and_then(|digits| hex::decode(digits).ok())
+        .ok_or(EncodingError::Hex)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn from_scale(s: &str) -> Result<Vec<u8>, EncodingError> {
+    Vec::<u8>::decode_all(&mut &from_hex(s)?[..]).map_err(EncodingError::Scale)
+}
+
+/// Big-endian bytes as a decimal string.
+fn to_decimal(be: &[u8]) -> String {
+    let mut n = be.to_vec();
+    let mut digits = Vec::new();
+    while n.iter().any(|b| *b != 0) {
+        let mut rest = 0u32;
+        for b in n.iter_mut() {
+            let v = rest << 8 | *b as u32;
+            *b = (v / 10) as u8;
+            rest = v % 10;
+        }
+        digits.push(b'0' + rest as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+fn compress<G: UncompressedEncoding + GroupEncoding>(
+    bytes: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), EncodingError> {
+    let mut repr = G::Uncompressed::default();
+    repr.as_mut().copy_from_slice(bytes);
+    let point: G = Option::from(G::from_uncompressed(&repr))
+        .ok_or(EncodingError::Invalid(VerifierError::InvalidEncoding))?;
+    out.extend_from_slice(point.to_bytes().as_ref());
+    Ok(())
+}
+
+fn decompress<G: UncompressedEncoding + GroupEncoding>(
+    bytes: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), EncodingError> {
+    let mut repr = G::Repr::default();
+    repr.as_mut().copy_from_slice(bytes);
+    let point: G = Option::from(G::from_bytes(&repr))
+        .ok_or(EncodingError::Invalid(VerifierError::InvalidEncoding))?;
+    out.extend_from_slice(point.to_uncompressed().as_ref());
+    Ok(())
+}
+
+/// Compresses the points of an item, or decompresses them.
+fn convert_points(
+    curve: Curve,
+    layout: &[Point],
+    bytes: &[u8],
+    compressed: bool,
+) -> Result<Vec<u8>, EncodingError> {
+    let mut out = Vec::new();
+    let mut rest = bytes;
+    for &p in layout {
+        let (point, tail) = rest.split_at(p.size(curve, compressed));
+        rest = tail;
+        match (curve, p, compressed) {
+            (Curve::Bn254, Point::G1, false) => compress::<bn256::G1Affine>(point, &mut out)?,
+            (Curve::Bn254, Point::G2, false) => compress::<bn256::G2Affine>(point, &mut out)?,
+            (Curve::Bn254, Point::G1, true) => decompress::<bn256::G1Affine>(point, &mut out)?,
+            (Curve::Bn254, Point::G2, true) => decompress::<bn256::G2Affine>(point, &mut out)?,
+            (Curve::Bls12_381, Point::G1, false) => {
+                compress::<bls12_381::G1Affine>(point, &mut out)?
+            }
+            (Curve::Bls12_381, Point::G2, false) => {
+                compress::<bls12_381::G2Affine>(point, &mut out)?
+            }
+            (Curve::Bls12_381, Point::G1, true) => {
+                decompress::<bls12_381::G1Affine>(point, &mut out)?
+            }
+            (Curve::Bls12_381, Point::G2, true) => {
+                decompress::<bls12_381::G2Affine>(point, &mut out)?
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Both curves set `0x40` in the first byte of the point at infinity.
+fn is_infinity(point: &[u8]) -> bool {
+    point[0] & 0x40 != 0
+}
+
+fn point_to_snarkjs(curve: Curve, p: Point, bytes: &[u8]) -> Result<Value, EncodingError> {
+    if is_infinity(bytes) {
+        return Err(EncodingError::Infinity(Format::Snarkjs));
+    }
+    let fq: Vec<String> = bytes.chunks(curve.fq_size()).map(to_decimal).collect();
+    Ok(match p {
+        Point::G1 => json!([fq[0], fq[1], "1"]),
+        Point::G2 => json!([[fq[1], fq[0]], [fq[3], fq[2]], ["1", "0"]]),
+    })
+}
+
+/// An item from the `0x` strings of `fields` in a `*_hex.json`, with `ic`
+/// as `ic0`, `ic1`, ... for keys.
+fn item_from_hex(item: Item, input: &str, fields: &[&str]) -> Result<Encoded, EncodingError> {
+    let v: Value = serde_json::from_str(input).map_err(JsonError::from)?;
+    let field = |name: &str| -> Result<Option<Vec<u8>>, EncodingError> {
+        match &v[name] {
+            Value::Null => Ok(None),
+            Value::String(s) => {
+                Ok(Some(from_hex(s).map_err(|_| {
+                    invalid(name, "expected `0x` and hex digits")
+                })?))
+            }
+            _ => Err(invalid(name, "expected `0x` and hex digits").into()),
+        }
+    };
+
+    let mut points = Vec::new();
+    for name in fields {
+        points.push(field(name)?.ok_or_else(|| invalid(name, "missing"))?);
+    }
+    if let Item::Key = item {
+        for i in 0.. {
+            match field(&format!("ic{}", i))? {
+                Some(point) => points.push(point),
+                None => break,
+            }
+        }
+    }
+
+    let found = Curve::from_g1_size(fields[0], points[0].len())?;
+    let bytes = points.concat();
+    raw(item, bytes, Some(found))
+}
+
+/// The arrays of `snarkjs generatecall`, written without the outer brackets.
+fn evm_arrays(input: &str) -> Result<Vec<Value>, EncodingError> {
+    let v: Value = serde_json::from_str(&format!("[{}]", input.trim())).map_err(JsonError::from)?;
+    Ok(v.as_array().cloned().unwrap_or_default())
+}
+
+/// Flattens nested arrays of `0x` strings into 32 byte words.
+fn evm_words(field: &str, v: &Value, out: &mut Vec<u8>) -> Result<(), EncodingError> {
+    match v {
+        Value::String(s) => {
+            out.extend(u256_from_hex(s).map_err(|_| invalid(field, "expected a `0x` uint256"))?)
+        }
+        Value::Array(values) => {
+            for v in values {
+                evm_words(field, v, out)?;
+            }
+        }
+        _ => return Err(invalid(field, "expected arrays of `0x` uint256").into()),
+    }
+    Ok(())
+}
+
+fn evm_word(bytes: &[u8]) -> Value {
+    Value::String(to_hex(bytes))
+}
+
+fn only_bn254(what: &'static str, curve: Option<Curve>) -> Result<(), EncodingError> {
+    match curve {
+        Some(Curve::Bls12_381) => Err(EncodingError::Unsupported {
+            format: Format::Evm,
+            what,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// The bytes of an item whose format has no curve name.
+fn raw(item: Item, bytes: Vec<u8>, curve: Option<Curve>) -> Result<Encoded, EncodingError> {
+    Ok(Encoded {
+        curve: item.curve(bytes.len(), false, curve)?,
+        bytes,
+    })
+}
+
+fn expect_curve(
+    item: Item,
+    decoded: Encoded,
+    curve: Option<Curve>,
+) -> Result<Encoded, EncodingError> {
+    match curve {
+        Some(expected) if expected != decoded.curve => Err(EncodingError::CurveMismatch {
+            what: item.what(),
+            expected,
+            found: decoded.curve,
+        }),
+        _ => {
+            item.check(&decoded)?;
+            Ok(decoded)
+        }
+    }
+}
+
+fn decode(
+    item: Item,
+    format: Format,
+    input: &str,
+    curve: Option<Curve>,
+) -> Result<Encoded, EncodingError> {
+    let decoded = match (item, format) {
+        (Item::Proof, Format::Snarkjs | Format::Uncompressed) => json::parse_proof(input)?,
+        (Item::Key, Format::Snarkjs | Format::Uncompressed) => json::parse_vkey(input)?,
+        (Item::Proof, Format::Hex) => item_from_hex(item, input, &["pi_a", "pi_b", "pi_c"])?,
+        (Item::Key, Format::Hex) => {
+            item_from_hex(item, input, &["alpha_1", "beta_2", "gamma_2", "delta_2"])?
+        }
+        (_, Format::Bytes) => raw(item, from_hex(input)?, curve)?,
+        (_, Format::Scale) => raw(item, from_scale(input)?, curve)?,
+        (_, Format::Compressed) => {
+            let bytes = from_hex(input)?;
+            let curve = item.curve(bytes.len(), true, curve)?;
+            let layout = item.layout(curve, bytes.len(), true).unwrap();
+            Encoded {
+                curve,
+                bytes: convert_points(curve, &layout, &bytes, true)?,
+            }
+        }
+        (Item::Proof, Format::Evm) => {
+            only_bn254("bls12-381 proofs", curve)?;
+            let arrays = evm_arrays(input)?;
+            // `generatecall` also writes the public inputs.
+            if !(3..=4).contains(&arrays.len()) {
+                return Err(invalid("calldata", "expected the arrays a, b, c").into());
+            }
+            let mut bytes = Vec::new();
+            for (field, v) in ["a", "b", "c"].iter().zip(&arrays) {
+                evm_words(field, v, &mut bytes)?;
+            }
+            raw(item, bytes, Some(Curve::Bn254))?
+        }
+        (Item::Key, Format::Evm) => {
+            return Err(EncodingError::Unsupported {
+                format,
+                what: "verification keys",
+            })
+        }
+    };
+    expect_curve(item, decoded, curve)
+}
+
+fn encode(item: Item, format: Format, encoded: &Encoded) -> Result<String, EncodingError> {
+    let curve = encoded.curve;
+    item.curve(encoded.bytes.len(), false, Some(curve))?;
+    let points = item.points(encoded);
+    Ok(match format {
+        Format::Snarkjs => {
+            let values = points
+                .iter()
+                .map(|&(p, bytes)| point_to_snarkjs(curve, p, bytes))
+                .collect::<Result<Vec<_>, _>>()?;
+            let v = match item {
+                Item::Proof => json!({
+                    "pi_a": values[0],
+                    "pi_b": values[1],
+                    "pi_c": values[2],
+                    "protocol": "groth16",
+                    "curve": curve.snarkjs_name(),
+                }),
+                Item::Key => json!({
+                    "protocol": "groth16",
+                    "curve": curve.snarkjs_name(),
+                    "nPublic": values.len() - 5,
+                    "vk_alpha_1": values[0],
+                    "vk_beta_2": values[1],
+                    "vk_gamma_2": values[2],
+                    "vk_delta_2": values[3],
+                    "IC": values[4..],
+                }),
+            };
+            serde_json::to_string_pretty(&v).map_err(JsonError::from)?
+        }
+        Format::Uncompressed => {
+            let v = match item {
+                Item::Proof => json!({
+                    "pi_a": points[0].1,
+                    "pi_b": points[1].1,
+                    "pi_c": points[2].1,
+                }),
+                Item::Key => json!({
+                    "alpha_1": points[0].1,
+                    "beta_2": points[1].1,
+                    "gamma_2": points[2].1,
+                    "delta_2": points[3].1,
+                    "ic": points[4..].iter().map(|p| p.1).collect::<Vec<_>>(),
+                }),
+            };
+            v.to_string()
+        }
+        Format::Hex => {
+            let mut v = json!({});
+            let names: &[&str] = match item {
+                Item::Proof => &["pi_a", "pi_b", "pi_c"],
+                Item::Key => &["alpha_1", "beta_2", "gamma_2", "delta_2"],
+            };
+            for (i, (_, bytes)) in points.iter().enumerate() {
+                let name = match names.get(i) {
+                    Some(name) => name.to_string(),
+                    None => format!("ic{}", i - names.len()),
+                };
+                v[name] = to_hex(bytes).into();
+            }
+            v.to_string()
+        }
+        Format::Bytes => to_hex(&encoded.bytes),
+        Format::Scale => to_hex(&encoded.bytes.encode()),
+        Format::Compressed => {
+            let layout: Vec<Point> = points.iter().map(|p| p.0).collect();
+            to_hex(&convert_points(curve, &layout, &encoded.bytes, false)?)
+        }
+        Format::Evm => match item {
+            Item::Proof => {
+                only_bn254("bls12-381 proofs", Some(curve))?;
+                if points.iter().any(|p| is_infinity(p.1)) {
+                    return Err(EncodingError::Infinity(format));
+                }
+                let words: Vec<Value> = encoded.bytes.chunks(32).map(evm_word).collect();
+                format!(
+                    "{},{},{}",
+                    json!([words[0], words[1]]),
+                    json!([[words[2], words[3]], [words[4], words[5]]]),
+                    json!([words[6], words[7]]),
+                )
+            }
+            Item::Key => {
+                return Err(EncodingError::Unsupported {
+                    format,
+                    what: "verification keys",
+                })
+            }
+        },
+    })
+}
+
+/// Reads a proof. `curve` is needed where the format has no curve name and
+/// both curves fit, and is checked otherwise.
+pub fn decode_proof(
+    format: Format,
+    input: &str,
+    curve: Option<Curve>,
+) -> Result<Encoded, EncodingError> {
+    decode(Item::Proof, format, input, curve)
+}
+
+pub fn encode_proof(format: Format, proof: &Encoded) -> Result<String, EncodingError> {
+    encode(Item::Proof, format, proof)
+}
+
+/// Reads a verification key, see `decode_proof`.
+pub fn decode_vkey(
+    format: Format,
+    input: &str,
+    curve: Option<Curve>,
+) -> Result<Encoded, EncodingError> {
+    decode(Item::Key, format, input, curve)
+}
+
+pub fn encode_vkey(format: Format, key: &Encoded) -> Result<String, EncodingError> {
+    encode(Item::Key, format, key)
+}
+
+/// Reads public inputs into consecutive 32 byte inputs.
+pub fn decode_public_inputs(format: Format, input: &str) -> Result<Vec<u8>, EncodingError> {
+    let bytes = match format {
+        Format::Snarkjs | Format::Hex => json::parse_public_inputs(input)?,
+        Format::Bytes => from_hex(input)?,
+        Format::Scale => from_scale(input)?,
+        Format::Evm => {
+            // The inputs alone, or the call data of `generatecall`.
+            let arrays = evm_arrays(input)?;
+            let inputs = match &arrays[..] {
+                [Value::Array(inputs)] => inputs,
+                [_, _, _, Value::Array(inputs)] => inputs,
+                _ => return Err(invalid("calldata", "expected an array of inputs").into()),
+            };
+            let mut bytes = Vec::new();
+            evm_words("input", &Value::Array(inputs.clone()), &mut bytes)?;
+            bytes
+        }
+        Format::Uncompressed | Format::Compressed => {
+            return Err(EncodingError::Unsupported {
+                format,
+                what: "public inputs",
+            })
+        }
+    };
+    if bytes.len() % PUBLIC_INPUT_SIZE != 0 {
+        return Err(EncodingError::Length {
+            what: "list of public inputs",
+            len: bytes.len(),
+        });
+    }
+    Ok(bytes)
+}
+
+pub fn encode_public_inputs(format: Format, inputs: &[u8]) -> Result<String, EncodingError> {
+    let inputs = inputs.chunks(PUBLIC_INPUT_SIZE);
+    Ok(match format {
+        Format::Snarkjs => {
+            serde_json::to_string_pretty(&inputs.map(to_decimal).collect::<Vec<_>>())
+                .map_err(JsonError::from)?
+        }
+        Format::Hex | Format::Evm => Value::Array(inputs.map(evm_word).collect()).to_string(),
+        Format::Bytes => to_hex(&inputs.collect::<Vec<_>>().concat()),
+        Format::Scale => to_hex(&inputs.collect::<Vec<_>>().concat().encode()),
+        Format::Uncompressed | Format::Compressed => {
+            return Err(EncodingError::Unsupported {
+                format,
+                what: "public inputs",
+            })
+        }
+    })
+}
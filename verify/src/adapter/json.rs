@@ -0,0 +1,274 @@
+//! Reading the JSON files of snarkjs and `prove/src/adapter.js` into the
+//! byte layout of `verifier_traits`: uncompressed points, a proof as
+//! `a || b || c`, a key as `alpha_g1 || beta_g2 || gamma_g2 || delta_g2 || ic...`
+//! and public inputs as 32 byte big-endian scalars.
+//!
+//! Files are only converted here, points and scalars are checked when the
+//! bytes are decoded for a curve.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+use thiserror::Error;
+use verifier_traits::{be_from_decimal, u256_from_decimal, u256_from_hex, InputError};
+
+/// The curves snarkjs writes keys and proofs for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Curve {
+    /// `bn128` in snarkjs.
+    Bn254,
+    /// `bls12381` in snarkjs.
+    Bls12_381,
+}
+
+impl Curve {
+    /// Bytes of a base field element.
+    pub fn fq_size(self) -> usize {
+        match self {
+            Curve::Bn254 => 32,
+            Curve::Bls12_381 => 48,
+        }
+    }
+
+    /// Bytes of an uncompressed G1 point.
+    pub fn g1_size(self) -> usize {
+        2 * self.fq_size()
+    }
+
+    /// Bytes of an uncompressed G2 point.
+    pub fn g2_size(self) -> usize {
+        4 * self.fq_size()
+    }
+
+    /// The name of the curve in snarkjs' files.
+    pub fn snarkjs_name(self) -> &'static str {
+        match self {
+            Curve::Bn254 => "bn128",
+            Curve::Bls12_381 => "bls12381",
+        }
+    }
+
+    fn from_snarkjs(v: &Value) -> Result<Self, JsonError> {
+        match v["curve"].as_str() {
+            Some("bn128") => Ok(Curve::Bn254),
+            Some("bls12381") => Ok(Curve::Bls12_381),
+            Some(other) => Err(JsonError::UnsupportedCurve(other.to_string())),
+            None => Err(invalid("curve", "expected a curve name")),
+        }
+    }
+
+    pub(super) fn from_g1_size(field: &str, size: usize) -> Result<Self, JsonError> {
+        [Curve::Bn254, Curve::Bls12_381]
+            .into_iter()
+            .find(|c| c.g1_size() == size)
+            .ok_or_else(|| invalid(field, "not an uncompressed G1 point"))
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Curve::Bn254 => "bn254",
+            Curve::Bls12_381 => "bls12-381",
+        })
+    }
+}
+
+impl FromStr for Curve {
+    type Err = JsonError;
+
+    /// Accepts both these names and those of snarkjs.
+    fn from_str(s: &str) -> Result<Self, JsonError> {
+        match s {
+            "bn254" | "bn128" => Ok(Curve::Bn254),
+            "bls12-381" | "bls12381" => Ok(Curve::Bls12_381),
+            _ => Err(JsonError::UnsupportedCurve(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum JsonError {
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported curve `{0}`")]
+    UnsupportedCurve(String),
+    #[error("`{field}`: {reason}")]
+    InvalidField { field: String, reason: &'static str },
+    #[error("neither a snarkjs nor an uncompressed {0}")]
+    UnknownFormat(&'static str),
+}
+
+pub(super) fn invalid(field: &str, reason: &'static str) -> JsonError {
+    JsonError::InvalidField {
+        field: field.to_string(),
+        reason,
+    }
+}
+
+/// A key or proof in the byte layout of `verifier_traits`, and its curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Encoded {
+    pub curve: Curve,
+    pub bytes: Vec<u8>,
+}
+
+/// A decimal string as `size` big-endian bytes.
+fn fq_from_decimal(field: &str, v: &Value, size: usize) -> Result<Vec<u8>, JsonError> {
+    let mut be = vec![0u8; size];
+    match v.as_str().map(|s| be_from_decimal(s, &mut be)) {
+        Some(Ok(())) => Ok(be),
+        Some(Err(InputError::Overflow)) => Err(invalid(field, "coordinate out of range")),
+        _ => Err(invalid(field, "expected a decimal string")),
+    }
+}
+
+/// `[x, y, "1"]` as `x || y`.
+fn g1_from_snarkjs(
+    curve: Curve,
+    field: &str,
+    v: &Value,
+    out: &mut Vec<u8>,
+) -> Result<(), JsonError> {
+    match v.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if z == "1" => {
+            out.extend(fq_from_decimal(field, x, curve.fq_size())?);
+            out.extend(fq_from_decimal(field, y, curve.fq_size())?);
+            Ok(())
+        }
+        _ => Err(invalid(field, "expected an affine G1 point [x, y, \"1\"]")),
+    }
+}
+
+/// `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]` as
+/// `x.c1 || x.c0 || y.c1 || y.c0`.
+fn g2_from_snarkjs(
+    curve: Curve,
+    field: &str,
+    v: &Value,
+    out: &mut Vec<u8>,
+) -> Result<(), JsonError> {
+    let fq2 = |c: &Value| match c.as_array().map(Vec::as_slice) {
+        Some([c0, c1]) => Some((c0.clone(), c1.clone())),
+        _ => None,
+    };
+    let err = || invalid(field, "expected an affine G2 point [x, y, [\"1\", \"0\"]]");
+
+    match v.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) if fq2(z) == Some(("1".into(), "0".into())) => {
+            for (c0, c1) in [fq2(x).ok_or_else(err)?, fq2(y).ok_or_else(err)?] {
+                out.extend(fq_from_decimal(field, &c1, curve.fq_size())?);
+                out.extend(fq_from_decimal(field, &c0, curve.fq_size())?);
+            }
+            Ok(())
+        }
+        _ => Err(err()),
+    }
+}
+
+/// An array of bytes, of `size` bytes if given.
+fn bytes(field: &str, v: &Value, size: Option<usize>) -> Result<Vec<u8>, JsonError> {
+    let bytes: Vec<u8> = v
+        .as_array()
+        .and_then(|a| a.iter().map(|b| b.as_u64()?.try_into().ok()).collect())
+        .ok_or_else(|| invalid(field, "expected an array of bytes"))?;
+    match size {
+        Some(size) if bytes.len() != size => Err(invalid(field, "wrong number of bytes")),
+        _ => Ok(bytes),
+    }
+}
+
+/// Reads a snarkjs `verification_key.json` or a `vkey_uncompressed.json`.
+pub fn parse_vkey(json: &str) -> Result<Encoded, JsonError> {
+    let v: Value = serde_json::from_str(json)?;
+
+    if v.get("vk_alpha_1").is_some() {
+        let curve = Curve::from_snarkjs(&v)?;
+        let ic = v["IC"]
+            .as_array()
+            .filter(|ic| !ic.is_empty())
+            .ok_or_else(|| invalid("IC", "expected an array of G1 points"))?;
+        if let Some(n) = v["nPublic"].as_u64() {
+            if ic.len() as u64 != n + 1 {
+                return Err(invalid("IC", "does not match `nPublic`"));
+            }
+        }
+
+        let mut bytes = Vec::new();
+        g1_from_snarkjs(curve, "vk_alpha_1", &v["vk_alpha_1"], &mut bytes)?;
+        for field in ["vk_beta_2", "vk_gamma_2", "vk_delta_2"] {
+            g2_from_snarkjs(curve, field, &v[field], &mut bytes)?;
+        }
+        for (i, p) in ic.iter().enumerate() {
+            g1_from_snarkjs(curve, &format!("IC[{}]", i), p, &mut bytes)?;
+        }
+        Ok(Encoded { curve, bytes })
+    } else if v.get("alpha_1").is_some() {
+        let mut bytes = self::bytes("alpha_1", &v["alpha_1"], None)?;
+        let curve = Curve::from_g1_size("alpha_1", bytes.len())?;
+        for field in ["beta_2", "gamma_2", "delta_2"] {
+            bytes.extend(self::bytes(field, &v[field], Some(curve.g2_size()))?);
+        }
+        let ic = v["ic"]
+            .as_array()
+            .filter(|ic| !ic.is_empty())
+            .ok_or_else(|| invalid("ic", "expected an array of G1 points"))?;
+        for (i, p) in ic.iter().enumerate() {
+            bytes.extend(self::bytes(
+                &format!("ic[{}]", i),
+                p,
+                Some(curve.g1_size()),
+            )?);
+        }
+        Ok(Encoded { curve, bytes })
+    } else {
+        Err(JsonError::UnknownFormat("verification key"))
+    }
+}
+
+/// Reads a snarkjs `proof.json` or a `proof_uncompressed.json`.
+pub fn parse_proof(json: &str) -> Result<Encoded, JsonError> {
+    let v: Value = serde_json::from_str(json)?;
+
+    match v["pi_a"].as_array().and_then(|a| a.first()) {
+        Some(Value::String(_)) => {
+            let curve = Curve::from_snarkjs(&v)?;
+            let mut bytes = Vec::new();
+            g1_from_snarkjs(curve, "pi_a", &v["pi_a"], &mut bytes)?;
+            g2_from_snarkjs(curve, "pi_b", &v["pi_b"], &mut bytes)?;
+            g1_from_snarkjs(curve, "pi_c", &v["pi_c"], &mut bytes)?;
+            Ok(Encoded { curve, bytes })
+        }
+        Some(Value::Number(_)) => {
+            let mut bytes = self::bytes("pi_a", &v["pi_a"], None)?;
+            let curve = Curve::from_g1_size("pi_a", bytes.len())?;
+            bytes.extend(self::bytes("pi_b", &v["pi_b"], Some(curve.g2_size()))?);
+            bytes.extend(self::bytes("pi_c", &v["pi_c"], Some(curve.g1_size()))?);
+            Ok(Encoded { curve, bytes })
+        }
+        _ => Err(JsonError::UnknownFormat("proof")),
+    }
+}
+
+/// Reads a public input, a decimal string as in snarkjs' `public.json` or a
+/// `0x` hex string, into 32 big-endian bytes.
+pub fn parse_public_input(s: &str) -> Option<[u8; 32]> {
+    if s.starts_with("0x") || s.starts_with("0X") {
+        u256_from_hex(s).ok()
+    } else {
+        u256_from_decimal(s).ok()
+    }
+}
+
+/// Reads snarkjs' `public.json` into consecutive 32 byte inputs.
+pub fn parse_public_inputs(json: &str) -> Result<Vec<u8>, JsonError> {
+    let inputs: Vec<String> = serde_json::from_str(json)?;
+    let mut bytes = Vec::with_capacity(32 * inputs.len());
+    for (i, input) in inputs.iter().enumerate() {
+        let input = parse_public_input(input)
+            .ok_or_else(|| invalid(&format!("[{}]", i), "expected a 256 bit integer"))?;
+        bytes.extend(input);
+    }
+    Ok(bytes)
+}
@@ -1,12 +1,19 @@
+pub mod encoding;
+pub mod json;
 pub mod parser_bls;
 pub mod parser_bn;
+pub mod pool;
+pub mod registry;
+pub mod replay;
 pub mod types;
 use ff::PrimeField as Fr;
 pub use parser_bls::{parse_bls_proof, parse_bls_vkey};
-pub use parser_bn::{parse_bn_proof, parse_bn_vkey};
+pub use parser_bn::{parse_bn_alphabeta, parse_bn_proof, parse_bn_vkey};
+pub use pool::{Job, VerificationPool};
+pub use registry::{KeyId, VkeyRegistry};
 
 use std::env;
-pub use types::{ProofStr, VkeyStr};
+pub use types::{ProofStr, SnarkjsVkeyStr, VkeyStr};
 
 #[test]
 pub fn snark_proof_bellman_bls_verify() {
@@ -2,9 +2,11 @@ use bellman_ce::groth16::{
     Proof, VerifyingKey,
 };
 use group::Group;
-use pairing_ce::bn256::{ G1Affine, G2Affine, G1Uncompressed, G2Uncompressed };
+use pairing_ce::bn256::{ G1Affine, G2Affine, G1Uncompressed, G2Uncompressed, Fq, Fq2, Fq6, Fq12 };
 use pairing_ce::{CurveAffine, RawEncodable, Engine, GenericCurveProjective, EncodedPoint, GroupDecodingError};
-use super::{ProofStr, VkeyStr};
+use pairing_ce::ff::{Field, PrimeField};
+use super::json::{invalid, JsonError};
+use super::{ProofStr, SnarkjsVkeyStr, VkeyStr};
 use std::path::PathBuf;
 use std::fs;
 
@@ -100,4 +102,58 @@ E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>
         delta_g2: delta2_affine,
         ic,
     }
-}
\ No newline at end of file
+}
+
+/// `λ⁻¹ mod r` for `λ = 2x(6x² + 3x + 1)`, `x` the BN parameter, as little
+/// endian limbs. snarkjs' final exponentiation raises to `λ` times the
+/// exponent of pairing_ce's, so a snarkjs `e(a, b)` is pairing_ce's to the
+/// power of `λ`.
+const SNARKJS_TO_PAIRING_CE: [u64; 4] = [
+    0xb8ca0b2d36636f22,
+    0xcc37a73fec2bc5e9,
+    0x048b6e193fd84104,
+    0x30644e72e131a029,
+];
+
+/// Reads `vk_alphabeta_12` from the contents of snarkjs' bn128
+/// `verification_key.json` into pairing_ce's `e(alpha_1, beta_2)`, so that
+/// `bellman_ce_verifier::prepare_verifying_key_with_alpha_beta` can skip the
+/// Miller loop. snarkjs writes the value with the same Fq12 = Fq6[w],
+/// Fq6 = Fq2[v], Fq2 = Fq[u] tower as pairing_ce, but a different final
+/// exponentiation, which is undone by raising it to `SNARKJS_TO_PAIRING_CE`.
+///
+/// Whether the value is `e(alpha_1, beta_2)` is not checked here; pass `check`
+/// to `prepare_verifying_key_with_alpha_beta` for that.
+pub fn parse_bn_alphabeta(json: &str) -> Result<Fq12, JsonError> {
+    let vk: SnarkjsVkeyStr = serde_json::from_str(json)?;
+    let field = "vk_alphabeta_12";
+
+    let fq = |c: &String| {
+        Fq::from_str(c).ok_or_else(|| invalid(field, "not a decimal element of Fq"))
+    };
+    let fq2 = |c: &Vec<String>| match &c[..] {
+        [c0, c1] => Ok(Fq2 {
+            c0: fq(c0)?,
+            c1: fq(c1)?,
+        }),
+        _ => Err(invalid(field, "expected [c0, c1] of Fq2")),
+    };
+    let fq6 = |c: &Vec<Vec<String>>| match &c[..] {
+        [c0, c1, c2] => Ok(Fq6 {
+            c0: fq2(c0)?,
+            c1: fq2(c1)?,
+            c2: fq2(c2)?,
+        }),
+        _ => Err(invalid(field, "expected [c0, c1, c2] of Fq6")),
+    };
+
+    let alpha_beta = match &vk.vk_alphabeta_12[..] {
+        [c0, c1] => Fq12 {
+            c0: fq6(c0)?,
+            c1: fq6(c1)?,
+        },
+        _ => return Err(invalid(field, "expected [c0, c1] of Fq12")),
+    };
+
+    Ok(alpha_beta.pow(SNARKJS_TO_PAIRING_CE))
+}
@@ -0,0 +1,202 @@
+//! Verifying many independent proofs on all cores.
+//!
+//! `VerificationPool` runs jobs on a rayon thread pool against keys resolved
+//! from a `VkeyRegistry` beforehand. At most `max_in_flight` jobs are queued or
+//! running at a time: `submit` blocks, `try_submit` hands the job back and,
+//! with the `tokio` feature, `verify` waits asynchronously until a job
+//! finishes. This keeps a fast producer from queueing unbounded work.
+
+use std::num::NonZeroUsize;
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use verifier_traits::VerifierError;
+
+use super::registry::{PreparedKey, VkeyRegistry};
+
+/// A proof to verify, in the byte layout of `verifier_traits`, against a key
+/// from `VkeyRegistry::resolve`.
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub key: Arc<PreparedKey>,
+    pub proof: Vec<u8>,
+    pub public_inputs: Vec<u8>,
+}
+
+pub type JobResult = Result<(), VerifierError>;
+
+/// A counting semaphore that can be waited on from threads and, with the
+/// `tokio` feature, from tasks.
+struct Permits {
+    available: Mutex<usize>,
+    released: Condvar,
+    #[cfg(feature = "tokio")]
+    released_async: tokio::sync::Notify,
+}
+
+impl Permits {
+    fn try_acquire(&self) -> bool {
+        let mut available = self.available.lock().unwrap();
+        if *available == 0 {
+            return false;
+        }
+        *available -= 1;
+        true
+    }
+
+    fn acquire(&self) {
+        let mut available = self.available.lock().unwrap();
+        while *available == 0 {
+            available = self.released.wait(available).unwrap();
+        }
+        *available -= 1;
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn acquire_async(&self) {
+        // A release between the failed attempt and `notified` leaves a
+        // stored notification, so no wakeup is lost.
+        while !self.try_acquire() {
+            self.released_async.notified().await;
+        }
+    }
+
+    fn release(&self) {
+        *self.available.lock().unwrap() += 1;
+        self.released.notify_one();
+        #[cfg(feature = "tokio")]
+        self.released_async.notify_one();
+    }
+}
+
+/// Returns its permit when dropped.
+struct Permit(Arc<Permits>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// The result of a submitted job.
+pub struct Pending(mpsc::Receiver<JobResult>);
+
+impl Pending {
+    /// Blocks until the job is done.
+    pub fn wait(self) -> JobResult {
+        self.0
+            .recv()
+            .expect("verification jobs always send a result")
+    }
+
+    /// The result, if the job is done.
+    pub fn try_result(&self) -> Option<JobResult> {
+        self.0.try_recv().ok()
+    }
+}
+
+/// See the module documentation.
+pub struct VerificationPool {
+    registry: Arc<VkeyRegistry>,
+    threads: ThreadPool,
+    permits: Arc<Permits>,
+    max_in_flight: usize,
+}
+
+impl VerificationPool {
+    /// A pool of `threads` workers, or one per core if `threads` is 0, that
+    /// holds up to `max_in_flight` jobs.
+    pub fn new(
+        registry: Arc<VkeyRegistry>,
+        threads: usize,
+        max_in_flight: NonZeroUsize,
+    ) -> Result<Self, ThreadPoolBuildError> {
+        let max_in_flight = max_in_flight.get();
+        let threads = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("groth16-verifier-{}", i))
+            .build()?;
+
+        Ok(VerificationPool {
+            registry,
+            threads,
+            permits: Arc::new(Permits {
+                available: Mutex::new(max_in_flight),
+                released: Condvar::new(),
+                #[cfg(feature = "tokio")]
+                released_async: tokio::sync::Notify::new(),
+            }),
+            max_in_flight,
+        })
+    }
+
+    pub fn registry(&self) -> &Arc<VkeyRegistry> {
+        &self.registry
+    }
+
+    /// The number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.threads.current_num_threads()
+    }
+
+    /// The number of jobs queued or running.
+    pub fn in_flight(&self) -> usize {
+        self.max_in_flight - *self.permits.available.lock().unwrap()
+    }
+
+    /// Runs a job that holds a permit and hands its result to `deliver`.
+    fn spawn(&self, job: Job, deliver: impl FnOnce(JobResult) + Send + 'static) {
+        let permit = Permit(self.permits.clone());
+        self.threads.spawn(move || {
+            let result = job.key.verify(&job.proof, &job.public_inputs);
+            // Free the slot before the result is seen, so that whoever
+            // waited on it can submit right away.
+            drop(permit);
+            deliver(result);
+        });
+    }
+
+    fn spawn_pending(&self, job: Job) -> Pending {
+        let (tx, rx) = mpsc::sync_channel(1);
+        self.spawn(job, move |result| {
+            // The caller may have dropped `Pending`.
+            let _ = tx.send(result);
+        });
+        Pending(rx)
+    }
+
+    /// Queues a job, blocking while `max_in_flight` jobs are in flight.
+    pub fn submit(&self, job: Job) -> Pending {
+        self.permits.acquire();
+        self.spawn_pending(job)
+    }
+
+    /// Queues a job if fewer than `max_in_flight` are in flight, and hands
+    /// it back otherwise.
+    pub fn try_submit(&self, job: Job) -> Result<Pending, Job> {
+        if !self.permits.try_acquire() {
+            return Err(job);
+        }
+        Ok(self.spawn_pending(job))
+    }
+
+    /// Verifies all jobs, returning their results in order.
+    pub fn verify_all(&self, jobs: impl IntoIterator<Item = Job>) -> Vec<JobResult> {
+        let pending: Vec<Pending> = jobs.into_iter().map(|job| self.submit(job)).collect();
+        pending.into_iter().map(Pending::wait).collect()
+    }
+
+    /// Verifies a job from async code, waiting without blocking the runtime
+    /// while the pool is full.
+    #[cfg(feature = "tokio")]
+    pub async fn verify(&self, job: Job) -> JobResult {
+        self.permits.acquire_async().await;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.spawn(job, move |result| {
+            let _ = tx.send(result);
+        });
+        rx.await.expect("verification jobs always send a result")
+    }
+}
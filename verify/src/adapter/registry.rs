@@ -0,0 +1,568 @@
+//! Verification keys of many circuits and versions, loaded from a directory
+//! and kept prepared for verification.
+//!
+//! The directory holds one subdirectory per circuit and, in it, one per
+//! version, named by its number:
+//!
+//! ```text
+//! keys/
+//!   multiplication/
+//!     1/verification_key.json
+//!     2/vkey_uncompressed.json
+//!     2/RETIRED
+//! ```
+//!
+//! A version holds a snarkjs `verification_key.json`, the
+//! `vkey_uncompressed.json` written by `prove/src/adapter.js`, or both if
+//! they hold the same key. An empty `RETIRED` file retires it: the key stays
+//! listed, but proofs are no longer verified against it.
+//!
+//! Keys are resolved by `KeyId`, the newest active version of a circuit, a
+//! given version, or a fingerprint. `reload` rescans the directory while
+//! verification goes on, and only prepares keys it has not seen before.
+//! `register` adds a key as the next version of a circuit.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use bellman_verifier::bn256::Bn256;
+use bellman_verifier::{BellmanVerifier, PreparedVerifyingKey};
+use bls12_381::Bls12;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use verifier_traits::{Groth16Verifier, VerifierError};
+
+use super::json::{self, Curve, Encoded, JsonError};
+
+/// The file that retires a version.
+pub const RETIRED_MARKER: &str = "RETIRED";
+
+/// The key files of a version, in the order they are read.
+const KEY_FILES: [&str; 2] = ["verification_key.json", "vkey_uncompressed.json"];
+
+/// The SHA-256 of a key in the byte layout of `verifier_traits`, which is the
+/// same whether the key was read from snarkjs' or the adapter's file.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(pub [u8; 32]);
+
+impl Fingerprint {
+    pub fn of(key: &[u8]) -> Self {
+        Fingerprint(Sha256::digest(key).into())
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = RegistryError;
+
+    fn from_str(s: &str) -> Result<Self, RegistryError> {
+        let mut bytes = [0u8; 32];
+        s.strip_prefix("0x")
+            .and_then(|digits| hex::decode_to_slice(digits, &mut bytes).ok())
+            .ok_or_else(|| RegistryError::InvalidKeyId(s.to_string()))?;
+        Ok(Fingerprint(bytes))
+    }
+}
+
+/// Names a key to verify against.
+///
+/// Parsed from `circuit`, `circuit@version` or a `0x` fingerprint.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KeyId {
+    /// The newest version of a circuit that is not retired.
+    Latest(String),
+    Version(String, u32),
+    Fingerprint(Fingerprint),
+}
+
+impl fmt::Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyId::Latest(circuit) => f.write_str(circuit),
+            KeyId::Version(circuit, version) => write!(f, "{}@{}", circuit, version),
+            KeyId::Fingerprint(fingerprint) => fingerprint.fmt(f),
+        }
+    }
+}
+
+impl FromStr for KeyId {
+    type Err = RegistryError;
+
+    fn from_str(s: &str) -> Result<Self, RegistryError> {
+        if s.starts_with("0x") {
+            return s.parse().map(KeyId::Fingerprint);
+        }
+        let invalid = || RegistryError::InvalidKeyId(s.to_string());
+        match s.split_once('@') {
+            Some((circuit, version)) if is_circuit_name(circuit) => Ok(KeyId::Version(
+                circuit.to_string(),
+                version.parse().map_err(|_| invalid())?,
+            )),
+            None if is_circuit_name(s) => Ok(KeyId::Latest(s.to_string())),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Circuit names are directory names that also read back as a `KeyId`.
+fn is_circuit_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.starts_with("0x")
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"_-.".contains(&b))
+}
+
+/// A key prepared for verification, on either curve.
+pub enum PreparedKey {
+    Bn254(PreparedVerifyingKey<Bn256>),
+    Bls12_381(PreparedVerifyingKey<Bls12>),
+}
+
+impl PreparedKey {
+    /// Decodes, checks and prepares a key.
+    pub fn new(key: &Encoded) -> Result<Self, VerifierError> {
+        Ok(match key.curve {
+            Curve::Bn254 => PreparedKey::Bn254(BellmanVerifier::prepare_verifying_key(
+                &BellmanVerifier::<Bn256>::decode_verifying_key(&key.bytes)?,
+            )),
+            Curve::Bls12_381 => PreparedKey::Bls12_381(BellmanVerifier::prepare_verifying_key(
+                &BellmanVerifier::<Bls12>::decode_verifying_key(&key.bytes)?,
+            )),
+        })
+    }
+
+    pub fn curve(&self) -> Curve {
+        match self {
+            PreparedKey::Bn254(_) => Curve::Bn254,
+            PreparedKey::Bls12_381(_) => Curve::Bls12_381,
+        }
+    }
+
+    /// Verifies a proof and public inputs in the byte layout of
+    /// `verifier_traits`.
+    pub fn verify(&self, proof: &[u8], public_inputs: &[u8]) -> Result<(), VerifierError> {
+        match self {
+            PreparedKey::Bn254(pvk) => {
+                BellmanVerifier::<Bn256>::verify_encoded(pvk, proof, public_inputs)
+            }
+            PreparedKey::Bls12_381(pvk) => {
+                BellmanVerifier::<Bls12>::verify_encoded(pvk, proof, public_inputs)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PreparedKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PreparedKey").field(&self.curve()).finish()
+    }
+}
+
+/// A version of a circuit in the registry.
+#[derive(Clone)]
+pub struct KeyEntry {
+    pub circuit: String,
+    pub version: u32,
+    pub curve: Curve,
+    pub fingerprint: Fingerprint,
+    /// The number of public inputs of the circuit.
+    pub public_inputs: usize,
+    pub retired: bool,
+    /// The directory of the version.
+    pub path: PathBuf,
+    /// Shared by all versions with the same fingerprint.
+    pub key: Arc<PreparedKey>,
+}
+
+impl KeyEntry {
+    /// The ID of exactly this version.
+    pub fn id(&self) -> KeyId {
+        KeyId::Version(self.circuit.clone(), self.version)
+    }
+}
+
+/// A human readable description of a verification failure.
+pub fn describe(error: &VerifierError) -> String {
+    match error {
+        VerifierError::InvalidVerifyingKey => "invalid verifying key".into(),
+        VerifierError::PublicInputCount { expected, actual } => {
+            format!("expected {} public inputs, got {}", expected, actual)
+        }
+        VerifierError::InvalidProof => "the proof is not valid".into(),
+        VerifierError::InvalidEncoding => "invalid point or scalar encoding".into(),
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("{}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}: {source}", .path.display())]
+    Json { path: PathBuf, source: JsonError },
+    #[error("{}: {}", .path.display(), describe(.error))]
+    InvalidKey { path: PathBuf, error: VerifierError },
+    #[error("{}: the key files hold different keys", .0.display())]
+    KeyMismatch(PathBuf),
+    #[error("{}: no verification_key.json or vkey_uncompressed.json", .0.display())]
+    MissingKey(PathBuf),
+    #[error("{}: expected a circuit name, or a version number inside a circuit", .0.display())]
+    InvalidPath(PathBuf),
+    #[error("{}: the version was added since the registry was loaded", .0.display())]
+    VersionExists(PathBuf),
+    #[error("invalid key ID `{0}`")]
+    InvalidKeyId(String),
+    #[error("unknown key `{0}`")]
+    UnknownKey(KeyId),
+    #[error("key `{0}` is retired")]
+    Retired(KeyId),
+    #[error("{}", describe(.0))]
+    Verification(VerifierError),
+}
+
+impl From<VerifierError> for RegistryError {
+    fn from(e: VerifierError) -> Self {
+        RegistryError::Verification(e)
+    }
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> RegistryError + '_ {
+    move |source| RegistryError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// The keys of all circuits and versions.
+#[derive(Default)]
+struct Index {
+    versions: BTreeMap<(String, u32), Arc<KeyEntry>>,
+    fingerprints: BTreeMap<Fingerprint, Vec<Arc<KeyEntry>>>,
+}
+
+impl Index {
+    fn insert(&mut self, entry: Arc<KeyEntry>) {
+        self.fingerprints
+            .entry(entry.fingerprint)
+            .or_default()
+            .push(entry.clone());
+        self.versions
+            .insert((entry.circuit.clone(), entry.version), entry);
+    }
+
+    fn prepared(&self, fingerprint: &Fingerprint) -> Option<Arc<PreparedKey>> {
+        self.fingerprints
+            .get(fingerprint)
+            .map(|entries| entries[0].key.clone())
+    }
+
+    fn resolve(&self, id: &KeyId) -> Result<Arc<KeyEntry>, RegistryError> {
+        let unknown = || RegistryError::UnknownKey(id.clone());
+        let entry = match id {
+            KeyId::Latest(circuit) => {
+                let mut versions = self
+                    .versions
+                    .range((circuit.clone(), 0)..=(circuit.clone(), u32::MAX))
+                    .map(|(_, entry)| entry)
+                    .peekable();
+                versions.peek().ok_or_else(unknown)?;
+                versions.rev().find(|entry| !entry.retired)
+            }
+            KeyId::Version(circuit, version) => Some(
+                self.versions
+                    .get(&(circuit.clone(), *version))
+                    .ok_or_else(unknown)?,
+            ),
+            KeyId::Fingerprint(fingerprint) => {
+                let entries = self.fingerprints.get(fingerprint).ok_or_else(unknown)?;
+                Some(entries.iter().find(|e| !e.retired).unwrap_or(&entries[0]))
+            }
+        };
+
+        match entry {
+            Some(entry) if !entry.retired => Ok(entry.clone()),
+            _ => Err(RegistryError::Retired(id.clone())),
+        }
+    }
+}
+
+/// What a reload changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReloadSummary {
+    pub added: usize,
+    /// Versions whose key changed.
+    pub replaced: usize,
+    pub removed: usize,
+    /// Versions retired since the last load.
+    pub retired: usize,
+}
+
+/// Reads the key of a version directory.
+fn read_version(dir: &Path) -> Result<Encoded, RegistryError> {
+    let mut key: Option<Encoded> = None;
+    for name in KEY_FILES {
+        let path = dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let json = fs::read_to_string(&path).map_err(io_error(&path))?;
+        let encoded =
+            json::parse_vkey(&json).map_err(|source| RegistryError::Json { path, source })?;
+        match &key {
+            Some(key) if *key != encoded => return Err(RegistryError::KeyMismatch(dir.into())),
+            _ => key = Some(encoded),
+        }
+    }
+    key.ok_or_else(|| RegistryError::MissingKey(dir.into()))
+}
+
+/// The subdirectories of `dir` with their names, skipping hidden ones.
+fn subdirectories(dir: &Path) -> Result<Vec<(String, PathBuf)>, RegistryError> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error(dir))? {
+        let path = entry.map_err(io_error(dir))?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string);
+        match name {
+            Some(name) if name.starts_with('.') => {}
+            Some(name) if path.is_dir() => dirs.push((name, path)),
+            _ => {}
+        }
+    }
+    Ok(dirs)
+}
+
+/// Scans `root`, reusing the prepared keys of `previous`.
+fn scan(root: &Path, previous: &Index) -> Result<Index, RegistryError> {
+    let mut index = Index::default();
+    for (circuit, circuit_dir) in subdirectories(root)? {
+        if !is_circuit_name(&circuit) {
+            return Err(RegistryError::InvalidPath(circuit_dir));
+        }
+        for (version, dir) in subdirectories(&circuit_dir)? {
+            let version = match version.parse::<u32>() {
+                Ok(version) => version,
+                Err(_) => return Err(RegistryError::InvalidPath(dir)),
+            };
+
+            let encoded = read_version(&dir)?;
+            let entry = new_entry(&circuit, version, dir, &encoded, &[&index, previous])?;
+            index.insert(Arc::new(entry));
+        }
+    }
+    Ok(index)
+}
+
+/// The entry of a version, with the prepared key of `indexes` if one of
+/// them has the same key.
+fn new_entry(
+    circuit: &str,
+    version: u32,
+    dir: PathBuf,
+    encoded: &Encoded,
+    indexes: &[&Index],
+) -> Result<KeyEntry, RegistryError> {
+    let fingerprint = Fingerprint::of(&encoded.bytes);
+    let key = match indexes
+        .iter()
+        .find_map(|index| index.prepared(&fingerprint))
+    {
+        Some(key) => key,
+        None => Arc::new(
+            PreparedKey::new(encoded).map_err(|error| RegistryError::InvalidKey {
+                path: dir.clone(),
+                error,
+            })?,
+        ),
+    };
+    let curve = encoded.curve;
+    let fixed = curve.g1_size() + 3 * curve.g2_size();
+
+    Ok(KeyEntry {
+        circuit: circuit.to_string(),
+        version,
+        curve,
+        fingerprint,
+        public_inputs: (encoded.bytes.len() - fixed) / curve.g1_size() - 1,
+        retired: dir.join(RETIRED_MARKER).exists(),
+        path: dir,
+        key,
+    })
+}
+
+/// Verification keys loaded from a directory, see the module documentation.
+pub struct VkeyRegistry {
+    root: PathBuf,
+    index: RwLock<Arc<Index>>,
+    /// Held while the directory changes, so that one change does not undo
+    /// another.
+    changes: Mutex<()>,
+}
+
+impl VkeyRegistry {
+    /// Loads all keys under `root`. Any invalid key fails the whole load.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, RegistryError> {
+        let root = root.into();
+        let index = scan(&root, &Index::default())?;
+        Ok(VkeyRegistry {
+            root,
+            index: RwLock::new(Arc::new(index)),
+            changes: Mutex::new(()),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn index(&self) -> Arc<Index> {
+        self.index.read().unwrap().clone()
+    }
+
+    /// Rescans the directory. If any key is invalid, the error is returned
+    /// and the keys loaded before are kept.
+    pub fn reload(&self) -> Result<ReloadSummary, RegistryError> {
+        let _changes = self.changes.lock().unwrap();
+        let previous = self.index();
+        let index = Arc::new(scan(&self.root, &previous)?);
+
+        let mut summary = ReloadSummary::default();
+        for (version, entry) in &index.versions {
+            match previous.versions.get(version) {
+                None => summary.added += 1,
+                Some(old) if old.fingerprint != entry.fingerprint => summary.replaced += 1,
+                Some(old) if entry.retired && !old.retired => summary.retired += 1,
+                Some(_) => {}
+            }
+        }
+        summary.removed = previous
+            .versions
+            .keys()
+            .filter(|version| !index.versions.contains_key(*version))
+            .count();
+
+        *self.index.write().unwrap() = index;
+        Ok(summary)
+    }
+
+    /// The entry of a key that is not retired.
+    pub fn resolve(&self, id: &KeyId) -> Result<Arc<KeyEntry>, RegistryError> {
+        self.index().resolve(id)
+    }
+
+    /// All versions of all circuits, retired ones included, ordered by
+    /// circuit and version.
+    pub fn entries(&self) -> Vec<Arc<KeyEntry>> {
+        self.index().versions.values().cloned().collect()
+    }
+
+    /// Retires a version by creating its `RETIRED` file, so that it stays
+    /// retired across reloads and restarts.
+    pub fn retire(&self, circuit: &str, version: u32) -> Result<(), RegistryError> {
+        let _changes = self.changes.lock().unwrap();
+        let id = KeyId::Version(circuit.to_string(), version);
+        let entry = self
+            .index()
+            .versions
+            .get(&(circuit.to_string(), version))
+            .cloned()
+            .ok_or(RegistryError::UnknownKey(id))?;
+        let marker = entry.path.join(RETIRED_MARKER);
+        fs::write(&marker, b"").map_err(io_error(&marker))?;
+
+        let mut index = self.index.write().unwrap();
+        let mut updated = Index::default();
+        for entry in index.versions.values() {
+            let mut entry = KeyEntry::clone(entry);
+            entry.retired |= entry.circuit == circuit && entry.version == version;
+            updated.insert(Arc::new(entry));
+        }
+        *index = Arc::new(updated);
+        Ok(())
+    }
+
+    /// Adds a snarkjs `verification_key.json` or a `vkey_uncompressed.json`
+    /// as the next version of `circuit`, creating the circuit if needed. The
+    /// version directory appears complete or not at all.
+    ///
+    /// The next version follows the newest one loaded, retired or not. If
+    /// its directory was created since the last load, this fails with
+    /// `RegistryError::VersionExists` rather than guess a number; reload
+    /// and retry.
+    pub fn register(&self, circuit: &str, key_json: &str) -> Result<Arc<KeyEntry>, RegistryError> {
+        let _changes = self.changes.lock().unwrap();
+        if !is_circuit_name(circuit) {
+            return Err(RegistryError::InvalidKeyId(circuit.to_string()));
+        }
+        let index = self.index();
+        let version = index
+            .versions
+            .range((circuit.to_string(), 0)..=(circuit.to_string(), u32::MAX))
+            .next_back()
+            .map_or(1, |((_, version), _)| version + 1);
+        let circuit_dir = self.root.join(circuit);
+        let dir = circuit_dir.join(version.to_string());
+        // Written since the last reload, or the registry would know it.
+        if dir.exists() {
+            return Err(RegistryError::VersionExists(dir));
+        }
+
+        let is_snarkjs = serde_json::from_str::<serde_json::Value>(key_json)
+            .map(|v| v.get("vk_alpha_1").is_some())
+            .unwrap_or(false);
+        let file = dir.join(KEY_FILES[if is_snarkjs { 0 } else { 1 }]);
+        let encoded = json::parse_vkey(key_json).map_err(|source| RegistryError::Json {
+            path: file.clone(),
+            source,
+        })?;
+        let entry = new_entry(circuit, version, dir.clone(), &encoded, &[&index])?;
+
+        // Hidden directories are not scanned, so a reload never sees the
+        // version half written.
+        let staging = circuit_dir.join(format!(".{}.new", version));
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging).map_err(io_error(&staging))?;
+        let staged = staging.join(file.file_name().unwrap());
+        fs::write(&staged, key_json).map_err(io_error(&staged))?;
+        fs::rename(&staging, &dir).map_err(io_error(&dir))?;
+
+        let entry = Arc::new(entry);
+        let mut index = self.index.write().unwrap();
+        let mut updated = Index::default();
+        for entry in index.versions.values().chain([&entry]) {
+            updated.insert(entry.clone());
+        }
+        *index = Arc::new(updated);
+        Ok(entry)
+    }
+
+    /// Verifies a proof and public inputs in the byte layout of
+    /// `verifier_traits` against a key that is not retired.
+    pub fn verify(
+        &self,
+        id: &KeyId,
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<(), RegistryError> {
+        Ok(self.resolve(id)?.key.verify(proof, public_inputs)?)
+    }
+}
@@ -0,0 +1,191 @@
+//! Rejecting proofs that were already accepted.
+//!
+//! A valid proof stays valid, so applications that act on a proof once must
+//! remember what they accepted. `ReplayProtection` wraps
+//! `VkeyRegistry::verify` and records every accepted submission in a
+//! `ReplayGuard`, either by proof hash or by a public input that the circuit
+//! designates as nullifier.
+//!
+//! Groth16 proofs are malleable: anyone holding a proof can derive other
+//! valid proofs for the same public inputs. The proof hash therefore only
+//! stops verbatim resubmissions, while a nullifier input stops every proof
+//! of the same statement and should be preferred where the circuit has one.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use verifier_traits::PUBLIC_INPUT_SIZE;
+
+use super::registry::{KeyId, RegistryError, VkeyRegistry};
+
+/// What makes two submissions the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayKey {
+    /// The proof bytes.
+    ProofHash,
+    /// The public input at this index, e.g. a nullifier.
+    PublicInput(usize),
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("the submission was already accepted")]
+    Replayed,
+    #[error("no public input {index} to use as replay key")]
+    MissingInput { index: usize },
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+    #[error("replay store: {0}")]
+    Store(String),
+}
+
+/// A set of accepted submissions, each identified by a 32 byte tag.
+pub trait ReplayGuard: Send + Sync {
+    /// Whether `tag` was recorded.
+    fn contains(&self, tag: &[u8; 32]) -> Result<bool, ReplayError>;
+
+    /// Records `tag`, returning `false` if it was already recorded. Of
+    /// concurrent calls with the same tag, only one returns `true`.
+    fn insert(&self, tag: &[u8; 32]) -> Result<bool, ReplayError>;
+}
+
+/// A `ReplayGuard` that forgets everything on restart.
+#[derive(Default)]
+pub struct MemoryGuard {
+    tags: Mutex<HashSet<[u8; 32]>>,
+}
+
+impl MemoryGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReplayGuard for MemoryGuard {
+    fn contains(&self, tag: &[u8; 32]) -> Result<bool, ReplayError> {
+        Ok(self.tags.lock().unwrap().contains(tag))
+    }
+
+    fn insert(&self, tag: &[u8; 32]) -> Result<bool, ReplayError> {
+        Ok(self.tags.lock().unwrap().insert(*tag))
+    }
+}
+
+/// A `ReplayGuard` kept in a sled tree, which survives restarts.
+#[cfg(feature = "sled")]
+pub struct SledGuard {
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledGuard {
+    /// Opens or creates a database at `path`.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, ReplayError> {
+        let db = sled::open(path).map_err(|e| ReplayError::Store(e.to_string()))?;
+        Ok(Self::new(
+            db.open_tree("replay")
+                .map_err(|e| ReplayError::Store(e.to_string()))?,
+        ))
+    }
+
+    /// Uses a tree of a database the application already has open.
+    pub fn new(tree: sled::Tree) -> Self {
+        SledGuard { tree }
+    }
+}
+
+#[cfg(feature = "sled")]
+impl ReplayGuard for SledGuard {
+    fn contains(&self, tag: &[u8; 32]) -> Result<bool, ReplayError> {
+        self.tree
+            .contains_key(tag)
+            .map_err(|e| ReplayError::Store(e.to_string()))
+    }
+
+    fn insert(&self, tag: &[u8; 32]) -> Result<bool, ReplayError> {
+        let swapped = self
+            .tree
+            .compare_and_swap(tag, None as Option<&[u8]>, Some(&[][..]))
+            .map_err(|e| ReplayError::Store(e.to_string()))?;
+        // Accepted submissions must not be lost in a crash.
+        self.tree
+            .flush()
+            .map_err(|e| ReplayError::Store(e.to_string()))?;
+        Ok(swapped.is_ok())
+    }
+}
+
+/// Verification through a `VkeyRegistry` that accepts each submission once.
+pub struct ReplayProtection<G> {
+    guard: G,
+    key: ReplayKey,
+}
+
+impl<G: ReplayGuard> ReplayProtection<G> {
+    pub fn new(guard: G, key: ReplayKey) -> Self {
+        ReplayProtection { guard, key }
+    }
+
+    pub fn guard(&self) -> &G {
+        &self.guard
+    }
+
+    /// The tag of a submission. It covers the circuit but not the version,
+    /// so a nullifier spent under one version of a key stays spent after
+    /// rotation.
+    pub fn tag(
+        &self,
+        circuit: &str,
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<[u8; 32], ReplayError> {
+        let mut hasher = Sha256::new();
+        hasher.update((circuit.len() as u64).to_le_bytes());
+        hasher.update(circuit);
+        match self.key {
+            ReplayKey::ProofHash => {
+                hasher.update([0]);
+                hasher.update(proof);
+            }
+            ReplayKey::PublicInput(index) => {
+                let input = public_inputs
+                    .chunks(PUBLIC_INPUT_SIZE)
+                    .nth(index)
+                    .filter(|input| input.len() == PUBLIC_INPUT_SIZE)
+                    .ok_or(ReplayError::MissingInput { index })?;
+                hasher.update([1]);
+                hasher.update(input);
+            }
+        }
+        Ok(hasher.finalize().into())
+    }
+
+    /// Verifies like `VkeyRegistry::verify` and records the submission.
+    /// Fails with `ReplayError::Replayed` if it was accepted before. Proofs
+    /// that fail verification are not recorded.
+    pub fn verify(
+        &self,
+        registry: &VkeyRegistry,
+        id: &KeyId,
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<(), ReplayError> {
+        let entry = registry.resolve(id)?;
+        let tag = self.tag(&entry.circuit, proof, public_inputs)?;
+        // Saves the pairings for plain resubmissions.
+        if self.guard.contains(&tag)? {
+            return Err(ReplayError::Replayed);
+        }
+
+        entry
+            .key
+            .verify(proof, public_inputs)
+            .map_err(RegistryError::from)?;
+        if !self.guard.insert(&tag)? {
+            return Err(ReplayError::Replayed);
+        }
+        Ok(())
+    }
+}
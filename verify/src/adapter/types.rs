@@ -15,3 +15,12 @@ pub struct VkeyStr {
     pub delta_2: Vec<u8>,
     pub ic: Vec<Vec<u8>>,
 }
+
+// The fields of snarkjs' `verification_key.json` that are read directly,
+// without going through the uncompressed files written by `adapter.js`.
+#[derive(Serialize, Deserialize)]
+pub struct SnarkjsVkeyStr {
+    // e(alpha_1, beta_2) as [c0, c1] of Fq6 elements, each [c0, c1, c2] of
+    // Fq2 elements, each [c0, c1] decimal strings.
+    pub vk_alphabeta_12: Vec<Vec<Vec<String>>>,
+}
@@ -0,0 +1,72 @@
+//! Serves `bellman_verify::server` for the keys of a registry directory.
+
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use bellman_verify::adapter::{VerificationPool, VkeyRegistry};
+use clap::Parser;
+
+/// Verifies snarkjs proofs over HTTP.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// The registry directory, with one directory per circuit and version.
+    #[arg(long)]
+    keys: PathBuf,
+    /// The address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    /// Verification threads, 0 for one per core.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+    /// Verifications queued or running before requests wait.
+    #[arg(long, default_value = "256")]
+    max_in_flight: NonZeroUsize,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let registry = match VkeyRegistry::open(&args.keys) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let pool = match VerificationPool::new(Arc::new(registry), args.threads, args.max_in_flight) {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match tokio::net::TcpListener::bind(args.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: {}: {}", args.listen, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    eprintln!(
+        "serving {} keys from {} on {}",
+        pool.registry().entries().len(),
+        args.keys.display(),
+        args.listen
+    );
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    match bellman_verify::server::serve(listener, Arc::new(pool), shutdown).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! `bellman-verify convert`: writes a proof, a verification key or public
+//! inputs in another format, with `adapter::encoding`.
+//!
+//! Exits with 0 on success and 2 if the input cannot be read or converted.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bellman_verify::adapter::encoding::{self, EncodingError, Format};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::ValueEnum;
+
+use super::{read, CurveArg};
+
+const ERROR: u8 = 2;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Kind {
+    Proof,
+    Vkey,
+    Public,
+}
+
+fn format_parser() -> impl TypedValueParser<Value = Format> {
+    PossibleValuesParser::new(Format::ALL.map(Format::name))
+        .map(|format| format.parse::<Format>().unwrap())
+}
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// What the input holds.
+    #[arg(value_enum)]
+    kind: Kind,
+    /// The input file, standard input if omitted or `-`.
+    input: Option<PathBuf>,
+    /// The format of the input.
+    #[arg(long, value_parser = format_parser())]
+    from: Format,
+    /// The format of the output.
+    #[arg(long, value_parser = format_parser())]
+    to: Format,
+    /// The curve of a proof or key, needed where the input format has no
+    /// curve name and both curves fit.
+    #[arg(long, value_enum, default_value_t = CurveArg::Auto)]
+    curve: CurveArg,
+    /// The output file, standard output if omitted.
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+}
+
+fn convert(args: &Args, input: &str) -> Result<String, EncodingError> {
+    let curve = args.curve.curve();
+    match args.kind {
+        Kind::Proof => {
+            encoding::encode_proof(args.to, &encoding::decode_proof(args.from, input, curve)?)
+        }
+        Kind::Vkey => {
+            encoding::encode_vkey(args.to, &encoding::decode_vkey(args.from, input, curve)?)
+        }
+        Kind::Public => encoding::encode_public_inputs(
+            args.to,
+            &encoding::decode_public_inputs(args.from, input)?,
+        ),
+    }
+}
+
+fn run_inner(args: &Args) -> Result<(), String> {
+    let (name, input) = match &args.input {
+        Some(path) if path.as_os_str() != "-" => (path.display().to_string(), read(path)?),
+        _ => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("standard input: {}", e))?;
+            ("standard input".to_string(), input)
+        }
+    };
+    let output = convert(args, &input).map_err(|e| format!("{}: {}", name, e))?;
+
+    match &args.out {
+        Some(path) => {
+            fs::write(path, output + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+        }
+        None => {
+            println!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+pub fn run(args: &Args) -> ExitCode {
+    match run_inner(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(ERROR)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! The subcommands of the `bellman-verify` binary.
+
+use std::fs;
+use std::path::Path;
+
+use bellman_verify::adapter::json::Curve;
+use clap::{Parser, Subcommand, ValueEnum};
+
+pub mod convert;
+pub mod verify;
+
+/// Tools for the Groth16 proofs and keys of snarkjs.
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Proves and verifies a MiMC preimage with bellman, the default.
+    Demo,
+    /// Verifies a proof against a verification key.
+    Verify(verify::Args),
+    /// Converts a proof, a verification key or public inputs to another
+    /// format.
+    Convert(convert::Args),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CurveArg {
+    Auto,
+    Bn254,
+    #[value(name = "bls12-381")]
+    Bls12_381,
+}
+
+impl CurveArg {
+    /// The curve, unless it is to be found from the input.
+    pub fn curve(self) -> Option<Curve> {
+        match self {
+            CurveArg::Auto => None,
+            CurveArg::Bn254 => Some(Curve::Bn254),
+            CurveArg::Bls12_381 => Some(Curve::Bls12_381),
+        }
+    }
+}
+
+/// How results are printed.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    Human,
+    Json,
+}
+
+/// Reads a file, with its path in the error.
+pub fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,102 @@
+//! `bellman-verify verify`: checks a proof against a key file, with the
+//! adapter's readers and the verifier of `bellman-verifier`.
+//!
+//! Exits with 0 if the proof is valid, 1 if it is not, and 2 if the files
+//! cannot be read, like for usage errors.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bellman_verify::adapter::json::{self, Curve};
+use bellman_verify::adapter::registry::{describe, PreparedKey};
+use serde_json::json;
+use verifier_traits::PUBLIC_INPUT_SIZE;
+
+use super::{read, CurveArg, Output};
+
+const INVALID: u8 = 1;
+const ERROR: u8 = 2;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// A snarkjs `verification_key.json` or a `vkey_uncompressed.json`.
+    #[arg(long)]
+    vkey: PathBuf,
+    /// A snarkjs `proof.json` or a `proof_uncompressed.json`.
+    #[arg(long)]
+    proof: PathBuf,
+    /// A snarkjs `public.json`.
+    #[arg(long)]
+    public: PathBuf,
+    /// The curve of the key and the proof, `auto` takes that of the key.
+    #[arg(long, value_enum, default_value_t = CurveArg::Auto)]
+    curve: CurveArg,
+    /// How to print the result.
+    #[arg(long, value_enum, default_value_t = Output::Human)]
+    output: Output,
+}
+
+/// What was verified, and whether the proof is valid.
+struct Outcome {
+    curve: Curve,
+    public_inputs: usize,
+    result: Result<(), String>,
+}
+
+fn check(args: &Args) -> Result<Outcome, String> {
+    let vkey = json::parse_vkey(&read(&args.vkey)?)
+        .map_err(|e| format!("{}: {}", args.vkey.display(), e))?;
+    let proof = json::parse_proof(&read(&args.proof)?)
+        .map_err(|e| format!("{}: {}", args.proof.display(), e))?;
+    let public_inputs = json::parse_public_inputs(&read(&args.public)?)
+        .map_err(|e| format!("{}: {}", args.public.display(), e))?;
+
+    let curve = args.curve.curve().unwrap_or(vkey.curve);
+    for (path, found) in [(&args.vkey, vkey.curve), (&args.proof, proof.curve)] {
+        if found != curve {
+            return Err(format!("{}: on {}, not {}", path.display(), found, curve));
+        }
+    }
+
+    let key = PreparedKey::new(&vkey)
+        .map_err(|e| format!("{}: {}", args.vkey.display(), describe(&e)))?;
+    Ok(Outcome {
+        curve,
+        public_inputs: public_inputs.len() / PUBLIC_INPUT_SIZE,
+        result: key
+            .verify(&proof.bytes, &public_inputs)
+            .map_err(|e| describe(&e)),
+    })
+}
+
+pub fn run(args: &Args) -> ExitCode {
+    let outcome = check(args);
+    match (args.output, &outcome) {
+        (Output::Human, Ok(outcome)) => match &outcome.result {
+            Ok(()) => println!(
+                "valid proof on {} with {} public inputs",
+                outcome.curve, outcome.public_inputs
+            ),
+            Err(e) => println!("invalid proof: {}", e),
+        },
+        (Output::Human, Err(e)) => eprintln!("error: {}", e),
+        (Output::Json, Ok(outcome)) => {
+            let mut report = json!({
+                "valid": outcome.result.is_ok(),
+                "curve": outcome.curve.to_string(),
+                "public_inputs": outcome.public_inputs,
+            });
+            if let Err(e) = &outcome.result {
+                report["error"] = e.as_str().into();
+            }
+            println!("{}", report);
+        }
+        (Output::Json, Err(e)) => println!("{}", json!({ "valid": false, "error": e })),
+    }
+
+    match outcome {
+        Ok(Outcome { result: Ok(()), .. }) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::from(INVALID),
+        Err(_) => ExitCode::from(ERROR),
+    }
+}
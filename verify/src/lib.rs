@@ -0,0 +1,6 @@
+//! The Rust side of the adapter: reading snarkjs output and verifying it
+//! with the verifier crates of this repository.
+
+pub mod adapter;
+#[cfg(feature = "server")]
+pub mod server;
@@ -10,13 +10,29 @@ use bls12_381::{Bls12, Scalar};
 // We're going to use the Groth16 proving system.
 use bellman::groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
 
+use std::process::ExitCode;
+
+use clap::Parser;
+
 mod circuit;
 use circuit::*;
 
-mod adapter;
-// use adapter::snark_proof_bellman_verify;
+mod cli;
+use cli::{Cli, Command};
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Some(Command::Verify(args)) => cli::verify::run(&args),
+        Some(Command::Convert(args)) => cli::convert::run(&args),
+        Some(Command::Demo) | None => {
+            demo();
+            ExitCode::SUCCESS
+        }
+    }
+}
 
-fn main() {
+/// Proves and verifies a MiMC preimage with bellman.
+fn demo() {
     // step1
     let mut rng = thread_rng();
     // Generate the MiMC round constants
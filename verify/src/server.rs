@@ -0,0 +1,243 @@
+//! An HTTP service that verifies snarkjs proofs against the keys of a
+//! `VkeyRegistry`, for callers that are not written in Rust. It is meant to
+//! run next to them as a sidecar and has no authentication.
+//!
+//! - `POST /verify` takes `{"key": ..., "proof": ..., "public": ...}`, a key
+//!   ID and the contents of `proof.json` and `public.json`, and answers
+//!   `{"valid": ..., "key": ..., "fingerprint": ...}` with the version that
+//!   was used and, for invalid proofs, an `error`. A proof on another curve
+//!   than the key is a bad request.
+//! - `GET /keys` lists all versions, `POST /keys` takes
+//!   `{"circuit": ..., "key": ...}` with the contents of a
+//!   `verification_key.json` and adds it as the next version.
+//! - `GET /health` and `GET /metrics`, the latter in Prometheus' text
+//!   format.
+//!
+//! Other failures are answered with a 4xx or 5xx status and `{"error": ...}`.
+
+use std::fmt::Write;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use crate::adapter::json::{self, JsonError};
+use crate::adapter::pool::{Job, VerificationPool};
+use crate::adapter::registry::{describe, KeyEntry, KeyId, RegistryError};
+
+#[derive(Default)]
+struct Metrics {
+    valid: AtomicU64,
+    invalid: AtomicU64,
+    failed: AtomicU64,
+    registered: AtomicU64,
+}
+
+struct Service {
+    pool: Arc<VerificationPool>,
+    metrics: Metrics,
+}
+
+/// A response with a status and `{"error": ...}`.
+struct Error(StatusCode, String);
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<JsonError> for Error {
+    fn from(e: JsonError) -> Self {
+        Error(StatusCode::BAD_REQUEST, e.to_string())
+    }
+}
+
+impl From<RegistryError> for Error {
+    fn from(e: RegistryError) -> Self {
+        let status = match e {
+            RegistryError::Json { .. } | RegistryError::InvalidKeyId(_) => StatusCode::BAD_REQUEST,
+            RegistryError::InvalidKey { .. } | RegistryError::Verification(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            RegistryError::UnknownKey(_) => StatusCode::NOT_FOUND,
+            RegistryError::Retired(_) => StatusCode::GONE,
+            RegistryError::VersionExists(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Error(status, e.to_string())
+    }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, Error> {
+    serde_json::from_slice(body).map_err(|e| Error::from(JsonError::from(e)))
+}
+
+fn entry_json(entry: &KeyEntry) -> Value {
+    json!({
+        "id": entry.id().to_string(),
+        "circuit": entry.circuit,
+        "version": entry.version,
+        "curve": entry.curve.to_string(),
+        "fingerprint": entry.fingerprint.to_string(),
+        "public_inputs": entry.public_inputs,
+        "retired": entry.retired,
+    })
+}
+
+#[derive(Deserialize)]
+struct VerifyRequest {
+    key: String,
+    proof: Value,
+    public: Value,
+}
+
+async fn verify(State(service): State<Arc<Service>>, body: Bytes) -> Result<Json<Value>, Error> {
+    let result = verify_request(&service, &body).await;
+    let counter = match &result {
+        Ok(response) if response["valid"] == true => &service.metrics.valid,
+        Ok(_) => &service.metrics.invalid,
+        Err(_) => &service.metrics.failed,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    result.map(Json)
+}
+
+async fn verify_request(service: &Service, body: &[u8]) -> Result<Value, Error> {
+    let request: VerifyRequest = parse_body(body)?;
+    let id: KeyId = request.key.parse()?;
+    let proof = json::parse_proof(&request.proof.to_string())?;
+    let public_inputs = json::parse_public_inputs(&request.public.to_string())?;
+
+    // Pins the version, so that the answer names the key that was used
+    // even if the keys change meanwhile.
+    let entry = service.pool.registry().resolve(&id)?;
+    if proof.curve != entry.curve {
+        return Err(Error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "the proof is on {}, the key on {}",
+                proof.curve, entry.curve
+            ),
+        ));
+    }
+    let job = Job {
+        key: entry.key.clone(),
+        proof: proof.bytes,
+        public_inputs,
+    };
+    let result = service.pool.verify(job).await;
+
+    let mut response = json!({
+        "key": entry.id().to_string(),
+        "fingerprint": entry.fingerprint.to_string(),
+    });
+    response["valid"] = result.is_ok().into();
+    if let Err(error) = result {
+        response["error"] = describe(&error).into();
+    }
+    Ok(response)
+}
+
+async fn keys(State(service): State<Arc<Service>>) -> Json<Value> {
+    let entries = service.pool.registry().entries();
+    Json(entries.iter().map(|e| entry_json(e)).collect())
+}
+
+#[derive(Deserialize)]
+struct RegisterRequest {
+    circuit: String,
+    key: Value,
+}
+
+async fn register(
+    State(service): State<Arc<Service>>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Value>), Error> {
+    let request: RegisterRequest = parse_body(&body)?;
+    let registry = service.pool.registry().clone();
+    // Preparing a key takes a few pairings, writing it a few syscalls.
+    let entry = tokio::task::spawn_blocking(move || {
+        registry.register(&request.circuit, &request.key.to_string())
+    })
+    .await
+    .map_err(|e| Error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+
+    service.metrics.registered.fetch_add(1, Ordering::Relaxed);
+    Ok((StatusCode::CREATED, Json(entry_json(&entry))))
+}
+
+async fn health(State(service): State<Arc<Service>>) -> Json<Value> {
+    Json(json!({
+        "status": "ok",
+        "keys": service.pool.registry().entries().len(),
+    }))
+}
+
+async fn metrics(State(service): State<Arc<Service>>) -> impl IntoResponse {
+    let metrics = &service.metrics;
+    let entries = service.pool.registry().entries();
+    let retired = entries.iter().filter(|e| e.retired).count();
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "# HELP groth16_verifications_total Verification requests by outcome.\n\
+         # TYPE groth16_verifications_total counter\n\
+         groth16_verifications_total{{result=\"valid\"}} {}\n\
+         groth16_verifications_total{{result=\"invalid\"}} {}\n\
+         groth16_verifications_total{{result=\"error\"}} {}\n\
+         # HELP groth16_keys_registered_total Keys added through POST /keys.\n\
+         # TYPE groth16_keys_registered_total counter\n\
+         groth16_keys_registered_total {}\n\
+         # HELP groth16_keys Key versions in the registry.\n\
+         # TYPE groth16_keys gauge\n\
+         groth16_keys{{state=\"active\"}} {}\n\
+         groth16_keys{{state=\"retired\"}} {}\n\
+         # HELP groth16_jobs_in_flight Verifications queued or running.\n\
+         # TYPE groth16_jobs_in_flight gauge\n\
+         groth16_jobs_in_flight {}\n",
+        metrics.valid.load(Ordering::Relaxed),
+        metrics.invalid.load(Ordering::Relaxed),
+        metrics.failed.load(Ordering::Relaxed),
+        metrics.registered.load(Ordering::Relaxed),
+        entries.len() - retired,
+        retired,
+        service.pool.in_flight(),
+    );
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+/// The routes of the service, verifying with `pool`.
+pub fn router(pool: Arc<VerificationPool>) -> Router {
+    let service = Arc::new(Service {
+        pool,
+        metrics: Metrics::default(),
+    });
+    Router::new()
+        .route("/verify", post(verify))
+        .route("/keys", get(keys).post(register))
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
+        .with_state(service)
+}
+
+/// Serves the routes on `listener` until `shutdown` completes.
+pub async fn serve(
+    listener: TcpListener,
+    pool: Arc<VerificationPool>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    axum::serve(listener, router(pool))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
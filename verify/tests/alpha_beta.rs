@@ -0,0 +1,64 @@
+use bellman_ce_verifier::{
+    prepare_verifying_key_with_alpha_beta, BellmanCeVerifier, PreparationConfig,
+};
+use bellman_verify::adapter::json::{self, JsonError};
+use bellman_verify::adapter::parse_bn_alphabeta;
+use pairing_ce::bn256::Bn256;
+use pairing_ce::ff::Field;
+use serde_json::Value;
+use verifier_traits::{Groth16Verifier, VerifierError};
+
+mod common;
+
+use common::read_fixture;
+
+type Verifier = BellmanCeVerifier<Bn256>;
+
+#[test]
+fn snarkjs_alpha_beta_matches_the_key() {
+    let vkey_json = read_fixture("bn128/verification_key.json");
+    let vk = Verifier::decode_verifying_key(&json::parse_vkey(&vkey_json).unwrap().bytes).unwrap();
+    let proof = json::parse_proof(&read_fixture("bn128/proof.json")).unwrap();
+    let inputs = json::parse_public_inputs(&read_fixture("bn128/public.json")).unwrap();
+    let config = PreparationConfig::default();
+
+    let alpha_beta = parse_bn_alphabeta(&vkey_json).unwrap();
+    let pvk = prepare_verifying_key_with_alpha_beta(&vk, alpha_beta, &config, true).unwrap();
+    assert_eq!(
+        Verifier::verify_encoded(&pvk, &proof.bytes, &inputs),
+        Ok(())
+    );
+
+    let mut wrong = alpha_beta;
+    wrong.negate();
+    assert_eq!(
+        prepare_verifying_key_with_alpha_beta(&vk, wrong, &config, true).err(),
+        Some(VerifierError::InvalidVerifyingKey)
+    );
+}
+
+#[test]
+fn rejects_malformed_alpha_beta() {
+    let vkey: Value = serde_json::from_str(&read_fixture("bn128/verification_key.json")).unwrap();
+
+    let mut v = vkey.clone();
+    v["vk_alphabeta_12"][0][0][0] = "x".into();
+    assert!(matches!(
+        parse_bn_alphabeta(&v.to_string()),
+        Err(JsonError::InvalidField { .. })
+    ));
+
+    let mut v = vkey.clone();
+    v["vk_alphabeta_12"][1].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        parse_bn_alphabeta(&v.to_string()),
+        Err(JsonError::InvalidField { .. })
+    ));
+
+    let mut v = vkey;
+    v.as_object_mut().unwrap().remove("vk_alphabeta_12");
+    assert!(matches!(
+        parse_bn_alphabeta(&v.to_string()),
+        Err(JsonError::Json(_))
+    ));
+}
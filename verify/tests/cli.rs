@@ -0,0 +1,158 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+
+use common::fixture;
+
+fn bellman_verify(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bellman-verify"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn verify(vkey: &str, proof: &str, public: &str, extra: &[&str]) -> Output {
+    let files = [fixture(vkey), fixture(proof), fixture(public)];
+    let mut args = vec!["verify"];
+    for (flag, file) in ["--vkey", "--proof", "--public"].iter().zip(&files) {
+        args.push(flag);
+        args.push(file.to_str().unwrap());
+    }
+    args.extend(extra);
+    bellman_verify(&args)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn verifies_snarkjs_and_adapter_files() {
+    let output = verify(
+        "bn128/verification_key.json",
+        "bn128/proof.json",
+        "bn128/public.json",
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "valid proof on bn254 with 1 public inputs\n"
+    );
+
+    let output = verify(
+        "bls12381/vkey_uncompressed.json",
+        "bls12381/proof_uncompressed.json",
+        "bls12381/public.json",
+        &["--curve", "bls12-381", "--output", "json"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report,
+        serde_json::json!({ "valid": true, "curve": "bls12-381", "public_inputs": 1 })
+    );
+}
+
+#[test]
+fn fails_on_invalid_proofs_and_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let public = dir.path().join("public.json");
+    std::fs::write(&public, r#"["34"]"#).unwrap();
+    let output = verify(
+        "bls12381/verification_key.json",
+        "bls12381/proof.json",
+        public.to_str().unwrap(),
+        &["--output", "json"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["error"], "the proof is not valid");
+
+    let output = verify(
+        "bls12381/verification_key.json",
+        "bls12381/proof.json",
+        "bls12381/public.json",
+        &["--curve", "bn254"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("on bls12-381, not bn254"));
+
+    let output = verify(
+        "bls12381/verification_key.json",
+        "bn128/proof.json",
+        "bls12381/public.json",
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = verify(
+        "bls12381/missing.json",
+        "bls12381/proof.json",
+        "bls12381/public.json",
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn converts_between_formats() {
+    let dir = tempfile::tempdir().unwrap();
+    let compressed = dir.path().join("proof_compressed");
+    let proof = fixture("bls12381/proof.json");
+    let output = bellman_verify(&[
+        "convert",
+        "proof",
+        proof.to_str().unwrap(),
+        "--from",
+        "snarkjs",
+        "--to",
+        "compressed",
+        "--out",
+        compressed.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    // Back from standard input.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bellman-verify"))
+        .args([
+            "convert",
+            "proof",
+            "--from",
+            "compressed",
+            "--to",
+            "snarkjs",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&std::fs::read(&compressed).unwrap())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let written: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let original: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&proof).unwrap()).unwrap();
+    assert_eq!(written, original);
+
+    let output = bellman_verify(&[
+        "convert",
+        "proof",
+        proof.to_str().unwrap(),
+        "--from",
+        "snarkjs",
+        "--to",
+        "evm",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("the evm format has no bls12-381 proofs")
+    );
+}
@@ -0,0 +1,54 @@
+//! Helpers shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bellman_verify::adapter::json;
+use bellman_verify::adapter::registry::{KeyId, VkeyRegistry};
+
+/// The path of a file in the repository's `fixtures` directory.
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../fixtures")
+        .join(name)
+}
+
+pub fn read_fixture(name: &str) -> String {
+    fs::read_to_string(fixture(name)).unwrap()
+}
+
+/// Copies the fixture `file` into `root/version`.
+pub fn add_key(root: &Path, version: &str, file: &str) {
+    let dir = root.join(version);
+    fs::create_dir_all(&dir).unwrap();
+    let name = Path::new(file).file_name().unwrap();
+    fs::copy(fixture(file), dir.join(name)).unwrap();
+}
+
+/// A registry of `(circuit/version, fixture key file)` pairs in a temporary
+/// directory.
+pub fn registry(versions: &[(&str, &str)]) -> (tempfile::TempDir, VkeyRegistry) {
+    let dir = tempfile::tempdir().unwrap();
+    for (version, file) in versions {
+        add_key(dir.path(), version, file);
+    }
+    let registry = VkeyRegistry::open(dir.path()).unwrap();
+    (dir, registry)
+}
+
+/// The fixture proof and public inputs of `curve`, in the byte layout of
+/// `verifier_traits`.
+pub fn submission(curve: &str) -> (Vec<u8>, Vec<u8>) {
+    let proof = read_fixture(&format!("{}/proof.json", curve));
+    let public = read_fixture(&format!("{}/public.json", curve));
+    (
+        json::parse_proof(&proof).unwrap().bytes,
+        json::parse_public_inputs(&public).unwrap(),
+    )
+}
+
+pub fn id(s: &str) -> KeyId {
+    s.parse().unwrap()
+}
@@ -0,0 +1,186 @@
+use bellman_verify::adapter::encoding::{self, EncodingError, Format};
+use bellman_verify::adapter::json::Curve;
+use serde_json::Value;
+use verifier_traits::VerifierError;
+
+mod common;
+
+use common::read_fixture;
+
+fn json(s: &str) -> Value {
+    serde_json::from_str(s).unwrap()
+}
+
+const FIXTURES: [(&str, Curve); 2] = [("bn128", Curve::Bn254), ("bls12381", Curve::Bls12_381)];
+
+#[test]
+fn proofs_and_keys_round_trip() {
+    for (dir, curve) in FIXTURES {
+        let proof_json = read_fixture(&format!("{}/proof.json", dir));
+        let vkey_json = read_fixture(&format!("{}/verification_key.json", dir));
+        let proof = encoding::decode_proof(Format::Snarkjs, &proof_json, None).unwrap();
+        let vkey = encoding::decode_vkey(Format::Snarkjs, &vkey_json, None).unwrap();
+        assert_eq!(proof.curve, curve);
+
+        for format in Format::ALL {
+            if format == Format::Evm && curve == Curve::Bls12_381 {
+                continue;
+            }
+            let written = encoding::encode_proof(format, &proof).unwrap();
+            assert_eq!(
+                encoding::decode_proof(format, &written, Some(curve)).unwrap(),
+                proof,
+                "{} proof as {}",
+                curve,
+                format
+            );
+            if format == Format::Evm {
+                continue;
+            }
+            let written = encoding::encode_vkey(format, &vkey).unwrap();
+            assert_eq!(
+                encoding::decode_vkey(format, &written, Some(curve)).unwrap(),
+                vkey,
+                "{} key as {}",
+                curve,
+                format
+            );
+        }
+
+        // snarkjs writes the same numbers.
+        let written = json(&encoding::encode_proof(Format::Snarkjs, &proof).unwrap());
+        assert_eq!(written, json(&proof_json));
+        let written = json(&encoding::encode_vkey(Format::Snarkjs, &vkey).unwrap());
+        let original = json(&vkey_json);
+        for field in [
+            "vk_alpha_1",
+            "vk_beta_2",
+            "vk_gamma_2",
+            "vk_delta_2",
+            "IC",
+            "nPublic",
+        ] {
+            assert_eq!(written[field], original[field], "{}", field);
+        }
+    }
+}
+
+#[test]
+fn reads_the_adapter_files() {
+    let proof = read_fixture("bls12381/proof_uncompressed.json");
+    let vkey = read_fixture("bls12381/vkey_uncompressed.json");
+    let decoded = encoding::decode_proof(Format::Uncompressed, &proof, None).unwrap();
+    assert_eq!(
+        json(&encoding::encode_proof(Format::Uncompressed, &decoded).unwrap()),
+        json(&proof)
+    );
+    assert_eq!(
+        decoded,
+        encoding::decode_proof(Format::Snarkjs, &read_fixture("bls12381/proof.json"), None)
+            .unwrap()
+    );
+    let decoded = encoding::decode_vkey(Format::Uncompressed, &vkey, None).unwrap();
+    assert_eq!(
+        json(&encoding::encode_vkey(Format::Uncompressed, &decoded).unwrap()),
+        json(&vkey)
+    );
+
+    // `vkey_hex.json` names the IC points `ic0`, `ic1`, ...
+    let hex = json(&encoding::encode_vkey(Format::Hex, &decoded).unwrap());
+    assert!(hex["ic0"].as_str().unwrap().starts_with("0x"));
+    assert!(hex["ic1"].is_string());
+    assert!(hex["ic2"].is_null());
+}
+
+#[test]
+fn converts_public_inputs() {
+    let public = read_fixture("bn128/public.json");
+    let inputs = encoding::decode_public_inputs(Format::Snarkjs, &public).unwrap();
+    let mut expected = [0u8; 32];
+    expected[31] = 33;
+    assert_eq!(inputs, expected);
+
+    for format in [
+        Format::Snarkjs,
+        Format::Hex,
+        Format::Bytes,
+        Format::Scale,
+        Format::Evm,
+    ] {
+        let written = encoding::encode_public_inputs(format, &inputs).unwrap();
+        assert_eq!(
+            encoding::decode_public_inputs(format, &written).unwrap(),
+            inputs,
+            "{}",
+            format
+        );
+    }
+    assert_eq!(
+        json(&encoding::encode_public_inputs(Format::Snarkjs, &inputs).unwrap()),
+        json(&public)
+    );
+    assert_eq!(
+        encoding::encode_public_inputs(Format::Scale, &inputs).unwrap(),
+        format!("0x80{}", hex::encode(expected))
+    );
+}
+
+#[test]
+fn reads_snarkjs_call_data() {
+    let proof =
+        encoding::decode_proof(Format::Snarkjs, &read_fixture("bn128/proof.json"), None).unwrap();
+    let inputs =
+        encoding::decode_public_inputs(Format::Snarkjs, &read_fixture("bn128/public.json"))
+            .unwrap();
+    let call = format!(
+        "{},{}",
+        encoding::encode_proof(Format::Evm, &proof).unwrap(),
+        encoding::encode_public_inputs(Format::Evm, &inputs).unwrap()
+    );
+    assert_eq!(
+        encoding::decode_proof(Format::Evm, &call, None).unwrap(),
+        proof
+    );
+    assert_eq!(
+        encoding::decode_public_inputs(Format::Evm, &call).unwrap(),
+        inputs
+    );
+}
+
+#[test]
+fn rejects_what_a_format_cannot_hold() {
+    let proof = encoding::decode_proof(Format::Snarkjs, &read_fixture("bls12381/proof.json"), None)
+        .unwrap();
+    assert!(matches!(
+        encoding::encode_proof(Format::Evm, &proof),
+        Err(EncodingError::Unsupported {
+            format: Format::Evm,
+            ..
+        })
+    ));
+    assert!(matches!(
+        encoding::encode_public_inputs(Format::Compressed, &[0; 32]),
+        Err(EncodingError::Unsupported { .. })
+    ));
+    assert!(matches!(
+        encoding::decode_proof(Format::Bytes, "0x00", None),
+        Err(EncodingError::Length { .. })
+    ));
+    assert!(matches!(
+        encoding::decode_proof(
+            Format::Snarkjs,
+            &read_fixture("bls12381/proof.json"),
+            Some(Curve::Bn254)
+        ),
+        Err(EncodingError::CurveMismatch { .. })
+    ));
+
+    // A point that is not on the curve.
+    let mut bytes = proof.bytes.clone();
+    bytes[10] ^= 1;
+    let written = format!("0x{}", hex::encode(&bytes));
+    assert!(matches!(
+        encoding::decode_proof(Format::Bytes, &written, None),
+        Err(EncodingError::Invalid(VerifierError::InvalidEncoding))
+    ));
+}
@@ -0,0 +1,102 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use bellman_verify::adapter::json::Curve;
+use bellman_verify::adapter::pool::{Job, VerificationPool};
+use bellman_verify::adapter::registry::VkeyRegistry;
+use verifier_traits::VerifierError;
+
+mod common;
+
+use common::submission;
+
+/// `bls/1` and `bn/1` with the fixture keys of both curves.
+fn registry() -> (tempfile::TempDir, Arc<VkeyRegistry>) {
+    let (dir, registry) = common::registry(&[
+        ("bls/1", "bls12381/verification_key.json"),
+        ("bn/1", "bn128/verification_key.json"),
+    ]);
+    (dir, Arc::new(registry))
+}
+
+fn job(registry: &VkeyRegistry, circuit: &str, curve: &str) -> Job {
+    let (proof, public_inputs) = submission(curve);
+    Job {
+        key: registry
+            .resolve(&circuit.parse().unwrap())
+            .unwrap()
+            .key
+            .clone(),
+        proof,
+        public_inputs,
+    }
+}
+
+#[test]
+fn returns_results_in_order() {
+    let (_dir, registry) = registry();
+    let pool = VerificationPool::new(registry.clone(), 4, NonZeroUsize::new(2).unwrap()).unwrap();
+
+    let mut wrong_inputs = job(&registry, "bn", "bn128");
+    wrong_inputs.public_inputs = vec![0; 32];
+    let jobs = vec![
+        job(&registry, "bls", "bls12381"),
+        job(&registry, "bn", "bn128"),
+        wrong_inputs,
+        job(&registry, "bls", "bls12381"),
+        job(&registry, "bn", "bn128"),
+    ];
+
+    let results = pool.verify_all(jobs);
+    assert_eq!(results.len(), 5);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    assert_eq!(results[2], Err(VerifierError::InvalidProof));
+    assert!(results[3].is_ok());
+    assert!(results[4].is_ok());
+    assert_eq!(pool.in_flight(), 0);
+}
+
+#[test]
+fn bounds_jobs_in_flight() {
+    let (_dir, registry) = registry();
+    let pool = VerificationPool::new(registry.clone(), 1, NonZeroUsize::new(1).unwrap()).unwrap();
+
+    let pending = pool.submit(job(&registry, "bls", "bls12381"));
+    assert!(pool.in_flight() <= 1);
+    // A full pool hands the job back.
+    match pool.try_submit(job(&registry, "bn", "bn128")) {
+        Err(rejected) => assert_eq!(rejected.key.curve(), Curve::Bn254),
+        // The first job was already done.
+        Ok(second) => assert!(second.wait().is_ok()),
+    }
+
+    // The slot is free once the result is in.
+    assert!(pending.wait().is_ok());
+    let pending = pool.try_submit(job(&registry, "bn", "bn128")).unwrap();
+    assert!(pending.wait().is_ok());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn verifies_from_async_code() {
+    let (_dir, registry) = registry();
+    let pool = Arc::new(
+        VerificationPool::new(registry.clone(), 2, NonZeroUsize::new(1).unwrap()).unwrap(),
+    );
+
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let pool = pool.clone();
+            let job = if i % 2 == 0 {
+                job(&registry, "bls", "bls12381")
+            } else {
+                job(&registry, "bn", "bn128")
+            };
+            tokio::spawn(async move { pool.verify(job).await })
+        })
+        .collect();
+    for task in tasks {
+        assert!(task.await.unwrap().is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+use std::fs;
+use std::sync::Arc;
+
+use bellman_verify::adapter::json::Curve;
+use bellman_verify::adapter::registry::{KeyId, RegistryError, ReloadSummary, VkeyRegistry};
+use verifier_traits::VerifierError;
+
+mod common;
+
+use common::{add_key, fixture, id, read_fixture, submission};
+
+/// `multiplication` at version 1 on bn128 and 2 on BLS12-381, and the
+/// BLS12-381 key again as `uncompressed`, from the adapter's file.
+fn registry() -> (tempfile::TempDir, VkeyRegistry) {
+    common::registry(&[
+        ("multiplication/1", "bn128/verification_key.json"),
+        ("multiplication/2", "bls12381/verification_key.json"),
+        ("uncompressed/1", "bls12381/vkey_uncompressed.json"),
+    ])
+}
+
+#[test]
+fn resolves_keys_by_id() {
+    let (_dir, registry) = registry();
+    let (bn_proof, bn_inputs) = submission("bn128");
+    let (bls_proof, bls_inputs) = submission("bls12381");
+
+    let latest = registry.resolve(&id("multiplication")).unwrap();
+    assert_eq!((latest.version, latest.curve), (2, Curve::Bls12_381));
+    assert_eq!(latest.public_inputs, 1);
+    registry
+        .verify(&id("multiplication"), &bls_proof, &bls_inputs)
+        .unwrap();
+    registry
+        .verify(&id("multiplication@1"), &bn_proof, &bn_inputs)
+        .unwrap();
+
+    // Both files of a key have the same fingerprint and share the prepared key.
+    let uncompressed = registry.resolve(&id("uncompressed@1")).unwrap();
+    assert_eq!(uncompressed.fingerprint, latest.fingerprint);
+    assert!(Arc::ptr_eq(&uncompressed.key, &latest.key));
+    let by_fingerprint = KeyId::Fingerprint(latest.fingerprint);
+    assert_eq!(
+        by_fingerprint.to_string().parse::<KeyId>().unwrap(),
+        by_fingerprint
+    );
+    registry
+        .verify(&by_fingerprint, &bls_proof, &bls_inputs)
+        .unwrap();
+
+    assert!(matches!(
+        registry.verify(&id("multiplication@1"), &bn_proof, &bls_inputs[..0]),
+        Err(RegistryError::Verification(
+            VerifierError::PublicInputCount {
+                expected: 1,
+                actual: 0
+            }
+        ))
+    ));
+    assert!(matches!(
+        registry.verify(&id("multiplication@1"), &bls_proof, &bls_inputs),
+        Err(RegistryError::Verification(VerifierError::InvalidEncoding))
+    ));
+    assert!(matches!(
+        registry.resolve(&id("multiplication@3")),
+        Err(RegistryError::UnknownKey(_))
+    ));
+    assert!(matches!(
+        "0x12".parse::<KeyId>(),
+        Err(RegistryError::InvalidKeyId(_))
+    ));
+    assert!(matches!(
+        "a@b".parse::<KeyId>(),
+        Err(RegistryError::InvalidKeyId(_))
+    ));
+}
+
+#[test]
+fn rotates_and_retires_versions() {
+    let (dir, registry) = registry();
+    let circuit = dir.path().join("multiplication");
+
+    registry.retire("multiplication", 2).unwrap();
+    assert_eq!(registry.resolve(&id("multiplication")).unwrap().version, 1);
+    assert!(matches!(
+        registry.resolve(&id("multiplication@2")),
+        Err(RegistryError::Retired(_))
+    ));
+
+    // Retirement survives reloads, new versions become the latest.
+    add_key(&circuit, "3", "bls12381/vkey_uncompressed.json");
+    fs::remove_dir_all(circuit.join("1")).unwrap();
+    assert_eq!(
+        registry.reload().unwrap(),
+        ReloadSummary {
+            added: 1,
+            removed: 1,
+            ..Default::default()
+        }
+    );
+    assert!(registry
+        .entries()
+        .iter()
+        .any(|e| e.version == 2 && e.retired));
+    assert_eq!(registry.resolve(&id("multiplication")).unwrap().version, 3);
+
+    registry.retire("multiplication", 3).unwrap();
+    assert!(matches!(
+        registry.resolve(&id("multiplication")),
+        Err(RegistryError::Retired(_))
+    ));
+}
+
+#[test]
+fn keeps_loaded_keys_when_a_reload_fails() {
+    let (dir, registry) = registry();
+    let version = dir.path().join("multiplication/3");
+    fs::create_dir_all(&version).unwrap();
+
+    fs::write(version.join("verification_key.json"), "{}").unwrap();
+    assert!(matches!(registry.reload(), Err(RegistryError::Json { .. })));
+    assert_eq!(registry.resolve(&id("multiplication")).unwrap().version, 2);
+
+    // A point that is not on the curve.
+    let mut key: serde_json::Value =
+        serde_json::from_str(&read_fixture("bn128/verification_key.json")).unwrap();
+    key["vk_alpha_1"][1] = "1".into();
+    fs::write(version.join("verification_key.json"), key.to_string()).unwrap();
+    assert!(matches!(
+        registry.reload(),
+        Err(RegistryError::InvalidKey {
+            error: VerifierError::InvalidEncoding,
+            ..
+        })
+    ));
+
+    fs::copy(
+        fixture("bls12381/vkey_uncompressed.json"),
+        version.join("vkey_uncompressed.json"),
+    )
+    .unwrap();
+    fs::copy(
+        fixture("bn128/verification_key.json"),
+        version.join("verification_key.json"),
+    )
+    .unwrap();
+    assert!(matches!(
+        registry.reload(),
+        Err(RegistryError::KeyMismatch(_))
+    ));
+    assert_eq!(registry.entries().len(), 3);
+}
+
+#[test]
+fn registers_new_versions() {
+    let (dir, registry) = registry();
+    let key = read_fixture("bls12381/vkey_uncompressed.json");
+
+    let entry = registry.register("multiplication", &key).unwrap();
+    assert_eq!(entry.id(), id("multiplication@3"));
+    assert!(dir
+        .path()
+        .join("multiplication/3/vkey_uncompressed.json")
+        .is_file());
+    assert_eq!(registry.resolve(&id("multiplication")).unwrap().version, 3);
+    // The key was prepared before, for version 2.
+    assert!(Arc::ptr_eq(
+        &entry.key,
+        &registry.resolve(&id("multiplication@2")).unwrap().key
+    ));
+
+    let entry = registry.register("other", &key).unwrap();
+    assert_eq!(entry.id(), id("other@1"));
+    // Written like any other version.
+    assert_eq!(registry.reload().unwrap(), ReloadSummary::default());
+
+    assert!(matches!(
+        registry.register("0xother", &key),
+        Err(RegistryError::InvalidKeyId(_))
+    ));
+    assert!(matches!(
+        registry.register("broken", "{}"),
+        Err(RegistryError::Json { .. })
+    ));
+    assert!(!dir.path().join("broken").exists());
+}
+
+#[test]
+fn registers_after_the_newest_loaded_version() {
+    let (dir, registry) = registry();
+    let circuit = dir.path().join("multiplication");
+    let key = read_fixture("bls12381/vkey_uncompressed.json");
+
+    // A gap left by a removed version is not filled.
+    fs::remove_dir_all(circuit.join("2")).unwrap();
+    let entry = registry.register("multiplication", &key).unwrap();
+    assert_eq!(entry.id(), id("multiplication@3"));
+
+    // A version written by someone else since the load is not overwritten.
+    add_key(&circuit, "4", "bn128/verification_key.json");
+    assert!(matches!(
+        registry.register("multiplication", &key),
+        Err(RegistryError::VersionExists(path)) if path == circuit.join("4")
+    ));
+    assert!(!circuit.join("4/vkey_uncompressed.json").exists());
+
+    registry.reload().unwrap();
+    let entry = registry.register("multiplication", &key).unwrap();
+    assert_eq!(entry.id(), id("multiplication@5"));
+}
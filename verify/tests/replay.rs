@@ -0,0 +1,88 @@
+use bellman_verify::adapter::registry::{RegistryError, VkeyRegistry};
+use bellman_verify::adapter::replay::{MemoryGuard, ReplayError, ReplayKey, ReplayProtection};
+use verifier_traits::VerifierError;
+
+mod common;
+
+use common::{id, submission};
+
+/// Versions 1 and 2 of `multiplication`, and `other`, all with the
+/// BLS12-381 fixture key.
+fn registry() -> (tempfile::TempDir, VkeyRegistry) {
+    let key = "bls12381/verification_key.json";
+    common::registry(&[
+        ("multiplication/1", key),
+        ("multiplication/2", key),
+        ("other/1", key),
+    ])
+}
+
+#[test]
+fn accepts_proofs_once() {
+    let (_dir, registry) = registry();
+    let (proof, inputs) = submission("bls12381");
+    let replay = ReplayProtection::new(MemoryGuard::new(), ReplayKey::ProofHash);
+
+    // Rejected proofs are not recorded.
+    assert!(matches!(
+        replay.verify(&registry, &id("multiplication"), &proof, &[0; 32]),
+        Err(ReplayError::Registry(RegistryError::Verification(
+            VerifierError::InvalidProof
+        )))
+    ));
+
+    replay
+        .verify(&registry, &id("multiplication"), &proof, &inputs)
+        .unwrap();
+    assert!(matches!(
+        replay.verify(&registry, &id("multiplication"), &proof, &inputs),
+        Err(ReplayError::Replayed)
+    ));
+    // Other circuits keep their own records.
+    replay
+        .verify(&registry, &id("other"), &proof, &inputs)
+        .unwrap();
+}
+
+#[test]
+fn nullifiers_stay_spent_across_versions() {
+    let (_dir, registry) = registry();
+    let (proof, inputs) = submission("bls12381");
+    let replay = ReplayProtection::new(MemoryGuard::new(), ReplayKey::PublicInput(0));
+
+    replay
+        .verify(&registry, &id("multiplication@1"), &proof, &inputs)
+        .unwrap();
+    assert!(matches!(
+        replay.verify(&registry, &id("multiplication@2"), &proof, &inputs),
+        Err(ReplayError::Replayed)
+    ));
+
+    let replay = ReplayProtection::new(MemoryGuard::new(), ReplayKey::PublicInput(1));
+    assert!(matches!(
+        replay.verify(&registry, &id("multiplication"), &proof, &inputs),
+        Err(ReplayError::MissingInput { index: 1 })
+    ));
+}
+
+#[cfg(feature = "sled")]
+#[test]
+fn sled_guard_survives_restarts() {
+    use bellman_verify::adapter::replay::SledGuard;
+
+    let (dir, registry) = registry();
+    let (proof, inputs) = submission("bls12381");
+    let db = dir.path().join("replay.db");
+
+    let replay = ReplayProtection::new(SledGuard::open(&db).unwrap(), ReplayKey::ProofHash);
+    replay
+        .verify(&registry, &id("multiplication"), &proof, &inputs)
+        .unwrap();
+    drop(replay);
+
+    let replay = ReplayProtection::new(SledGuard::open(&db).unwrap(), ReplayKey::ProofHash);
+    assert!(matches!(
+        replay.verify(&registry, &id("multiplication"), &proof, &inputs),
+        Err(ReplayError::Replayed)
+    ));
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "server")]
+
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use bellman_verify::adapter::VerificationPool;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+mod common;
+
+use common::read_fixture;
+
+fn fixture_json(name: &str) -> Value {
+    serde_json::from_str(&read_fixture(name)).unwrap()
+}
+
+/// Serves a registry with the BLS12-381 key as `multiplication/1` on a free
+/// port of localhost.
+async fn start() -> (tempfile::TempDir, String) {
+    let (dir, registry) =
+        common::registry(&[("multiplication/1", "bls12381/verification_key.json")]);
+    let registry = Arc::new(registry);
+    let pool = Arc::new(VerificationPool::new(registry, 2, NonZeroUsize::new(8).unwrap()).unwrap());
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(bellman_verify::server::serve(
+        listener,
+        pool,
+        std::future::pending(),
+    ));
+    (dir, url)
+}
+
+async fn post(url: &str, body: &Value) -> (StatusCode, Value) {
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn verifies_proofs() {
+    let (_dir, url) = start().await;
+    let verify = format!("{}/verify", url);
+    let request = json!({
+        "key": "multiplication",
+        "proof": fixture_json("bls12381/proof.json"),
+        "public": fixture_json("bls12381/public.json"),
+    });
+
+    let (status, response) = post(&verify, &request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["valid"], true);
+    assert_eq!(response["key"], "multiplication@1");
+
+    let mut wrong = request.clone();
+    wrong["public"] = json!(["34"]);
+    let (status, response) = post(&verify, &wrong).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["valid"], false);
+    assert_eq!(response["error"], "the proof is not valid");
+
+    let mut wrong_curve = request.clone();
+    wrong_curve["proof"] = fixture_json("bn128/proof.json");
+    let (status, response) = post(&verify, &wrong_curve).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["error"], "the proof is on bn254, the key on bls12-381");
+
+    let mut unknown = request.clone();
+    unknown["key"] = json!("other");
+    let (status, response) = post(&verify, &unknown).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(response["error"], "unknown key `other`");
+
+    let (status, _) = post(&verify, &json!({ "key": "multiplication" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let metrics = reqwest::get(format!("{}/metrics", url))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("groth16_verifications_total{result=\"valid\"} 1\n"));
+    assert!(metrics.contains("groth16_verifications_total{result=\"invalid\"} 1\n"));
+    assert!(metrics.contains("groth16_verifications_total{result=\"error\"} 3\n"));
+}
+
+#[tokio::test]
+async fn registers_keys() {
+    let (dir, url) = start().await;
+    let keys = format!("{}/keys", url);
+
+    let (status, entry) = post(
+        &keys,
+        &json!({
+            "circuit": "multiplication",
+            "key": fixture_json("bn128/verification_key.json"),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(entry["id"], "multiplication@2");
+    assert_eq!(entry["curve"], "bn254");
+    assert!(dir
+        .path()
+        .join("multiplication/2/verification_key.json")
+        .is_file());
+
+    // The new version is the latest.
+    let (status, response) = post(
+        &format!("{}/verify", url),
+        &json!({
+            "key": "multiplication",
+            "proof": fixture_json("bn128/proof.json"),
+            "public": fixture_json("bn128/public.json"),
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["valid"], true);
+    assert_eq!(response["key"], "multiplication@2");
+
+    let (status, _) = post(
+        &keys,
+        &json!({ "circuit": "0xbad", "key": fixture_json("bn128/verification_key.json") }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = post(
+        &keys,
+        &json!({ "circuit": "broken", "key": { "vk_alpha_1": [] } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(!dir.path().join("broken").exists());
+
+    let listed: Value = reqwest::get(&keys).await.unwrap().json().await.unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+    let health: Value = reqwest::get(format!("{}/health", url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(health, json!({ "status": "ok", "keys": 2 }));
+}